@@ -0,0 +1,5 @@
+DROP TRIGGER IF EXISTS record_punishment_event ON punishments;
+DROP FUNCTION IF EXISTS record_punishment_event();
+
+DROP INDEX IF EXISTS idx_punishment_events_player_sequence;
+DROP TABLE IF EXISTS punishment_events;
@@ -0,0 +1,32 @@
+-- Outbox of punishment changes, used to resume live streams after a reconnect
+CREATE TABLE punishment_events (
+    sequence      BIGSERIAL   PRIMARY KEY,          -- Monotonic event sequence number
+    punishment_id UUID        NOT NULL REFERENCES punishments(id) ON DELETE CASCADE,
+    player_uuid   UUID        NOT NULL REFERENCES players(uuid),
+    created_at    TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX idx_punishment_events_player_sequence ON punishment_events(player_uuid, sequence);
+
+-- Records an event for every write to punishments and notifies the backend relay.
+-- The advisory lock serialises writers so sequence numbers become visible in commit order,
+-- otherwise a reader resuming from N could miss a lower sequence committed after N.
+CREATE OR REPLACE FUNCTION record_punishment_event()
+RETURNS TRIGGER AS $$
+DECLARE
+    event_sequence BIGINT;
+BEGIN
+    PERFORM pg_advisory_xact_lock(hashtext('punishment_events'));
+
+    INSERT INTO punishment_events (punishment_id, player_uuid)
+    VALUES (NEW.id, NEW.player_uuid)
+    RETURNING sequence INTO event_sequence;
+
+    PERFORM pg_notify('punishment_events', event_sequence::TEXT);
+    RETURN NEW;
+END;
+$$ LANGUAGE plpgsql;
+
+CREATE TRIGGER record_punishment_event
+    AFTER INSERT OR UPDATE ON punishments
+    FOR EACH ROW EXECUTE FUNCTION record_punishment_event();
@@ -0,0 +1,11 @@
+ALTER TABLE punishments
+    DROP CONSTRAINT IF EXISTS server_group_matches_scope,
+    DROP CONSTRAINT IF EXISTS valid_scope,
+    DROP COLUMN IF EXISTS server_group,
+    DROP COLUMN IF EXISTS scope;
+
+ALTER TABLE punishment_templates
+    DROP CONSTRAINT IF EXISTS server_group_matches_scope,
+    DROP CONSTRAINT IF EXISTS valid_scope,
+    DROP COLUMN IF EXISTS server_group,
+    DROP COLUMN IF EXISTS scope;
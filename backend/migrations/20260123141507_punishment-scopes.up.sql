@@ -0,0 +1,12 @@
+-- Punishments can apply to the whole network, only the proxy or a named group of servers
+ALTER TABLE punishment_templates
+    ADD COLUMN scope        VARCHAR(20) NOT NULL DEFAULT 'global', -- 'global', 'proxy', 'server_group'
+    ADD COLUMN server_group VARCHAR(50),                           -- e.g. "skyblock"; only for the 'server_group' scope
+    ADD CONSTRAINT valid_scope CHECK (scope IN ('global', 'proxy', 'server_group')),
+    ADD CONSTRAINT server_group_matches_scope CHECK ((scope = 'server_group') = (server_group IS NOT NULL));
+
+ALTER TABLE punishments
+    ADD COLUMN scope        VARCHAR(20) NOT NULL DEFAULT 'global', -- Copied from the template unless overridden
+    ADD COLUMN server_group VARCHAR(50),
+    ADD CONSTRAINT valid_scope CHECK (scope IN ('global', 'proxy', 'server_group')),
+    ADD CONSTRAINT server_group_matches_scope CHECK ((scope = 'server_group') = (server_group IS NOT NULL));
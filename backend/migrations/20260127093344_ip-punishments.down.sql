@@ -0,0 +1,8 @@
+DROP INDEX IF EXISTS idx_punishments_ip_range;
+
+ALTER TABLE punishments
+    DROP CONSTRAINT IF EXISTS ip_range_punishment_type,
+    DROP COLUMN IF EXISTS ip_range;
+
+DROP INDEX IF EXISTS idx_player_addresses_ip_address;
+DROP TABLE IF EXISTS player_addresses;
@@ -0,0 +1,21 @@
+-- Addresses players connected from, reported by the proxy on every login check
+CREATE TABLE player_addresses (
+    player_uuid UUID        NOT NULL,               -- No foreign key, players connect before they have a players row
+    ip_address  INET        NOT NULL,
+    first_seen  TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    last_seen   TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    login_count INTEGER     NOT NULL DEFAULT 1,
+
+    PRIMARY KEY (player_uuid, ip_address)
+);
+
+CREATE INDEX idx_player_addresses_ip_address ON player_addresses(ip_address);
+
+-- IP punishments apply to every connection from a single address (/32, /128) or a CIDR range
+ALTER TABLE punishments
+    ADD COLUMN ip_range CIDR,                       -- NULL = account punishment
+    ADD CONSTRAINT ip_range_punishment_type CHECK (
+        ip_range IS NULL OR punishment_type IN ('mute', 'temp_ban', 'perm_ban')
+    );
+
+CREATE INDEX idx_punishments_ip_range ON punishments USING gist (ip_range inet_ops) WHERE ip_range IS NOT NULL;
@@ -0,0 +1,2 @@
+DROP INDEX IF EXISTS idx_player_addresses_ip_last_seen;
+CREATE INDEX idx_player_addresses_ip_address ON player_addresses(ip_address);
@@ -0,0 +1,3 @@
+-- The alt graph joins player_addresses on the address within a time window
+DROP INDEX IF EXISTS idx_player_addresses_ip_address;
+CREATE INDEX idx_player_addresses_ip_last_seen ON player_addresses(ip_address, last_seen);
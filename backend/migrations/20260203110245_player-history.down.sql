@@ -0,0 +1,7 @@
+DROP INDEX IF EXISTS idx_player_sessions_open_stream;
+DROP INDEX IF EXISTS idx_player_sessions_open_player;
+DROP INDEX IF EXISTS idx_player_sessions_player_uuid;
+DROP TABLE IF EXISTS player_sessions;
+
+DROP INDEX IF EXISTS idx_player_name_history_username;
+DROP TABLE IF EXISTS player_name_history;
@@ -0,0 +1,30 @@
+-- Every username a player has been seen with, kept current from GetPlayerLogin
+CREATE TABLE player_name_history (
+    player_uuid UUID        NOT NULL REFERENCES players(uuid) ON DELETE CASCADE,
+    username    VARCHAR(16) NOT NULL,
+    first_seen  TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    last_seen   TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+
+    PRIMARY KEY (player_uuid, username)
+);
+
+CREATE INDEX idx_player_name_history_username ON player_name_history(username);
+
+INSERT INTO player_name_history (player_uuid, username, first_seen, last_seen)
+SELECT uuid, username, created_at, updated_at FROM players
+ON CONFLICT DO NOTHING;
+
+-- Network sessions, opened and closed by the online flag the proxy sends on its live stream
+CREATE TABLE player_sessions (
+    id          BIGSERIAL   PRIMARY KEY,
+    player_uuid UUID        NOT NULL REFERENCES players(uuid) ON DELETE CASCADE,
+    stream_id   UUID        NOT NULL,               -- Live stream that reported the session, closes it when it ends
+    started_at  TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    ended_at    TIMESTAMPTZ,                        -- NULL = still online
+
+    CONSTRAINT ended_after_started CHECK (ended_at IS NULL OR ended_at >= started_at)
+);
+
+CREATE INDEX idx_player_sessions_player_uuid ON player_sessions(player_uuid, started_at);
+CREATE INDEX idx_player_sessions_open_player ON player_sessions(player_uuid) WHERE ended_at IS NULL;
+CREATE INDEX idx_player_sessions_open_stream ON player_sessions(stream_id) WHERE ended_at IS NULL;
@@ -0,0 +1,5 @@
+DROP INDEX IF EXISTS idx_player_name_history_username;
+CREATE INDEX idx_player_name_history_username ON player_name_history(username);
+
+DROP INDEX IF EXISTS idx_players_username;
+CREATE INDEX idx_players_username ON players(username);
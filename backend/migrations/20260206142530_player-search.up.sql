@@ -0,0 +1,6 @@
+-- Username searches are case-insensitive prefix matches, which a plain index cannot serve
+DROP INDEX idx_players_username;
+CREATE INDEX idx_players_username ON players(LOWER(username) text_pattern_ops);
+
+DROP INDEX idx_player_name_history_username;
+CREATE INDEX idx_player_name_history_username ON player_name_history(LOWER(username) text_pattern_ops);
@@ -0,0 +1,10 @@
+DROP INDEX IF EXISTS idx_audit_events_target;
+DROP INDEX IF EXISTS idx_audit_events_actor_uuid;
+DROP TABLE IF EXISTS audit_events;
+
+DROP INDEX IF EXISTS idx_punishment_categories_position;
+ALTER TABLE punishment_categories DROP COLUMN IF EXISTS position;
+
+DROP INDEX IF EXISTS idx_players_role_id;
+ALTER TABLE players DROP COLUMN IF EXISTS role_id;
+DROP TABLE IF EXISTS staff_roles;
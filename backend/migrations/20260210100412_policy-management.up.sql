@@ -0,0 +1,49 @@
+-- Staff roles grant permissions such as 'policy.manage', '*' grants every permission
+CREATE TABLE staff_roles (
+    id          SERIAL      PRIMARY KEY,
+    name        VARCHAR(50) NOT NULL UNIQUE,
+    permissions TEXT[]      NOT NULL DEFAULT '{}',
+    created_at  TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at  TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+INSERT INTO staff_roles (name, permissions) VALUES
+    ('Administrator', '{*}'),
+    ('Moderator',     '{}');
+
+ALTER TABLE players ADD COLUMN role_id INTEGER REFERENCES staff_roles(id);
+
+CREATE INDEX idx_players_role_id ON players(role_id) WHERE role_id IS NOT NULL;
+
+-- The seeded account administers the network, every other staff member starts as a moderator
+UPDATE players
+SET role_id = (SELECT id FROM staff_roles WHERE name = 'Administrator')
+WHERE uuid = 'e0251b43-351c-4318-a742-aa350627df60' AND staff = true;
+
+UPDATE players
+SET role_id = (SELECT id FROM staff_roles WHERE name = 'Moderator')
+WHERE staff = true AND role_id IS NULL;
+
+-- Order of the categories in the plugin and web panel menus
+ALTER TABLE punishment_categories ADD COLUMN position INTEGER NOT NULL DEFAULT 0;
+
+UPDATE punishment_categories pc
+SET position = ordered.position
+FROM (SELECT id, ROW_NUMBER() OVER (ORDER BY id) AS position FROM punishment_categories) ordered
+WHERE pc.id = ordered.id;
+
+CREATE INDEX idx_punishment_categories_position ON punishment_categories(position);
+
+-- Record of staff changes, written in the same transaction as the change itself
+CREATE TABLE audit_events (
+    id          BIGSERIAL   PRIMARY KEY,
+    actor_uuid  UUID        REFERENCES players(uuid),   -- NULL = the backend itself
+    action      VARCHAR(50) NOT NULL,                   -- e.g. 'category.create', 'ladder.replace'
+    target_type VARCHAR(30) NOT NULL,                   -- e.g. 'category'
+    target_id   TEXT,
+    details     JSONB       NOT NULL DEFAULT '{}',      -- Values before and after the change
+    created_at  TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX idx_audit_events_actor_uuid ON audit_events(actor_uuid, created_at);
+CREATE INDEX idx_audit_events_target     ON audit_events(target_type, target_id, created_at);
@@ -0,0 +1,21 @@
+DROP TRIGGER IF EXISTS protect_published_policy_templates ON punishment_templates;
+DROP TRIGGER IF EXISTS protect_published_policy_versions ON policy_versions;
+DROP FUNCTION IF EXISTS protect_published_policy();
+
+DROP INDEX IF EXISTS idx_punishments_template_id;
+ALTER TABLE punishments DROP COLUMN IF EXISTS template_id;
+
+-- Only the active ladder of every category survives
+DELETE FROM punishment_templates
+WHERE policy_version_id IS DISTINCT FROM active_policy_version_id(category_id);
+
+DROP FUNCTION IF EXISTS active_policy_version_id(INTEGER);
+
+ALTER TABLE punishment_templates
+    DROP CONSTRAINT IF EXISTS punishment_templates_version_offense_key,
+    ADD CONSTRAINT punishment_templates_category_id_offense_number_key UNIQUE (category_id, offense_number),
+    DROP COLUMN IF EXISTS policy_version_id;
+
+DROP INDEX IF EXISTS idx_policy_versions_active;
+DROP INDEX IF EXISTS idx_policy_versions_one_draft;
+DROP TABLE IF EXISTS policy_versions;
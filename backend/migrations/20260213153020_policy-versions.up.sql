@@ -0,0 +1,93 @@
+-- Escalation ladders are versioned per category. Drafts are edited freely, published versions are
+-- immutable and take over from the previous version at activates_at.
+CREATE TABLE policy_versions (
+    id             SERIAL      PRIMARY KEY,
+    category_id    INTEGER     NOT NULL REFERENCES punishment_categories(id) ON DELETE CASCADE,
+    version_number INTEGER     NOT NULL,
+    status         VARCHAR(20) NOT NULL DEFAULT 'draft', -- 'draft', 'published'
+    created_by     UUID        REFERENCES players(uuid),  -- NULL for versions created by migrations
+    created_at     TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    published_by   UUID        REFERENCES players(uuid),
+    published_at   TIMESTAMPTZ,
+    activates_at   TIMESTAMPTZ,                          -- Applies to punishments issued from then on
+
+    CONSTRAINT valid_policy_status CHECK (status IN ('draft', 'published')),
+    CONSTRAINT published_fields_consistency CHECK (
+        (status = 'published') = (published_at IS NOT NULL AND activates_at IS NOT NULL)
+    ),
+    CONSTRAINT positive_version_number CHECK (version_number > 0),
+    UNIQUE(category_id, version_number)
+);
+
+CREATE UNIQUE INDEX idx_policy_versions_one_draft ON policy_versions(category_id) WHERE status = 'draft';
+CREATE INDEX idx_policy_versions_active ON policy_versions(category_id, activates_at) WHERE status = 'published';
+
+-- The current ladders become version 1, active since their category was created
+INSERT INTO policy_versions (category_id, version_number, status, created_at, published_at, activates_at)
+SELECT id, 1, 'published', created_at, created_at, created_at FROM punishment_categories;
+
+ALTER TABLE punishment_templates ADD COLUMN policy_version_id INTEGER REFERENCES policy_versions(id) ON DELETE CASCADE;
+
+UPDATE punishment_templates pt
+SET policy_version_id = pv.id
+FROM policy_versions pv
+WHERE pv.category_id = pt.category_id AND pv.version_number = 1;
+
+ALTER TABLE punishment_templates
+    ALTER COLUMN policy_version_id SET NOT NULL,
+    DROP CONSTRAINT punishment_templates_category_id_offense_number_key,
+    ADD CONSTRAINT punishment_templates_version_offense_key UNIQUE (policy_version_id, offense_number);
+
+-- The template a punishment was issued from, NULL for punishments issued before versioning
+-- whose step no longer exists
+ALTER TABLE punishments ADD COLUMN template_id INTEGER REFERENCES punishment_templates(id);
+
+CREATE INDEX idx_punishments_template_id ON punishments(template_id) WHERE template_id IS NOT NULL;
+
+-- Backfilling is not a change players need to hear about
+ALTER TABLE punishments DISABLE TRIGGER record_punishment_event;
+
+UPDATE punishments p
+SET template_id = pt.id
+FROM punishment_templates pt
+WHERE pt.category_id = p.category_id AND pt.offense_number = p.offense_number;
+
+ALTER TABLE punishments ENABLE TRIGGER record_punishment_event;
+
+-- Latest published version of the category that has been activated
+CREATE OR REPLACE FUNCTION active_policy_version_id(category INTEGER)
+RETURNS INTEGER AS $$
+    SELECT id FROM policy_versions
+    WHERE category_id = category AND status = 'published' AND activates_at <= NOW()
+    ORDER BY activates_at DESC, version_number DESC
+    LIMIT 1
+$$ LANGUAGE sql STABLE;
+
+-- Published versions are history, punishments point at their templates
+CREATE OR REPLACE FUNCTION protect_published_policy()
+RETURNS TRIGGER AS $$
+BEGIN
+    IF TG_TABLE_NAME = 'policy_versions' THEN
+        IF OLD.status = 'published' THEN
+            RAISE EXCEPTION 'Published policy version % cannot be changed', OLD.id;
+        END IF;
+    ELSE
+        IF TG_OP <> 'INSERT' AND EXISTS (SELECT 1 FROM policy_versions WHERE id = OLD.policy_version_id AND status = 'published') THEN
+            RAISE EXCEPTION 'Templates of published policy version % cannot be changed', OLD.policy_version_id;
+        END IF;
+        IF TG_OP <> 'DELETE' AND EXISTS (SELECT 1 FROM policy_versions WHERE id = NEW.policy_version_id AND status = 'published') THEN
+            RAISE EXCEPTION 'Templates of published policy version % cannot be changed', NEW.policy_version_id;
+        END IF;
+    END IF;
+
+    RETURN COALESCE(NEW, OLD);
+END;
+$$ LANGUAGE plpgsql;
+
+CREATE TRIGGER protect_published_policy_versions
+    BEFORE UPDATE OR DELETE ON policy_versions
+    FOR EACH ROW EXECUTE FUNCTION protect_published_policy();
+
+CREATE TRIGGER protect_published_policy_templates
+    BEFORE INSERT OR UPDATE OR DELETE ON punishment_templates
+    FOR EACH ROW EXECUTE FUNCTION protect_published_policy();
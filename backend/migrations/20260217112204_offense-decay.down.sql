@@ -0,0 +1,5 @@
+ALTER TABLE punishment_categories
+    DROP CONSTRAINT IF EXISTS decay_days_matches_mode,
+    DROP CONSTRAINT IF EXISTS valid_decay_mode,
+    DROP COLUMN IF EXISTS decay_days,
+    DROP COLUMN IF EXISTS decay_mode;
@@ -0,0 +1,12 @@
+-- How old offenses stop counting towards the next escalation step of a category:
+--   'none'      every non-revoked offense counts
+--   'expire'    offenses older than decay_days are ignored
+--   'step_down' every decay_days without an offense moves the player one step back down the ladder
+ALTER TABLE punishment_categories
+    ADD COLUMN decay_mode VARCHAR(20) NOT NULL DEFAULT 'none',
+    ADD COLUMN decay_days INTEGER,
+    ADD CONSTRAINT valid_decay_mode CHECK (decay_mode IN ('none', 'expire', 'step_down')),
+    ADD CONSTRAINT decay_days_matches_mode CHECK (
+        (decay_mode = 'none' AND decay_days IS NULL) OR
+        (decay_mode <> 'none' AND decay_days > 0)
+    );
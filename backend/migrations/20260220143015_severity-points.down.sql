@@ -0,0 +1,11 @@
+ALTER TABLE punishments
+    DROP CONSTRAINT IF EXISTS non_negative_points,
+    DROP COLUMN IF EXISTS threshold_points,
+    DROP COLUMN IF EXISTS points;
+
+ALTER TABLE punishment_templates
+    DROP CONSTRAINT IF EXISTS non_negative_points,
+    DROP COLUMN IF EXISTS points;
+
+DROP TABLE IF EXISTS severity_thresholds;
+DROP TABLE IF EXISTS severity_settings;
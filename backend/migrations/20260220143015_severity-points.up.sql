@@ -0,0 +1,42 @@
+-- Severity points, an optional network wide alternative to escalating per category. Every template
+-- awards points, a player's points fade out over decay_days and reaching a threshold issues an
+-- automatic ban on top of the punishment that crossed it.
+CREATE TABLE severity_settings (
+    id         BOOLEAN     PRIMARY KEY DEFAULT TRUE,  -- Single row
+    enabled    BOOLEAN     NOT NULL DEFAULT FALSE,
+    decay_days INTEGER,                               -- Points shrink linearly to zero over this many days, NULL = never
+    updated_by UUID        REFERENCES players(uuid),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+
+    CONSTRAINT single_row CHECK (id),
+    CONSTRAINT positive_decay_days CHECK (decay_days IS NULL OR decay_days > 0)
+);
+
+INSERT INTO severity_settings DEFAULT VALUES;
+
+CREATE TABLE severity_thresholds (
+    id               SERIAL      PRIMARY KEY,
+    points           INTEGER     NOT NULL UNIQUE,     -- Reached when the total goes from below to at least this
+    punishment_type  VARCHAR(20) NOT NULL,            -- 'temp_ban' or 'perm_ban'
+    duration_minutes INTEGER,
+    reason           TEXT        NOT NULL,
+    created_at       TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+
+    CONSTRAINT positive_threshold_points CHECK (points > 0),
+    CONSTRAINT valid_threshold_type CHECK (punishment_type IN ('temp_ban', 'perm_ban')),
+    CONSTRAINT threshold_duration_matches_type CHECK (
+        (punishment_type = 'temp_ban' AND duration_minutes > 0) OR
+        (punishment_type = 'perm_ban' AND duration_minutes IS NULL)
+    )
+);
+
+ALTER TABLE punishment_templates
+    ADD COLUMN points INTEGER NOT NULL DEFAULT 0,
+    ADD CONSTRAINT non_negative_points CHECK (points >= 0);
+
+-- Points are copied from the template at issue time. Automatic bans award none and remember the
+-- threshold that issued them by its points, thresholds are replaced as a whole when reconfigured.
+ALTER TABLE punishments
+    ADD COLUMN points INTEGER NOT NULL DEFAULT 0,
+    ADD COLUMN threshold_points INTEGER,
+    ADD CONSTRAINT non_negative_points CHECK (points >= 0);
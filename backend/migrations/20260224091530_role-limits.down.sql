@@ -0,0 +1,16 @@
+DROP INDEX IF EXISTS idx_punishments_staff_uuid_issued_at;
+
+DROP TABLE IF EXISTS punishment_approvals;
+
+UPDATE players
+SET role_id = (SELECT id FROM staff_roles WHERE name = 'Moderator')
+WHERE role_id = (SELECT id FROM staff_roles WHERE name = 'Trial Moderator');
+
+DELETE FROM staff_roles WHERE name = 'Trial Moderator';
+
+ALTER TABLE staff_roles
+    DROP CONSTRAINT IF EXISTS positive_daily_limit,
+    DROP CONSTRAINT IF EXISTS positive_max_duration,
+    DROP COLUMN IF EXISTS daily_limit,
+    DROP COLUMN IF EXISTS max_duration_minutes,
+    DROP COLUMN IF EXISTS allowed_types;
@@ -0,0 +1,50 @@
+-- Limits on what the members of a role can issue, NULL = unlimited
+ALTER TABLE staff_roles
+    ADD COLUMN allowed_types        TEXT[],             -- Punishment types the role may issue
+    ADD COLUMN max_duration_minutes INTEGER,            -- Longest mute or temporary ban, permanent bans exceed it
+    ADD COLUMN daily_limit          INTEGER,            -- Punishments a member may issue per 24 hours
+    ADD CONSTRAINT positive_max_duration CHECK (max_duration_minutes IS NULL OR max_duration_minutes > 0),
+    ADD CONSTRAINT positive_daily_limit CHECK (daily_limit IS NULL OR daily_limit > 0);
+
+INSERT INTO staff_roles (name, allowed_types, max_duration_minutes) VALUES
+    ('Trial Moderator', '{warn,mute,kick,temp_ban}', 1440);
+
+-- Punishments waiting for a staff member with the punishment.approve permission, queued instead of
+-- failing when the issuing staff member hits a limit of their role
+CREATE TABLE punishment_approvals (
+    id               UUID        PRIMARY KEY DEFAULT uuid_generate_v4(),
+    player_uuid      UUID        NOT NULL REFERENCES players(uuid),
+    requested_by     UUID        NOT NULL REFERENCES players(uuid),
+    category_id      INTEGER     NOT NULL REFERENCES punishment_categories(id),
+    punishment_type  VARCHAR(20) NOT NULL,             -- Step the ladder gave when requested, planned again on approval
+    duration_minutes INTEGER,
+    reason           TEXT,                             -- Overrides as given to IssuePunishment
+    evidence         TEXT,
+    note             TEXT,
+    scope            VARCHAR(20),
+    server_group     VARCHAR(50),
+    ip_range         CIDR,
+    approval_reason  TEXT        NOT NULL,             -- Why the punishment needs approval, e.g. the limit hit
+
+    status           VARCHAR(20) NOT NULL DEFAULT 'pending', -- 'pending', 'approved', 'rejected'
+    reviewed_by      UUID        REFERENCES players(uuid),
+    reviewed_at      TIMESTAMPTZ,
+    review_comment   TEXT,
+    punishment_id    UUID        REFERENCES punishments(id),  -- Issued on approval
+
+    created_at       TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+
+    CONSTRAINT valid_approval_status CHECK (status IN ('pending', 'approved', 'rejected')),
+    CONSTRAINT approval_review_consistency CHECK (
+        (status = 'pending'  AND reviewed_by IS NULL     AND reviewed_at IS NULL) OR
+        (status <> 'pending' AND reviewed_by IS NOT NULL AND reviewed_at IS NOT NULL)
+    ),
+    CONSTRAINT approved_has_punishment CHECK ((status = 'approved') = (punishment_id IS NOT NULL)),
+    CONSTRAINT reviewer_not_requester CHECK (reviewed_by IS NULL OR reviewed_by != requested_by)
+);
+
+CREATE INDEX idx_punishment_approvals_pending ON punishment_approvals(created_at) WHERE status = 'pending';
+CREATE INDEX idx_punishment_approvals_player_uuid ON punishment_approvals(player_uuid, created_at);
+
+-- Counting today's punishments of a staff member for the daily limit
+CREATE INDEX idx_punishments_staff_uuid_issued_at ON punishments(staff_uuid, issued_at);
@@ -0,0 +1,3 @@
+ALTER TABLE punishment_categories DROP COLUMN IF EXISTS requires_approval;
+
+DROP TABLE IF EXISTS approval_settings;
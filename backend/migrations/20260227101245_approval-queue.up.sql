@@ -0,0 +1,13 @@
+-- Punishments that always need a second staff member, regardless of who issues them
+CREATE TABLE approval_settings (
+    id             BOOLEAN     PRIMARY KEY DEFAULT TRUE,  -- Single row
+    required_types TEXT[]      NOT NULL DEFAULT '{}',     -- Punishment types that need approval
+    updated_by     UUID        REFERENCES players(uuid),
+    updated_at     TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+
+    CONSTRAINT single_row CHECK (id)
+);
+
+INSERT INTO approval_settings (required_types) VALUES ('{perm_ban}');
+
+ALTER TABLE punishment_categories ADD COLUMN requires_approval BOOLEAN NOT NULL DEFAULT FALSE;
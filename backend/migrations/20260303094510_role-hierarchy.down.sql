@@ -0,0 +1 @@
+ALTER TABLE staff_roles DROP COLUMN IF EXISTS weight;
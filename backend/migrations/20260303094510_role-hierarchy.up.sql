@@ -0,0 +1,7 @@
+-- Staff members can only punish players, and revoke or edit punishments of staff members, whose
+-- role weighs less than their own. The 'punishment.override_hierarchy' permission lifts this.
+ALTER TABLE staff_roles ADD COLUMN weight INTEGER NOT NULL DEFAULT 0;
+
+UPDATE staff_roles SET weight = 100 WHERE name = 'Administrator';
+UPDATE staff_roles SET weight = 50  WHERE name = 'Moderator';
+UPDATE staff_roles SET weight = 25  WHERE name = 'Trial Moderator';
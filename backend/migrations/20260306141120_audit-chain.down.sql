@@ -0,0 +1,8 @@
+DROP TRIGGER IF EXISTS reject_audit_event_truncate ON audit_events;
+DROP TRIGGER IF EXISTS reject_audit_event_change ON audit_events;
+DROP TRIGGER IF EXISTS chain_audit_event ON audit_events;
+DROP FUNCTION IF EXISTS reject_audit_event_change();
+DROP FUNCTION IF EXISTS chain_audit_event();
+DROP FUNCTION IF EXISTS audit_event_hash(audit_events);
+ALTER TABLE audit_events DROP COLUMN IF EXISTS hash;
+ALTER TABLE audit_events DROP COLUMN IF EXISTS prev_hash;
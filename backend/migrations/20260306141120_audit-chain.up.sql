@@ -0,0 +1,72 @@
+-- Each audit event stores the SHA-256 of its predecessor's hash and its own contents, so editing or
+-- deleting a row breaks the chain from that row on. The payload hashed is, joined by newlines:
+-- prev_hash, actor_uuid (or ''), action, target_type, target_id (or ''), details::TEXT and
+-- created_at in Unix microseconds. The backend's verify-audit command recomputes it.
+ALTER TABLE audit_events ADD COLUMN prev_hash CHAR(64);
+ALTER TABLE audit_events ADD COLUMN hash      CHAR(64);
+
+CREATE OR REPLACE FUNCTION audit_event_hash(event audit_events)
+RETURNS CHAR(64) AS $$
+    SELECT encode(sha256(convert_to(concat_ws(E'\n',
+        event.prev_hash,
+        COALESCE(event.actor_uuid::TEXT, ''),
+        event.action,
+        event.target_type,
+        COALESCE(event.target_id, ''),
+        event.details::TEXT,
+        (EXTRACT(EPOCH FROM event.created_at) * 1000000)::BIGINT::TEXT
+    ), 'UTF8')), 'hex');
+$$ LANGUAGE sql IMMUTABLE;
+
+-- Existing events become the start of the chain
+DO $$
+DECLARE
+    event audit_events;
+    previous CHAR(64) := repeat('0', 64);
+BEGIN
+    FOR event IN SELECT * FROM audit_events ORDER BY id LOOP
+        event.prev_hash := previous;
+        previous := audit_event_hash(event);
+        UPDATE audit_events SET prev_hash = event.prev_hash, hash = previous WHERE id = event.id;
+    END LOOP;
+END;
+$$;
+
+ALTER TABLE audit_events ALTER COLUMN prev_hash SET NOT NULL;
+ALTER TABLE audit_events ALTER COLUMN hash SET NOT NULL;
+
+-- Links every new event to the latest one. The advisory lock serialises writers and the id is drawn
+-- again once it is held, so ids follow the chain and verification can walk it in id order.
+CREATE OR REPLACE FUNCTION chain_audit_event()
+RETURNS TRIGGER AS $$
+BEGIN
+    PERFORM pg_advisory_xact_lock(hashtext('audit_events'));
+
+    NEW.id := nextval(pg_get_serial_sequence('audit_events', 'id'));
+    NEW.prev_hash := COALESCE(
+        (SELECT hash FROM audit_events ORDER BY id DESC LIMIT 1),
+        repeat('0', 64)
+    );
+    NEW.hash := audit_event_hash(NEW);
+    RETURN NEW;
+END;
+$$ LANGUAGE plpgsql;
+
+CREATE TRIGGER chain_audit_event
+    BEFORE INSERT ON audit_events
+    FOR EACH ROW EXECUTE FUNCTION chain_audit_event();
+
+CREATE OR REPLACE FUNCTION reject_audit_event_change()
+RETURNS TRIGGER AS $$
+BEGIN
+    RAISE EXCEPTION 'audit_events is append-only';
+END;
+$$ LANGUAGE plpgsql;
+
+CREATE TRIGGER reject_audit_event_change
+    BEFORE UPDATE OR DELETE ON audit_events
+    FOR EACH ROW EXECUTE FUNCTION reject_audit_event_change();
+
+CREATE TRIGGER reject_audit_event_truncate
+    BEFORE TRUNCATE ON audit_events
+    FOR EACH STATEMENT EXECUTE FUNCTION reject_audit_event_change();
@@ -0,0 +1,13 @@
+-- Every edit of an issued punishment, numbered per punishment. Updating the punishment itself
+-- also records a punishment event, so online players get the new expiry right away.
+CREATE TABLE punishment_revisions (
+    id            BIGSERIAL   PRIMARY KEY,
+    punishment_id UUID        NOT NULL REFERENCES punishments(id),
+    revision      INTEGER     NOT NULL,
+    edited_by     UUID        NOT NULL REFERENCES players(uuid),
+    changes       JSONB       NOT NULL,                 -- [{"field", "before", "after"}]
+    created_at    TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+
+    CONSTRAINT unique_punishment_revision UNIQUE (punishment_id, revision),
+    CONSTRAINT positive_revision CHECK (revision > 0)
+);
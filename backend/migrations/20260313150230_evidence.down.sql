@@ -0,0 +1,2 @@
+DROP TABLE IF EXISTS evidence_links;
+DROP TABLE IF EXISTS evidence;
@@ -0,0 +1,34 @@
+-- Uploaded evidence. Contents live in the blob store under their SHA-256 digest, identical uploads
+-- share one blob but each upload keeps its own row.
+CREATE TABLE evidence (
+    id           UUID         PRIMARY KEY DEFAULT uuid_generate_v4(),
+    kind         VARCHAR(10)  NOT NULL,                 -- 'file' or 'text', e.g. a chat log snapshot
+    file_name    VARCHAR(255),
+    content_type VARCHAR(100) NOT NULL,
+    size_bytes   BIGINT       NOT NULL,
+    sha256       CHAR(64)     NOT NULL,
+    uploaded_by  UUID         NOT NULL REFERENCES players(uuid),
+    created_at   TIMESTAMPTZ  NOT NULL DEFAULT NOW(),
+
+    CONSTRAINT valid_evidence_kind CHECK (kind IN ('file', 'text')),
+    CONSTRAINT positive_evidence_size CHECK (size_bytes > 0)
+);
+
+CREATE INDEX idx_evidence_sha256 ON evidence(sha256);
+
+-- Attaches evidence to exactly one punishment or appeal, evidence can be attached to several
+CREATE TABLE evidence_links (
+    id            BIGSERIAL   PRIMARY KEY,
+    evidence_id   UUID        NOT NULL REFERENCES evidence(id),
+    punishment_id UUID        REFERENCES punishments(id),
+    appeal_id     UUID        REFERENCES appeals(id),
+    linked_by     UUID        NOT NULL REFERENCES players(uuid),
+    linked_at     TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+
+    CONSTRAINT one_evidence_target CHECK (num_nonnulls(punishment_id, appeal_id) = 1),
+    CONSTRAINT unique_punishment_evidence UNIQUE (evidence_id, punishment_id),
+    CONSTRAINT unique_appeal_evidence UNIQUE (evidence_id, appeal_id)
+);
+
+CREATE INDEX idx_evidence_links_punishment_id ON evidence_links(punishment_id) WHERE punishment_id IS NOT NULL;
+CREATE INDEX idx_evidence_links_appeal_id     ON evidence_links(appeal_id) WHERE appeal_id IS NOT NULL;
@@ -0,0 +1,19 @@
+-- Chat forwarded by the servers and the proxy, partitioned by UTC day of sent_at. The backend creates
+-- the daily partitions ahead of time and drops them once they pass CHAT_RETENTION_DAYS. Late lines
+-- for days without a partition land in the default partition, which is pruned row by row.
+CREATE TABLE chat_messages (
+    id           BIGINT       GENERATED ALWAYS AS IDENTITY,
+    player_uuid  UUID         NOT NULL,                 -- No foreign key, checking one per line costs too much
+    server_name  VARCHAR(64)  NOT NULL,
+    server_group VARCHAR(64),
+    message      TEXT         NOT NULL,
+    sent_at      TIMESTAMPTZ  NOT NULL,
+    received_at  TIMESTAMPTZ  NOT NULL DEFAULT NOW(),
+
+    PRIMARY KEY (id, sent_at)
+) PARTITION BY RANGE (sent_at);
+
+CREATE INDEX idx_chat_messages_player_uuid ON chat_messages(player_uuid, sent_at);
+CREATE INDEX idx_chat_messages_server_name ON chat_messages(server_name, sent_at);
+
+CREATE TABLE chat_messages_default PARTITION OF chat_messages DEFAULT;
@@ -0,0 +1,9 @@
+DROP TABLE IF EXISTS chat_filter_rules;
+DROP TABLE IF EXISTS chat_filter_settings;
+
+-- The account stays once something references it, the audit log can never let go of it
+DELETE FROM players
+WHERE uuid = '00000000-0000-0000-0000-000000000000'
+  AND NOT EXISTS (SELECT 1 FROM punishments WHERE staff_uuid = '00000000-0000-0000-0000-000000000000')
+  AND NOT EXISTS (SELECT 1 FROM punishment_approvals WHERE requested_by = '00000000-0000-0000-0000-000000000000')
+  AND NOT EXISTS (SELECT 1 FROM audit_events WHERE actor_uuid = '00000000-0000-0000-0000-000000000000');
@@ -0,0 +1,40 @@
+-- The backend's own account, the staff member of punishments it issues by itself such as those of the
+-- chat filter. It has no password, so nobody can log in as it, and no role, so it cannot punish staff.
+INSERT INTO players (uuid, username, password_change_required)
+VALUES ('00000000-0000-0000-0000-000000000000', 'Sentinel', FALSE)
+ON CONFLICT (uuid) DO NOTHING;
+
+-- The chat filter servers consult through CheckChat before a message goes out
+CREATE TABLE chat_filter_settings (
+    id                       BOOLEAN     PRIMARY KEY DEFAULT TRUE,  -- Single row
+    enabled                  BOOLEAN     NOT NULL DEFAULT FALSE,
+    advertising_action       VARCHAR(10),                          -- 'block' or 'censor' links and IPs, NULL = allow them
+    allowed_domains          TEXT[]      NOT NULL DEFAULT '{}',     -- Never counted as advertising, subdomains included
+    spam_max_repeats         INTEGER,                              -- Identical messages allowed within the window, NULL = unlimited
+    spam_window_seconds      INTEGER     NOT NULL DEFAULT 30,
+    punish_category_id       INTEGER     REFERENCES punishment_categories(id),  -- NULL = never punish automatically
+    punish_after_violations  INTEGER     NOT NULL DEFAULT 3,
+    violation_window_minutes INTEGER     NOT NULL DEFAULT 10,
+    updated_by               UUID        REFERENCES players(uuid),
+    updated_at               TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+
+    CONSTRAINT single_row CHECK (id),
+    CONSTRAINT valid_advertising_action CHECK (advertising_action IN ('block', 'censor')),
+    CONSTRAINT positive_spam_max_repeats CHECK (spam_max_repeats IS NULL OR spam_max_repeats > 0),
+    CONSTRAINT positive_spam_window CHECK (spam_window_seconds > 0),
+    CONSTRAINT positive_punish_after_violations CHECK (punish_after_violations > 0),
+    CONSTRAINT positive_violation_window CHECK (violation_window_minutes > 0)
+);
+
+INSERT INTO chat_filter_settings DEFAULT VALUES;
+
+CREATE TABLE chat_filter_rules (
+    id         SERIAL      PRIMARY KEY,
+    kind       VARCHAR(10) NOT NULL,  -- 'word' matches whole words after normalising leetspeak, 'regex' the raw message
+    pattern    TEXT        NOT NULL,
+    action     VARCHAR(10) NOT NULL,  -- 'block' or 'censor'
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+
+    CONSTRAINT valid_rule_kind CHECK (kind IN ('word', 'regex')),
+    CONSTRAINT valid_rule_action CHECK (action IN ('block', 'censor'))
+);
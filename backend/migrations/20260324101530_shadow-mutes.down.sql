@@ -0,0 +1,18 @@
+-- Issued shadow mutes and those in published templates, which cannot change anymore, stay behind.
+-- NOT VALID only keeps new rows from using the type.
+ALTER TABLE punishment_templates
+    DROP CONSTRAINT IF EXISTS valid_punishment_type,
+    DROP CONSTRAINT IF EXISTS duration_required_for_timed,
+    ADD CONSTRAINT valid_punishment_type CHECK (punishment_type IN ('warn', 'mute', 'kick', 'temp_ban', 'perm_ban')) NOT VALID,
+    ADD CONSTRAINT duration_required_for_timed CHECK (
+        (punishment_type IN ('temp_ban', 'mute') AND duration_minutes > 0) OR
+        (punishment_type NOT IN ('temp_ban', 'mute'))
+    ) NOT VALID;
+
+ALTER TABLE punishments
+    DROP CONSTRAINT IF EXISTS valid_punishment_type,
+    DROP CONSTRAINT IF EXISTS ip_range_punishment_type,
+    ADD CONSTRAINT valid_punishment_type CHECK (punishment_type IN ('warn', 'mute', 'kick', 'temp_ban', 'perm_ban')) NOT VALID,
+    ADD CONSTRAINT ip_range_punishment_type CHECK (
+        ip_range IS NULL OR punishment_type IN ('mute', 'temp_ban', 'perm_ban')
+    ) NOT VALID;
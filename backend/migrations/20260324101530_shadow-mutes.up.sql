@@ -0,0 +1,18 @@
+-- Shadow mutes hide a player's chat from everyone but the player, who is never told about it.
+-- Timed like a regular mute.
+ALTER TABLE punishment_templates
+    DROP CONSTRAINT valid_punishment_type,
+    DROP CONSTRAINT duration_required_for_timed,
+    ADD CONSTRAINT valid_punishment_type CHECK (punishment_type IN ('warn', 'mute', 'shadow_mute', 'kick', 'temp_ban', 'perm_ban')),
+    ADD CONSTRAINT duration_required_for_timed CHECK (
+        (punishment_type IN ('temp_ban', 'mute', 'shadow_mute') AND duration_minutes > 0) OR
+        (punishment_type NOT IN ('temp_ban', 'mute', 'shadow_mute'))
+    );
+
+ALTER TABLE punishments
+    DROP CONSTRAINT valid_punishment_type,
+    DROP CONSTRAINT ip_range_punishment_type,
+    ADD CONSTRAINT valid_punishment_type CHECK (punishment_type IN ('warn', 'mute', 'shadow_mute', 'kick', 'temp_ban', 'perm_ban')),
+    ADD CONSTRAINT ip_range_punishment_type CHECK (
+        ip_range IS NULL OR punishment_type IN ('mute', 'shadow_mute', 'temp_ban', 'perm_ban')
+    );
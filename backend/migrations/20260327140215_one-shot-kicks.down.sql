@@ -0,0 +1,19 @@
+ALTER TABLE punishments DROP CONSTRAINT IF EXISTS kicks_never_active;
+
+CREATE OR REPLACE FUNCTION record_punishment_event()
+RETURNS TRIGGER AS $$
+DECLARE
+    event_sequence BIGINT;
+BEGIN
+    PERFORM pg_advisory_xact_lock(hashtext('punishment_events'));
+
+    INSERT INTO punishment_events (punishment_id, player_uuid)
+    VALUES (NEW.id, NEW.player_uuid)
+    RETURNING sequence INTO event_sequence;
+
+    PERFORM pg_notify('punishment_events', event_sequence::TEXT);
+    RETURN NEW;
+END;
+$$ LANGUAGE plpgsql;
+
+ALTER TABLE punishment_events DROP COLUMN IF EXISTS issued;
@@ -0,0 +1,30 @@
+-- Kicks happen once: the live stream delivers them to the player's server when they are issued and
+-- they stay in the history, but they are never active, so the login check cannot keep finding them.
+ALTER TABLE punishment_events
+    ADD COLUMN issued BOOLEAN NOT NULL DEFAULT FALSE;  -- Written by the insert of the punishment, later events are changes
+
+UPDATE punishment_events e
+SET issued = TRUE
+WHERE e.sequence = (SELECT MIN(first.sequence) FROM punishment_events first WHERE first.punishment_id = e.punishment_id);
+
+CREATE OR REPLACE FUNCTION record_punishment_event()
+RETURNS TRIGGER AS $$
+DECLARE
+    event_sequence BIGINT;
+BEGIN
+    PERFORM pg_advisory_xact_lock(hashtext('punishment_events'));
+
+    INSERT INTO punishment_events (punishment_id, player_uuid, issued)
+    VALUES (NEW.id, NEW.player_uuid, TG_OP = 'INSERT')
+    RETURNING sequence INTO event_sequence;
+
+    PERFORM pg_notify('punishment_events', event_sequence::TEXT);
+    RETURN NEW;
+END;
+$$ LANGUAGE plpgsql;
+
+-- Kicks issued so far were left active and blocked every later login
+UPDATE punishments SET active = FALSE WHERE punishment_type = 'kick' AND active;
+
+ALTER TABLE punishments
+    ADD CONSTRAINT kicks_never_active CHECK (punishment_type != 'kick' OR active = FALSE);
@@ -0,0 +1,8 @@
+ALTER TABLE punishments
+    DROP CONSTRAINT IF EXISTS acknowledged_only_when_required,
+    DROP COLUMN IF EXISTS acknowledged_at,
+    DROP COLUMN IF EXISTS requires_acknowledgement;
+
+ALTER TABLE punishment_templates
+    DROP CONSTRAINT IF EXISTS acknowledgement_only_for_warns,
+    DROP COLUMN IF EXISTS requires_acknowledgement;
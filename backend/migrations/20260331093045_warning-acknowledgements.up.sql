@@ -0,0 +1,10 @@
+-- Warns can ask the player to confirm them. Servers keep showing an unacknowledged warning on login,
+-- e.g. as a book the player has to close, until AcknowledgeWarning records the confirmation.
+ALTER TABLE punishment_templates
+    ADD COLUMN requires_acknowledgement BOOLEAN NOT NULL DEFAULT FALSE,
+    ADD CONSTRAINT acknowledgement_only_for_warns CHECK (NOT requires_acknowledgement OR punishment_type = 'warn');
+
+ALTER TABLE punishments
+    ADD COLUMN requires_acknowledgement BOOLEAN NOT NULL DEFAULT FALSE,  -- Copied from the template at issue time
+    ADD COLUMN acknowledged_at          TIMESTAMPTZ,
+    ADD CONSTRAINT acknowledged_only_when_required CHECK (acknowledged_at IS NULL OR requires_acknowledgement);
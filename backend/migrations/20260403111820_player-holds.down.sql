@@ -0,0 +1,2 @@
+DROP TABLE IF EXISTS hold_settings;
+DROP TABLE IF EXISTS player_holds;
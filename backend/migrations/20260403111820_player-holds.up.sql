@@ -0,0 +1,32 @@
+-- Staff freezing a player for a screenshare. The player cannot play or leave cleanly while the hold is
+-- open, and disconnecting before staff release them issues the configured punishment.
+CREATE TABLE player_holds (
+    id            UUID        PRIMARY KEY DEFAULT uuid_generate_v4(),
+    player_uuid   UUID        NOT NULL REFERENCES players(uuid),
+    staff_uuid    UUID        NOT NULL REFERENCES players(uuid),
+    reason        TEXT,
+    created_at    TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    ended_at      TIMESTAMPTZ,                                -- NULL while the player is held
+    ended_by      UUID        REFERENCES players(uuid),       -- NULL when the player disconnected
+    outcome       VARCHAR(20),                                -- 'released' or 'disconnected'
+    punishment_id UUID        REFERENCES punishments(id),     -- Issued because the player disconnected
+
+    CONSTRAINT valid_outcome CHECK (outcome IN ('released', 'disconnected')),
+    CONSTRAINT ended_with_outcome CHECK ((ended_at IS NULL) = (outcome IS NULL))
+);
+
+-- A player is only ever held once at a time
+CREATE UNIQUE INDEX idx_player_holds_open ON player_holds(player_uuid) WHERE ended_at IS NULL;
+CREATE INDEX idx_player_holds_player ON player_holds(player_uuid, created_at DESC);
+
+CREATE TABLE hold_settings (
+    id                     BOOLEAN     PRIMARY KEY DEFAULT TRUE,  -- Single row
+    disconnect_category_id INTEGER     REFERENCES punishment_categories(id),  -- NULL = disconnecting only ends the hold
+    updated_by             UUID        REFERENCES players(uuid),
+    updated_at             TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+
+    CONSTRAINT single_row CHECK (id)
+);
+
+INSERT INTO hold_settings (disconnect_category_id)
+VALUES ((SELECT id FROM punishment_categories WHERE name = 'Cheating/Hacking'));
@@ -0,0 +1,14 @@
+-- Reports players file against each other in game, forwarded by the server through CreateReport
+CREATE TABLE reports (
+    id            UUID        PRIMARY KEY DEFAULT uuid_generate_v4(),
+    reporter_uuid UUID        NOT NULL REFERENCES players(uuid),
+    reported_uuid UUID        NOT NULL REFERENCES players(uuid),
+    reason        TEXT        NOT NULL,
+    server_name   VARCHAR(64),                              -- Server the reporter was on
+    created_at    TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+
+    CONSTRAINT no_self_reports CHECK (reporter_uuid <> reported_uuid)
+);
+
+CREATE INDEX idx_reports_reported ON reports(reported_uuid, created_at DESC);
+CREATE INDEX idx_reports_reporter ON reports(reporter_uuid);
@@ -0,0 +1,3 @@
+UPDATE player_holds SET outcome = 'disconnected' WHERE outcome = 'punished';
+ALTER TABLE player_holds DROP CONSTRAINT IF EXISTS valid_outcome;
+ALTER TABLE player_holds ADD CONSTRAINT valid_outcome CHECK (outcome IN ('released', 'disconnected'));
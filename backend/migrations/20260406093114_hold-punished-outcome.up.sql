@@ -0,0 +1,3 @@
+-- Holds ended by staff kicking or banning the held player, which disconnects them without dodging
+ALTER TABLE player_holds DROP CONSTRAINT IF EXISTS valid_outcome;
+ALTER TABLE player_holds ADD CONSTRAINT valid_outcome CHECK (outcome IN ('released', 'disconnected', 'punished'));
@@ -0,0 +1,5 @@
+-- Without the threshold the requests would be approved as ladder steps
+DELETE FROM punishment_approvals WHERE threshold_points IS NOT NULL;
+ALTER TABLE punishment_approvals DROP CONSTRAINT IF EXISTS automatic_ban_has_offense;
+ALTER TABLE punishment_approvals DROP COLUMN IF EXISTS offense_id;
+ALTER TABLE punishment_approvals DROP COLUMN IF EXISTS threshold_points;
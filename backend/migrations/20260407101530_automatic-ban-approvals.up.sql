@@ -0,0 +1,5 @@
+-- Automatic bans of severity thresholds that need approval are queued like staff punishments
+ALTER TABLE punishment_approvals
+    ADD COLUMN threshold_points INTEGER,                              -- Set on queued automatic bans
+    ADD COLUMN offense_id       UUID REFERENCES punishments(id),      -- Punishment that reached the threshold
+    ADD CONSTRAINT automatic_ban_has_offense CHECK ((threshold_points IS NULL) = (offense_id IS NULL));
@@ -0,0 +1,7 @@
+DELETE FROM evidence_links WHERE report_id IS NOT NULL;
+DROP INDEX IF EXISTS idx_evidence_links_report_id;
+ALTER TABLE evidence_links
+    DROP CONSTRAINT IF EXISTS unique_report_evidence,
+    DROP CONSTRAINT IF EXISTS one_evidence_target,
+    DROP COLUMN IF EXISTS report_id,
+    ADD CONSTRAINT one_evidence_target CHECK (num_nonnulls(punishment_id, appeal_id) = 1);
@@ -0,0 +1,8 @@
+-- Evidence can also be attached to reports, e.g. the chat log a server uploads with one
+ALTER TABLE evidence_links
+    ADD COLUMN report_id UUID REFERENCES reports(id),
+    DROP CONSTRAINT IF EXISTS one_evidence_target,
+    ADD CONSTRAINT one_evidence_target CHECK (num_nonnulls(punishment_id, appeal_id, report_id) = 1),
+    ADD CONSTRAINT unique_report_evidence UNIQUE (evidence_id, report_id);
+
+CREATE INDEX idx_evidence_links_report_id ON evidence_links(report_id) WHERE report_id IS NOT NULL;
@@ -0,0 +1,7 @@
+use dotenvy::var;
+use std::str::FromStr;
+
+/// Reads an optional setting from the environment, falling back to `default` when unset or unparsable.
+pub fn var_or<T: FromStr>(key: &str, default: T) -> T {
+    var(key).ok().and_then(|value| value.parse().ok()).unwrap_or(default)
+}
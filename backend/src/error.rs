@@ -6,6 +6,7 @@ use std::net::AddrParseError;
 use std::result::Result as StdResult;
 use thiserror::Error;
 use tonic::transport::Error as TonicTransportError;
+use tonic::Status;
 use uuid::Error as UuidError;
 use validator::ValidationErrors;
 
@@ -35,10 +36,26 @@ pub enum AppError {
     WrongCredentials(String),
     #[error("Not found: {0}")]
     NotFound(String),
+    #[error("Permission denied: {0}")]
+    PermissionDenied(String),
     #[error("Validation failed: {0}")]
     CustomValidationError(String),
     #[error("Internal server error: {0}")]
     InternalError(String),
 }
 
-pub type AppResult<T> = StdResult<T, AppError>;
\ No newline at end of file
+pub type AppResult<T> = StdResult<T, AppError>;
+
+impl From<AppError> for Status {
+    fn from(error: AppError) -> Self {
+        match error {
+            AppError::Unauthorized(_) | AppError::WrongCredentials(_) => Status::unauthenticated(error.to_string()),
+            AppError::NotFound(_) => Status::not_found(error.to_string()),
+            AppError::PermissionDenied(_) => Status::permission_denied(error.to_string()),
+            AppError::ValidationError(_) | AppError::CustomValidationError(_) | AppError::UuidError(_) => {
+                Status::invalid_argument(error.to_string())
+            }
+            _ => Status::internal(error.to_string()),
+        }
+    }
+}
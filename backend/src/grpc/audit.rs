@@ -0,0 +1,77 @@
+use crate::grpc::generated::audit_service_server::AuditService as GeneratedAuditService;
+use crate::grpc::generated::{ListAuditEventsRequest, ListAuditEventsResponse};
+use crate::models::{permissions, AuditFilter};
+use crate::services::{AuditService, PlayerService};
+use std::str::FromStr;
+use std::sync::Arc;
+use time::OffsetDateTime;
+use tonic::{Request, Response, Status};
+use uuid::Uuid;
+
+pub struct GrpcAuditService {
+    player_service: Arc<PlayerService>,
+    audit_service: Arc<AuditService>,
+}
+
+impl GrpcAuditService {
+    const DEFAULT_PAGE_SIZE: u32 = 50;
+    const MAX_PAGE_SIZE: u32 = 200;
+
+    pub fn new(player_service: Arc<PlayerService>, audit_service: Arc<AuditService>) -> Self {
+        Self {
+            player_service,
+            audit_service,
+        }
+    }
+}
+
+#[tonic::async_trait]
+impl GeneratedAuditService for GrpcAuditService {
+    async fn list_audit_events(
+        &self,
+        request: Request<ListAuditEventsRequest>,
+    ) -> Result<Response<ListAuditEventsResponse>, Status> {
+        self.player_service.verify_permission(&request, permissions::VIEW_AUDIT_LOG).await?;
+
+        let request = request.into_inner();
+        let parse_timestamp = |timestamp: Option<i64>, name: &str| {
+            timestamp
+                .map(OffsetDateTime::from_unix_timestamp)
+                .transpose()
+                .map_err(|e| Status::invalid_argument(format!("Invalid {}: {}", name, e)))
+        };
+
+        let filter = AuditFilter {
+            actor_uuid: request
+                .actor_id
+                .as_deref()
+                .map(Uuid::from_str)
+                .transpose()
+                .map_err(|e| Status::invalid_argument(format!("Invalid actor ID: {}", e)))?,
+            action: request.action,
+            target_type: request.target_type,
+            target_id: request.target_id,
+            created_after: parse_timestamp(request.created_after, "created_after")?,
+            created_before: parse_timestamp(request.created_before, "created_before")?,
+        };
+        let limit = request.limit.unwrap_or(Self::DEFAULT_PAGE_SIZE).clamp(1, Self::MAX_PAGE_SIZE) as usize;
+
+        // Fetching one extra event tells whether there is another page
+        let mut events = self
+            .audit_service
+            .list_events(&filter, request.cursor, limit as i64 + 1)
+            .await?;
+
+        let next_cursor = if events.len() > limit {
+            events.truncate(limit);
+            events.last().map(|last| last.id)
+        } else {
+            None
+        };
+
+        Ok(Response::new(ListAuditEventsResponse {
+            events: events.into_iter().map(Into::into).collect(),
+            next_cursor,
+        }))
+    }
+}
@@ -0,0 +1,223 @@
+use crate::grpc::generated::chat_service_server::ChatService as GeneratedChatService;
+use crate::grpc::generated::{
+    CaptureChatContextRequest, CaptureChatContextResponse, ChatFilterResponse, ChatLine as GeneratedChatLine, CheckChatRequest,
+    CheckChatResponse, GetChatContextRequest, GetChatContextResponse, GetChatFilterRequest, IngestChatResponse,
+    UpdateChatFilterRequest,
+};
+use crate::models::{
+    format_chat_log, permissions, ChatFilterUpdate, ChatLine, EvidenceTarget, EvidenceUpload, IssueOutcome, NewChatLine, NewEvidence,
+    MAX_CHAT_MESSAGE_LENGTH,
+};
+use crate::services::{ChatFilterService, ChatService, EvidenceService, PlayerService};
+use std::str::FromStr;
+use std::sync::Arc;
+use std::time::Duration;
+use time::OffsetDateTime;
+use tokio::time::{timeout_at, Instant};
+use tokio_stream::StreamExt;
+use tonic::{Request, Response, Status, Streaming};
+use uuid::Uuid;
+
+pub struct GrpcChatService {
+    player_service: Arc<PlayerService>,
+    chat_service: Arc<ChatService>,
+    chat_filter_service: Arc<ChatFilterService>,
+    evidence_service: Arc<EvidenceService>,
+}
+
+impl GrpcChatService {
+    const BATCH_SIZE: usize = 200;
+    const FLUSH_INTERVAL: Duration = Duration::from_secs(1);
+    const DEFAULT_CONTEXT_LINES: u32 = 25;
+    const MAX_CONTEXT_LINES: u32 = 200;
+
+    pub fn new(
+        player_service: Arc<PlayerService>,
+        chat_service: Arc<ChatService>,
+        chat_filter_service: Arc<ChatFilterService>,
+        evidence_service: Arc<EvidenceService>,
+    ) -> Self {
+        Self {
+            player_service,
+            chat_service,
+            chat_filter_service,
+            evidence_service,
+        }
+    }
+
+    async fn load_context(&self, request: GetChatContextRequest) -> Result<Vec<ChatLine>, Status> {
+        let player_uuid = Uuid::from_str(&request.player_id)
+            .map_err(|e| Status::invalid_argument(format!("Invalid player ID: {}", e)))?;
+        let at = OffsetDateTime::from_unix_timestamp_nanos(request.at_millis as i128 * 1_000_000)
+            .map_err(|e| Status::invalid_argument(format!("Invalid at_millis: {}", e)))?;
+        let lines = |count: Option<u32>| count.unwrap_or(Self::DEFAULT_CONTEXT_LINES).min(Self::MAX_CONTEXT_LINES) as i64;
+
+        Ok(self
+            .chat_service
+            .get_context(player_uuid, at, lines(request.before), lines(request.after), request.server_name.as_deref())
+            .await?)
+    }
+}
+
+#[tonic::async_trait]
+impl GeneratedChatService for GrpcChatService {
+    async fn ingest_chat(
+        &self,
+        request: Request<Streaming<GeneratedChatLine>>,
+    ) -> Result<Response<IngestChatResponse>, Status> {
+        self.player_service.verify_server(&request)?;
+
+        let mut stream = request.into_inner();
+        let mut batch = Vec::new();
+        let mut flush_at = None;
+        let mut accepted = 0;
+        let mut rejected = 0;
+
+        loop {
+            // Waits at most until the oldest buffered line is due, so quiet servers are not held back
+            let next = match flush_at {
+                Some(deadline) => match timeout_at(deadline, stream.next()).await {
+                    Ok(next) => next,
+                    Err(_) => {
+                        accepted += self.chat_service.insert_lines(&batch).await?;
+                        batch.clear();
+                        flush_at = None;
+                        continue;
+                    }
+                },
+                None => stream.next().await,
+            };
+
+            let Some(line) = next else {
+                break;
+            };
+
+            match NewChatLine::from_message(line?, OffsetDateTime::now_utc(), self.chat_service.retention()) {
+                Ok(line) => {
+                    batch.push(line);
+                    flush_at.get_or_insert_with(|| Instant::now() + Self::FLUSH_INTERVAL);
+                }
+                Err(_) => rejected += 1,
+            }
+
+            if batch.len() >= Self::BATCH_SIZE {
+                accepted += self.chat_service.insert_lines(&batch).await?;
+                batch.clear();
+                flush_at = None;
+            }
+        }
+
+        accepted += self.chat_service.insert_lines(&batch).await?;
+
+        Ok(Response::new(IngestChatResponse {
+            accepted,
+            rejected,
+        }))
+    }
+
+    async fn get_chat_context(
+        &self,
+        request: Request<GetChatContextRequest>,
+    ) -> Result<Response<GetChatContextResponse>, Status> {
+        let claims = self.player_service.verify_request(&request).await?;
+        if !claims.staff {
+            return Err(Status::permission_denied("Only staff members can read chat"));
+        }
+
+        let lines = self.load_context(request.into_inner()).await?;
+
+        Ok(Response::new(GetChatContextResponse {
+            lines: lines.into_iter().map(Into::into).collect(),
+        }))
+    }
+
+    async fn capture_chat_context(
+        &self,
+        request: Request<CaptureChatContextRequest>,
+    ) -> Result<Response<CaptureChatContextResponse>, Status> {
+        let claims = self.player_service.verify_request(&request).await?;
+        if !claims.staff {
+            return Err(Status::permission_denied("Only staff members can capture chat"));
+        }
+
+        let request = request.into_inner();
+        let context = request
+            .context
+            .ok_or_else(|| Status::invalid_argument("The chat context is missing"))?;
+        let target = request.target.map(EvidenceTarget::try_from).transpose()?;
+        let file_name = format!("chat-{}-{}.txt", context.player_id, context.at_millis);
+
+        let lines = self.load_context(context).await?;
+        if lines.is_empty() {
+            return Err(Status::not_found("The player sent no chat around that time"));
+        }
+
+        let mut upload = EvidenceUpload::new(usize::MAX);
+        upload.push(format_chat_log(&lines).as_bytes())?;
+        let (data, sha256) = upload.finish("text", None)?;
+        let evidence = NewEvidence::new("text".to_string(), Some(file_name), None)?;
+
+        let stored = self
+            .evidence_service
+            .store_evidence(claims.sub, evidence, data, sha256, target)
+            .await?;
+
+        Ok(Response::new(CaptureChatContextResponse {
+            evidence: Some(stored.into()),
+        }))
+    }
+
+    async fn check_chat(
+        &self,
+        request: Request<CheckChatRequest>,
+    ) -> Result<Response<CheckChatResponse>, Status> {
+        // The verdict can punish the player, so only servers may ask for one
+        self.player_service.verify_server(&request)?;
+
+        let request = request.into_inner();
+        let player_uuid = Uuid::from_str(&request.player_id)
+            .map_err(|e| Status::invalid_argument(format!("Invalid player ID: {}", e)))?;
+        if request.message.is_empty() || request.message.len() > MAX_CHAT_MESSAGE_LENGTH {
+            return Err(Status::invalid_argument(format!(
+                "Chat messages have to be between 1 and {} bytes long",
+                MAX_CHAT_MESSAGE_LENGTH
+            )));
+        }
+
+        let (verdict, outcome) = self.chat_filter_service.check_chat(player_uuid, &request.message).await?;
+        let punishment_id = match outcome {
+            Some(IssueOutcome::Issued(issued)) => Some(issued.punishment.id.to_string()),
+            _ => None,
+        };
+
+        Ok(Response::new(CheckChatResponse {
+            verdict: verdict.action.as_str().to_string(),
+            message: verdict.message,
+            reasons: verdict.reasons,
+            punishment_id,
+        }))
+    }
+
+    async fn get_chat_filter(
+        &self,
+        request: Request<GetChatFilterRequest>,
+    ) -> Result<Response<ChatFilterResponse>, Status> {
+        self.player_service.verify_permission(&request, permissions::MANAGE_POLICY).await?;
+
+        let (settings, rules) = self.chat_filter_service.get_chat_filter().await?;
+
+        Ok(Response::new(settings.into_message(rules)))
+    }
+
+    async fn update_chat_filter(
+        &self,
+        request: Request<UpdateChatFilterRequest>,
+    ) -> Result<Response<ChatFilterResponse>, Status> {
+        let claims = self.player_service.verify_permission(&request, permissions::MANAGE_POLICY).await?;
+
+        let update = ChatFilterUpdate::from(request.into_inner());
+        let (settings, rules) = self.chat_filter_service.update_chat_filter(claims.sub, update).await?;
+
+        Ok(Response::new(settings.into_message(rules)))
+    }
+}
@@ -0,0 +1,147 @@
+use crate::grpc::generated::download_evidence_response::Part as DownloadPart;
+use crate::grpc::generated::evidence_service_server::EvidenceService as GeneratedEvidenceService;
+use crate::grpc::generated::upload_evidence_request::Part as UploadPart;
+use crate::grpc::generated::{
+    DownloadEvidenceRequest, DownloadEvidenceResponse, EvidenceTarget as GeneratedEvidenceTarget, LinkEvidenceRequest,
+    LinkEvidenceResponse, ListEvidenceRequest, ListEvidenceResponse, UploadEvidenceRequest, UploadEvidenceResponse,
+};
+use crate::models::{EvidenceTarget, EvidenceUpload, NewEvidence, SYSTEM_PLAYER_UUID};
+use crate::services::{EvidenceService, PlayerService};
+use std::str::FromStr;
+use std::sync::Arc;
+use std::vec::IntoIter;
+use tokio_stream::{Iter, StreamExt};
+use tonic::{Request, Response, Status, Streaming};
+use uuid::Uuid;
+
+pub struct GrpcEvidenceService {
+    player_service: Arc<PlayerService>,
+    evidence_service: Arc<EvidenceService>,
+    max_bytes: usize,
+}
+
+impl GrpcEvidenceService {
+    const CHUNK_SIZE: usize = 64 * 1024;
+
+    pub fn new(player_service: Arc<PlayerService>, evidence_service: Arc<EvidenceService>, max_bytes: usize) -> Self {
+        Self {
+            player_service,
+            evidence_service,
+            max_bytes,
+        }
+    }
+
+    fn parse_target(target: Option<GeneratedEvidenceTarget>) -> Result<EvidenceTarget, Status> {
+        let target = target.ok_or_else(|| Status::invalid_argument("The evidence target is missing"))?;
+        Ok(EvidenceTarget::try_from(target)?)
+    }
+}
+
+#[tonic::async_trait]
+impl GeneratedEvidenceService for GrpcEvidenceService {
+    async fn upload_evidence(
+        &self,
+        request: Request<Streaming<UploadEvidenceRequest>>,
+    ) -> Result<Response<UploadEvidenceResponse>, Status> {
+        // Servers upload chat logs with their token, recorded as uploads of the backend's own account
+        let uploaded_by = if request.metadata().contains_key("x-server-token") {
+            self.player_service.verify_server(&request)?;
+            SYSTEM_PLAYER_UUID
+        } else {
+            let claims = self.player_service.verify_request(&request).await?;
+            if !claims.staff {
+                return Err(Status::permission_denied("Only staff members can upload evidence"));
+            }
+            claims.sub
+        };
+
+        let mut parts = request.into_inner();
+        let metadata = match parts.next().await.transpose()?.and_then(|request| request.part) {
+            Some(UploadPart::Metadata(metadata)) => metadata,
+            _ => return Err(Status::invalid_argument("The first message has to carry the evidence metadata")),
+        };
+
+        let evidence = NewEvidence::new(metadata.kind, metadata.file_name, metadata.content_type)?;
+        let target = metadata.target.map(|target| Self::parse_target(Some(target))).transpose()?;
+
+        let mut upload = EvidenceUpload::new(self.max_bytes);
+        while let Some(request) = parts.next().await.transpose()? {
+            match request.part {
+                Some(UploadPart::Chunk(chunk)) => upload.push(&chunk)?,
+                _ => return Err(Status::invalid_argument("Only the first message can carry metadata")),
+            }
+        }
+        let (data, sha256) = upload.finish(&evidence.kind, metadata.sha256.as_deref())?;
+
+        let stored = self
+            .evidence_service
+            .store_evidence(uploaded_by, evidence, data, sha256, target)
+            .await?;
+
+        Ok(Response::new(UploadEvidenceResponse {
+            evidence: Some(stored.into()),
+        }))
+    }
+
+    async fn link_evidence(
+        &self,
+        request: Request<LinkEvidenceRequest>,
+    ) -> Result<Response<LinkEvidenceResponse>, Status> {
+        let claims = self.player_service.verify_request(&request).await?;
+        if !claims.staff {
+            return Err(Status::permission_denied("Only staff members can attach evidence"));
+        }
+
+        let request = request.into_inner();
+        let evidence_id = Uuid::from_str(&request.evidence_id)
+            .map_err(|e| Status::invalid_argument(format!("Invalid evidence ID: {}", e)))?;
+        let target = Self::parse_target(request.target)?;
+
+        self.evidence_service.link_evidence(claims.sub, evidence_id, target).await?;
+
+        Ok(Response::new(LinkEvidenceResponse {}))
+    }
+
+    async fn list_evidence(
+        &self,
+        request: Request<ListEvidenceRequest>,
+    ) -> Result<Response<ListEvidenceResponse>, Status> {
+        let claims = self.player_service.verify_request(&request).await?;
+        if !claims.staff {
+            return Err(Status::permission_denied("Only staff members can list evidence"));
+        }
+
+        let target = Self::parse_target(request.into_inner().target)?;
+        let evidence = self.evidence_service.list_evidence(target).await?;
+
+        Ok(Response::new(ListEvidenceResponse {
+            evidence: evidence.into_iter().map(Into::into).collect(),
+        }))
+    }
+
+    type DownloadEvidenceStream = Iter<IntoIter<Result<DownloadEvidenceResponse, Status>>>;
+
+    async fn download_evidence(
+        &self,
+        request: Request<DownloadEvidenceRequest>,
+    ) -> Result<Response<Self::DownloadEvidenceStream>, Status> {
+        let claims = self.player_service.verify_request(&request).await?;
+        if !claims.staff {
+            return Err(Status::permission_denied("Only staff members can download evidence"));
+        }
+
+        let evidence_id = Uuid::from_str(&request.into_inner().evidence_id)
+            .map_err(|e| Status::invalid_argument(format!("Invalid evidence ID: {}", e)))?;
+        let (evidence, data) = self.evidence_service.read_evidence(evidence_id).await?;
+
+        let metadata = DownloadEvidenceResponse {
+            part: Some(DownloadPart::Evidence(evidence.into())),
+        };
+        let chunks = data.chunks(Self::CHUNK_SIZE).map(|chunk| DownloadEvidenceResponse {
+            part: Some(DownloadPart::Chunk(chunk.to_vec())),
+        });
+        let responses: Vec<_> = std::iter::once(metadata).chain(chunks).map(Ok).collect();
+
+        Ok(Response::new(tokio_stream::iter(responses)))
+    }
+}
@@ -1,38 +1,103 @@
+mod audit;
 mod authentication;
+mod chat;
+mod evidence;
 mod report;
 mod punishment;
+mod player;
+mod policy;
 
+use crate::config::var_or;
 use crate::error::AppResult;
+use crate::grpc::audit::GrpcAuditService;
 use crate::grpc::authentication::GrpcAuthenticationService;
+use crate::grpc::chat::GrpcChatService;
+use crate::grpc::evidence::GrpcEvidenceService;
+use crate::grpc::generated::audit_service_server::AuditServiceServer;
 use crate::grpc::generated::authentication_service_server::AuthenticationServiceServer;
+use crate::grpc::generated::chat_service_server::ChatServiceServer;
+use crate::grpc::generated::evidence_service_server::EvidenceServiceServer;
+use crate::grpc::generated::player_directory_service_server::PlayerDirectoryServiceServer;
+use crate::grpc::generated::player_service_server::PlayerServiceServer;
+use crate::grpc::generated::policy_service_server::PolicyServiceServer;
 use crate::grpc::generated::punishment_service_server::PunishmentServiceServer;
 use crate::grpc::generated::report_service_server::ReportServiceServer;
+use crate::grpc::player::{GrpcPlayerDirectoryService, GrpcPlayerService};
+use crate::grpc::policy::GrpcPolicyService;
 use crate::grpc::punishment::GrpcPunishmentService;
 use crate::grpc::report::GrpcReportService;
-use crate::services::{BroadcastService, MessageService, PlayerService, PunishmentService, ReportService};
+use crate::services::{AltService, AuditService, BroadcastService, ChatFilterService, ChatService, EvidenceService, HoldService, MessageService, PlayerService, PolicyService, PunishmentService, ReportService};
 use std::sync::Arc;
+use std::time::Duration;
 use tonic::transport::Server;
 
 pub mod generated {
-    tonic::include_proto!("authentication");
-    tonic::include_proto!("punishment");
-    tonic::include_proto!("report");
+    #![allow(clippy::all)]
+
+    // One module per package so imports between protos resolve, re-exported flat for convenience
+    pub mod authentication { tonic::include_proto!("authentication"); }
+    pub mod punishment { tonic::include_proto!("punishment"); }
+    pub mod report { tonic::include_proto!("report"); }
+    pub mod player { tonic::include_proto!("player"); }
+    pub mod policy { tonic::include_proto!("policy"); }
+    pub mod audit { tonic::include_proto!("audit"); }
+    pub mod evidence { tonic::include_proto!("evidence"); }
+    pub mod chat { tonic::include_proto!("chat"); }
+
+    pub use authentication::*;
+    pub use punishment::*;
+    pub use report::*;
+    pub use player::*;
+    pub use policy::*;
+    pub use audit::*;
+    pub use evidence::*;
+    pub use chat::*;
 }
 
+#[allow(clippy::too_many_arguments)]
 pub async fn start_grpc_server(player_service: Arc<PlayerService>,
                                punishment_service: Arc<PunishmentService>,
                                report_service: Arc<ReportService>,
                                message_service: Arc<MessageService>,
-                               broadcast_service: Arc<BroadcastService>) -> AppResult<()> {
+                               broadcast_service: Arc<BroadcastService>,
+                               alt_service: Arc<AltService>,
+                               policy_service: Arc<PolicyService>,
+                               audit_service: Arc<AuditService>,
+                               evidence_service: Arc<EvidenceService>,
+                               chat_service: Arc<ChatService>,
+                               chat_filter_service: Arc<ChatFilterService>,
+                               hold_service: Arc<HoldService>) -> AppResult<()> {
     let addr = "0.0.0.0:50051".parse()?;
-    let grpc_auth_service = GrpcAuthenticationService::new(player_service);
-    let grpc_punishment_service = GrpcPunishmentService::new(punishment_service, message_service, broadcast_service);
-    let grpc_report_service = GrpcReportService::new(report_service);
+    let grpc_audit_service = GrpcAuditService::new(player_service.clone(), audit_service);
+    let grpc_chat_service = GrpcChatService::new(player_service.clone(), chat_service, chat_filter_service, evidence_service.clone());
+    let grpc_evidence_service = GrpcEvidenceService::new(
+        player_service.clone(),
+        evidence_service,
+        var_or("EVIDENCE_MAX_BYTES", 10 * 1024 * 1024),
+    );
+    let grpc_auth_service = GrpcAuthenticationService::new(player_service.clone());
+    let grpc_player_service = GrpcPlayerService::new(player_service.clone());
+    let grpc_player_directory_service = GrpcPlayerDirectoryService::new(player_service.clone(), punishment_service.clone(), report_service.clone());
+    let grpc_policy_service = GrpcPolicyService::new(player_service.clone(), policy_service);
+    let grpc_report_service = GrpcReportService::new(player_service.clone(), report_service);
+    let grpc_punishment_service = GrpcPunishmentService::new(player_service, punishment_service, message_service, broadcast_service, alt_service, hold_service);
+
+    let keepalive_interval = Duration::from_secs(var_or("GRPC_KEEPALIVE_INTERVAL_SECONDS", 30));
+    let keepalive_timeout = Duration::from_secs(var_or("GRPC_KEEPALIVE_TIMEOUT_SECONDS", 10));
 
     Server::builder()
+        .http2_keepalive_interval(Some(keepalive_interval))
+        .http2_keepalive_timeout(Some(keepalive_timeout))
+        .tcp_keepalive(Some(keepalive_interval))
         .add_service(ReportServiceServer::new(grpc_report_service))
         .add_service(PunishmentServiceServer::new(grpc_punishment_service))
         .add_service(AuthenticationServiceServer::new(grpc_auth_service))
+        .add_service(PlayerServiceServer::new(grpc_player_service))
+        .add_service(PlayerDirectoryServiceServer::new(grpc_player_directory_service))
+        .add_service(PolicyServiceServer::new(grpc_policy_service))
+        .add_service(AuditServiceServer::new(grpc_audit_service))
+        .add_service(EvidenceServiceServer::new(grpc_evidence_service))
+        .add_service(ChatServiceServer::new(grpc_chat_service))
         .serve(addr).await?;
 
     Ok(())
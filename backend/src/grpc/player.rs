@@ -0,0 +1,170 @@
+use crate::grpc::generated::player_directory_service_server::PlayerDirectoryService as GeneratedPlayerDirectoryService;
+use crate::grpc::generated::player_service_server::PlayerService as GeneratedPlayerService;
+use crate::grpc::generated::{
+    GetPlayerHistoryRequest, GetPlayerHistoryResponse, GetPlayerProfileRequest, GetPlayerProfileResponse,
+    PlayerSearchResult, SearchPlayersRequest, SearchPlayersResponse,
+};
+use crate::models::PlayerCursor;
+use crate::services::{PlayerService, PunishmentService, ReportService};
+use std::str::FromStr;
+use std::sync::Arc;
+use tonic::{Request, Response, Status};
+use uuid::Uuid;
+
+pub struct GrpcPlayerService {
+    player_service: Arc<PlayerService>,
+}
+
+impl GrpcPlayerService {
+    pub fn new(player_service: Arc<PlayerService>) -> Self {
+        Self { player_service }
+    }
+}
+
+#[tonic::async_trait]
+impl GeneratedPlayerService for GrpcPlayerService {
+    async fn get_player_history(
+        &self,
+        request: Request<GetPlayerHistoryRequest>,
+    ) -> Result<Response<GetPlayerHistoryResponse>, Status> {
+        let claims = self.player_service.verify_request(&request).await?;
+        if !claims.staff {
+            return Err(Status::permission_denied("Only staff members can view player history"));
+        }
+
+        let request = request.into_inner();
+        let player_uuid = Uuid::from_str(&request.player_id)
+            .map_err(|e| Status::invalid_argument(format!("Invalid player ID: {}", e)))?;
+
+        let player = self
+            .player_service
+            .get_player_by_uuid(player_uuid)
+            .await?
+            .ok_or_else(|| Status::not_found("Player not found"))?;
+        let name_history = self.player_service.get_name_history(player_uuid).await?;
+        let activity = self.player_service.get_activity(player_uuid).await?;
+
+        Ok(Response::new(GetPlayerHistoryResponse {
+            player_id: player.uuid.to_string(),
+            username: player.username,
+            name_history: name_history.into_iter().map(Into::into).collect(),
+            // Players created before sessions were tracked have no session yet
+            first_seen: Some(activity.first_seen.unwrap_or(player.created_at).timestamp()),
+            last_seen: activity.last_seen.map(|last_seen| last_seen.timestamp()),
+            total_playtime_seconds: activity.total_playtime_seconds,
+            online: activity.online,
+        }))
+    }
+}
+
+pub struct GrpcPlayerDirectoryService {
+    player_service: Arc<PlayerService>,
+    punishment_service: Arc<PunishmentService>,
+    report_service: Arc<ReportService>,
+}
+
+impl GrpcPlayerDirectoryService {
+    const DEFAULT_PAGE_SIZE: u32 = 25;
+    const MAX_PAGE_SIZE: u32 = 100;
+
+    pub fn new(player_service: Arc<PlayerService>, punishment_service: Arc<PunishmentService>, report_service: Arc<ReportService>) -> Self {
+        Self {
+            player_service,
+            punishment_service,
+            report_service,
+        }
+    }
+}
+
+#[tonic::async_trait]
+impl GeneratedPlayerDirectoryService for GrpcPlayerDirectoryService {
+    async fn search_players(
+        &self,
+        request: Request<SearchPlayersRequest>,
+    ) -> Result<Response<SearchPlayersResponse>, Status> {
+        let claims = self.player_service.verify_request(&request).await?;
+        if !claims.staff {
+            return Err(Status::permission_denied("Only staff members can search players"));
+        }
+
+        let request = request.into_inner();
+        let query = request.query.trim();
+
+        if let Ok(player_uuid) = Uuid::parse_str(query) {
+            let player = self.player_service.get_player_by_uuid(player_uuid).await?;
+
+            return Ok(Response::new(SearchPlayersResponse {
+                players: player
+                    .into_iter()
+                    .map(|player| PlayerSearchResult {
+                        player_id: player.uuid.to_string(),
+                        username: player.username,
+                        matched_name: None,
+                        staff: player.staff,
+                    })
+                    .collect(),
+                next_cursor: None,
+            }));
+        }
+
+        let cursor = request.cursor.as_deref().map(PlayerCursor::from_str).transpose()?;
+        let limit = request.limit.unwrap_or(Self::DEFAULT_PAGE_SIZE).clamp(1, Self::MAX_PAGE_SIZE) as usize;
+
+        // Fetching one extra result tells whether there is another page
+        let mut players = self
+            .player_service
+            .search_players(query, cursor.as_ref(), limit as i64 + 1)
+            .await?;
+
+        let next_cursor = if players.len() > limit {
+            players.truncate(limit);
+            players.last().map(|last| PlayerCursor::after(last).to_string())
+        } else {
+            None
+        };
+
+        Ok(Response::new(SearchPlayersResponse {
+            players: players.into_iter().map(Into::into).collect(),
+            next_cursor,
+        }))
+    }
+
+    async fn get_player_profile(
+        &self,
+        request: Request<GetPlayerProfileRequest>,
+    ) -> Result<Response<GetPlayerProfileResponse>, Status> {
+        let claims = self.player_service.verify_request(&request).await?;
+        if !claims.staff {
+            return Err(Status::permission_denied("Only staff members can view player profiles"));
+        }
+
+        let request = request.into_inner();
+        let player_uuid = Uuid::from_str(&request.player_id)
+            .map_err(|e| Status::invalid_argument(format!("Invalid player ID: {}", e)))?;
+
+        let player = self
+            .player_service
+            .get_player_by_uuid(player_uuid)
+            .await?
+            .ok_or_else(|| Status::not_found("Player not found"))?;
+        let punishment_counts = self.punishment_service.get_punishment_counts(player_uuid).await?;
+        let active_punishments = self.punishment_service.get_player_active_punishments(player_uuid).await?;
+        let appeal_counts = self.punishment_service.get_appeal_counts(player_uuid).await?;
+        let report_counts = self.report_service.get_report_counts(player_uuid).await?;
+        let severity_points = self.punishment_service.get_severity_points(player_uuid).await?;
+
+        Ok(Response::new(GetPlayerProfileResponse {
+            player_id: player.uuid.to_string(),
+            username: player.username,
+            staff: player.staff,
+            created_at: player.created_at.timestamp(),
+            punishment_counts: punishment_counts.into_iter().map(Into::into).collect(),
+            active_punishments: active_punishments.into_iter().map(|punishment| punishment.into_details(true)).collect(),
+            appeal_count: appeal_counts.total,
+            open_appeal_count: appeal_counts.open,
+            report_count: report_counts.received,
+            filed_report_count: report_counts.filed,
+            severity_points,
+        }))
+    }
+}
@@ -0,0 +1,326 @@
+use crate::grpc::generated::policy_service_server::PolicyService as GeneratedPolicyService;
+use crate::grpc::generated::{
+    ApprovalSettingsResponse, CategoryResponse, CreateCategoryRequest, DiffPolicyVersionsRequest,
+    DiffPolicyVersionsResponse, GetApprovalSettingsRequest, GetSeveritySettingsRequest, ListCategoriesRequest, ListCategoriesResponse, ListPolicyVersionsRequest,
+    ListPolicyVersionsResponse, ListStaffRolesRequest, ListStaffRolesResponse, PolicyVersionResponse,
+    PublishPolicyVersionRequest, ReorderCategoriesRequest, SetEscalationLadderRequest, SetRoleWeightRequest, SeveritySettingsResponse,
+    StaffRoleResponse, UpdateApprovalSettingsRequest, UpdateCategoryRequest, UpdateRoleLimitsRequest,
+    UpdateSeveritySettingsRequest,
+};
+use crate::models::{
+    diff_ladders, permissions, CategoryUpdate, DecayRule, EscalationStep, NewCategory, NewSeverityThreshold, RoleLimits,
+};
+use crate::services::{PlayerService, PolicyService};
+use std::sync::Arc;
+use time::OffsetDateTime;
+use tonic::{Request, Response, Status};
+
+pub struct GrpcPolicyService {
+    player_service: Arc<PlayerService>,
+    policy_service: Arc<PolicyService>,
+}
+
+impl GrpcPolicyService {
+    pub fn new(player_service: Arc<PlayerService>, policy_service: Arc<PolicyService>) -> Self {
+        Self {
+            player_service,
+            policy_service,
+        }
+    }
+
+    async fn category_response(&self, category_id: i32) -> Result<Response<CategoryResponse>, Status> {
+        let (category, ladder) = self.policy_service.get_category(category_id).await?;
+
+        Ok(Response::new(CategoryResponse {
+            category: Some(category.into_message(ladder)),
+        }))
+    }
+}
+
+#[tonic::async_trait]
+impl GeneratedPolicyService for GrpcPolicyService {
+    async fn list_categories(
+        &self,
+        request: Request<ListCategoriesRequest>,
+    ) -> Result<Response<ListCategoriesResponse>, Status> {
+        self.player_service.verify_permission(&request, permissions::MANAGE_POLICY).await?;
+
+        let categories = self
+            .policy_service
+            .list_categories(request.into_inner().include_inactive)
+            .await?;
+
+        Ok(Response::new(ListCategoriesResponse {
+            categories: categories
+                .into_iter()
+                .map(|(category, ladder)| category.into_message(ladder))
+                .collect(),
+        }))
+    }
+
+    async fn create_category(
+        &self,
+        request: Request<CreateCategoryRequest>,
+    ) -> Result<Response<CategoryResponse>, Status> {
+        let claims = self.player_service.verify_permission(&request, permissions::MANAGE_POLICY).await?;
+
+        let request = request.into_inner();
+        let category = self
+            .policy_service
+            .create_category(
+                claims.sub,
+                NewCategory {
+                    name: request.name,
+                    description: request.description,
+                    color_hex: request.color_hex,
+                },
+            )
+            .await?;
+
+        self.category_response(category.id).await
+    }
+
+    async fn update_category(
+        &self,
+        request: Request<UpdateCategoryRequest>,
+    ) -> Result<Response<CategoryResponse>, Status> {
+        let claims = self.player_service.verify_permission(&request, permissions::MANAGE_POLICY).await?;
+
+        let request = request.into_inner();
+        let decay = request
+            .decay_mode
+            .as_deref()
+            .map(|mode| DecayRule::from_columns(mode, request.decay_days))
+            .transpose()?;
+
+        self.policy_service
+            .update_category(
+                claims.sub,
+                request.category_id,
+                CategoryUpdate {
+                    name: request.name,
+                    description: request.description,
+                    color_hex: request.color_hex,
+                    active: request.active,
+                    decay,
+                    requires_approval: request.requires_approval,
+                },
+            )
+            .await?;
+
+        self.category_response(request.category_id).await
+    }
+
+    async fn reorder_categories(
+        &self,
+        request: Request<ReorderCategoriesRequest>,
+    ) -> Result<Response<ListCategoriesResponse>, Status> {
+        let claims = self.player_service.verify_permission(&request, permissions::MANAGE_POLICY).await?;
+
+        self.policy_service
+            .reorder_categories(claims.sub, &request.into_inner().category_ids)
+            .await?;
+
+        let categories = self.policy_service.list_categories(true).await?;
+
+        Ok(Response::new(ListCategoriesResponse {
+            categories: categories
+                .into_iter()
+                .map(|(category, ladder)| category.into_message(ladder))
+                .collect(),
+        }))
+    }
+
+    async fn set_escalation_ladder(
+        &self,
+        request: Request<SetEscalationLadderRequest>,
+    ) -> Result<Response<PolicyVersionResponse>, Status> {
+        let claims = self.player_service.verify_permission(&request, permissions::MANAGE_POLICY).await?;
+
+        let request = request.into_inner();
+        let steps: Vec<EscalationStep> = request.steps.into_iter().map(Into::into).collect();
+
+        let (draft, ladder) = self
+            .policy_service
+            .set_escalation_ladder(claims.sub, request.category_id, &steps)
+            .await?;
+
+        Ok(Response::new(PolicyVersionResponse {
+            version: Some(draft.into_message(ladder)),
+        }))
+    }
+
+    async fn list_policy_versions(
+        &self,
+        request: Request<ListPolicyVersionsRequest>,
+    ) -> Result<Response<ListPolicyVersionsResponse>, Status> {
+        self.player_service.verify_permission(&request, permissions::MANAGE_POLICY).await?;
+
+        let versions = self
+            .policy_service
+            .list_policy_versions(request.into_inner().category_id)
+            .await?;
+
+        Ok(Response::new(ListPolicyVersionsResponse {
+            versions: versions
+                .into_iter()
+                .map(|(version, ladder)| version.into_message(ladder))
+                .collect(),
+        }))
+    }
+
+    async fn publish_policy_version(
+        &self,
+        request: Request<PublishPolicyVersionRequest>,
+    ) -> Result<Response<PolicyVersionResponse>, Status> {
+        let claims = self.player_service.verify_permission(&request, permissions::MANAGE_POLICY).await?;
+
+        let request = request.into_inner();
+        let activates_at = request
+            .activates_at
+            .map(OffsetDateTime::from_unix_timestamp)
+            .transpose()
+            .map_err(|e| Status::invalid_argument(format!("Invalid activates_at: {}", e)))?;
+
+        let (version, ladder) = self
+            .policy_service
+            .publish_policy_version(claims.sub, request.category_id, activates_at)
+            .await?;
+
+        Ok(Response::new(PolicyVersionResponse {
+            version: Some(version.into_message(ladder)),
+        }))
+    }
+
+    async fn diff_policy_versions(
+        &self,
+        request: Request<DiffPolicyVersionsRequest>,
+    ) -> Result<Response<DiffPolicyVersionsResponse>, Status> {
+        self.player_service.verify_permission(&request, permissions::MANAGE_POLICY).await?;
+
+        let request = request.into_inner();
+        let (from, from_ladder) = self.policy_service.get_policy_version(request.from_version_id).await?;
+        let (to, to_ladder) = self.policy_service.get_policy_version(request.to_version_id).await?;
+
+        if from.category_id != to.category_id {
+            return Err(Status::invalid_argument(format!(
+                "Versions {} and {} belong to different categories, only versions of one category can be compared",
+                from.id, to.id
+            )));
+        }
+
+        let changes = diff_ladders(&from_ladder, &to_ladder);
+
+        Ok(Response::new(DiffPolicyVersionsResponse {
+            from: Some(from.into_message(from_ladder)),
+            to: Some(to.into_message(to_ladder)),
+            changes: changes.into_iter().map(Into::into).collect(),
+        }))
+    }
+
+    async fn get_severity_settings(
+        &self,
+        request: Request<GetSeveritySettingsRequest>,
+    ) -> Result<Response<SeveritySettingsResponse>, Status> {
+        self.player_service.verify_permission(&request, permissions::MANAGE_POLICY).await?;
+
+        let (settings, thresholds) = self.policy_service.get_severity_settings().await?;
+
+        Ok(Response::new(settings.into_message(thresholds)))
+    }
+
+    async fn update_severity_settings(
+        &self,
+        request: Request<UpdateSeveritySettingsRequest>,
+    ) -> Result<Response<SeveritySettingsResponse>, Status> {
+        let claims = self.player_service.verify_permission(&request, permissions::MANAGE_POLICY).await?;
+
+        let request = request.into_inner();
+        let thresholds: Vec<NewSeverityThreshold> = request.thresholds.into_iter().map(Into::into).collect();
+
+        let (settings, thresholds) = self
+            .policy_service
+            .update_severity_settings(claims.sub, request.enabled, request.decay_days, thresholds)
+            .await?;
+
+        Ok(Response::new(settings.into_message(thresholds)))
+    }
+
+    async fn set_role_weight(
+        &self,
+        request: Request<SetRoleWeightRequest>,
+    ) -> Result<Response<StaffRoleResponse>, Status> {
+        let claims = self.player_service.verify_permission(&request, permissions::MANAGE_POLICY).await?;
+
+        let request = request.into_inner();
+        let role = self
+            .policy_service
+            .set_role_weight(claims.sub, request.role_id, request.weight)
+            .await?;
+
+        Ok(Response::new(StaffRoleResponse {
+            role: Some(role.into()),
+        }))
+    }
+
+    async fn get_approval_settings(
+        &self,
+        request: Request<GetApprovalSettingsRequest>,
+    ) -> Result<Response<ApprovalSettingsResponse>, Status> {
+        self.player_service.verify_permission(&request, permissions::MANAGE_POLICY).await?;
+
+        let settings = self.policy_service.get_approval_settings().await?;
+
+        Ok(Response::new(settings.into()))
+    }
+
+    async fn update_approval_settings(
+        &self,
+        request: Request<UpdateApprovalSettingsRequest>,
+    ) -> Result<Response<ApprovalSettingsResponse>, Status> {
+        let claims = self.player_service.verify_permission(&request, permissions::MANAGE_POLICY).await?;
+
+        let settings = self
+            .policy_service
+            .update_approval_settings(claims.sub, request.into_inner().required_types)
+            .await?;
+
+        Ok(Response::new(settings.into()))
+    }
+
+    async fn list_staff_roles(
+        &self,
+        request: Request<ListStaffRolesRequest>,
+    ) -> Result<Response<ListStaffRolesResponse>, Status> {
+        self.player_service.verify_permission(&request, permissions::MANAGE_POLICY).await?;
+
+        let roles = self.policy_service.list_staff_roles().await?;
+
+        Ok(Response::new(ListStaffRolesResponse {
+            roles: roles.into_iter().map(Into::into).collect(),
+        }))
+    }
+
+    async fn update_role_limits(
+        &self,
+        request: Request<UpdateRoleLimitsRequest>,
+    ) -> Result<Response<StaffRoleResponse>, Status> {
+        let claims = self.player_service.verify_permission(&request, permissions::MANAGE_POLICY).await?;
+
+        let request = request.into_inner();
+        let limits = RoleLimits {
+            allowed_types: request.restrict_types.then_some(request.allowed_types),
+            max_duration_minutes: request.max_duration_minutes,
+            daily_limit: request.daily_limit,
+        };
+
+        let role = self
+            .policy_service
+            .update_role_limits(claims.sub, request.role_id, limits)
+            .await?;
+
+        Ok(Response::new(StaffRoleResponse {
+            role: Some(role.into()),
+        }))
+    }
+}
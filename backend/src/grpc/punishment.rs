@@ -1,60 +1,301 @@
+use crate::error::AppError;
 use crate::grpc::generated::punishment_service_server::PunishmentService as GeneratedPunishmentService;
-use crate::grpc::generated::{ChatMessage, DisconnectMessage, GetLivePunishmentsRequest, GetLivePunishmentsResponse, GetPlayerLoginRequest, GetPlayerLoginResponse, Punishment, PunishmentsWithDetails};
-use crate::handler::BroadcastHandler;
-use crate::models::PunishmentWithTemplate;
-use crate::services::{BroadcastService, MessageService, PunishmentService};
+use crate::grpc::generated::{AcknowledgeWarningRequest, AcknowledgeWarningResponse, ChatMessage, CreateHoldRequest, DisconnectMessage, GetAltAccountsRequest, GetAltAccountsResponse, GetHoldSettingsRequest, GetLivePunishmentsRequest, GetLivePunishmentsResponse, GetPlayerLoginRequest, GetPlayerLoginResponse, GetPunishmentRequest, GetPunishmentResponse, HoldResponse, HoldSettingsResponse, IssuePunishmentRequest, IssuePunishmentResponse, ListPunishmentApprovalsRequest, ListPunishmentApprovalsResponse, ReleaseHoldRequest, ReviewPunishmentApprovalRequest, ReviewPunishmentApprovalResponse, RevokePunishmentRequest, RevokePunishmentResponse, UpdateHoldSettingsRequest, UpdatePunishmentRequest, UpdatePunishmentResponse, ListPunishmentRevisionsRequest, ListPunishmentRevisionsResponse, ListPunishmentsRequest, ListPunishmentsResponse, Pong, PreviewPunishmentRequest, PreviewPunishmentResponse, PunishmentScope, Punishment, PunishmentsWithDetails};
+use crate::models::{permissions, BannedAlt, Hold, IssueOutcome, LiveEvent, NewPunishment, PunishmentApproval, PunishmentCursor, PunishmentEvent, PunishmentFilter, PunishmentSort, PunishmentStatus, PunishmentUpdate, ServerIdentity, StaffAlert};
+use crate::services::{AltService, BanEvasionAction, BroadcastService, HoldService, MessageService, PlayerService, PunishmentService};
+use sqlx::types::ipnetwork::IpNetwork;
+use std::net::IpAddr;
 use std::str::FromStr;
 use std::sync::Arc;
-use tokio::sync::mpsc;
+use time::OffsetDateTime;
+use tokio::sync::broadcast::error::RecvError;
+use tokio::sync::{mpsc, RwLock};
 use tokio_stream::wrappers::ReceiverStream;
 use tokio_stream::StreamExt;
 use tonic::{Request, Response, Status, Streaming};
 use uuid::Uuid;
 
 pub struct GrpcPunishmentService {
+    player_service: Arc<PlayerService>,
     punishment_service: Arc<PunishmentService>,
     message_service: Arc<MessageService>,
     broadcast_service: Arc<BroadcastService>,
+    alt_service: Arc<AltService>,
+    hold_service: Arc<HoldService>,
 }
 
 impl GrpcPunishmentService {
+    const DEFAULT_PAGE_SIZE: u32 = 50;
+    const MAX_PAGE_SIZE: u32 = 200;
+
     pub fn new(
+        player_service: Arc<PlayerService>,
         punishment_service: Arc<PunishmentService>,
         message_service: Arc<MessageService>,
         broadcast_service: Arc<BroadcastService>,
+        alt_service: Arc<AltService>,
+        hold_service: Arc<HoldService>,
     ) -> Self {
         Self {
+            player_service,
             punishment_service,
             message_service,
             broadcast_service,
+            alt_service,
+            hold_service,
         }
     }
 }
 
 impl GrpcPunishmentService {
+    #[allow(clippy::too_many_arguments)]
     async fn handle_player_status_change(
-        broadcast_handler: &BroadcastHandler<Uuid, PunishmentWithTemplate>,
+        broadcast_service: &BroadcastService,
+        player_service: &PlayerService,
+        punishment_service: &PunishmentService,
+        message_service: &MessageService,
+        hold_service: &HoldService,
+        tx: &mpsc::Sender<Result<GetLivePunishmentsResponse, Status>>,
         identifier: &Uuid,
         request: &GetLivePunishmentsRequest,
     ) -> Result<(), String> {
         let player_id = Uuid::from_str(&request.player_id)
             .map_err(|e| format!("Invalid player ID: {}", e))?;
+        let broadcast_handler = &broadcast_service.live;
 
-        if request.online {
-            Ok(broadcast_handler.add_key_to_listener(identifier, player_id).await)
-        } else {
-            Ok(broadcast_handler.remove_key_from_listener(identifier, player_id).await)
+        // Only the proxy sees players join and leave the network, backend servers report server switches
+        if request.proxy {
+            let session = if request.online {
+                player_service.start_session(player_id, *identifier).await
+            } else {
+                player_service.end_session(player_id).await
+            };
+            session.map_err(|e| format!("Failed to record player session: {}", e))?;
+
+            // Leaving the network while held counts as dodging the screenshare
+            if !request.online {
+                match hold_service.handle_disconnect(player_id).await {
+                    Ok(Some(disconnect)) => {
+                        Self::alert_hold_disconnect(player_service, broadcast_service, &disconnect.hold, disconnect.approval.is_some()).await;
+                        if let Some(approval) = &disconnect.approval {
+                            Self::notify_approvers(player_service, broadcast_service, approval).await;
+                        }
+                    }
+                    Ok(None) => {}
+                    Err(e) => eprintln!("Error ending the hold of disconnected player {}: {}", player_id, e),
+                }
+            }
+        }
+
+        if !request.online {
+            broadcast_handler.remove_key_from_listener(identifier, player_id).await;
+            return Ok(());
+        }
+
+        broadcast_handler.add_key_to_listener(identifier, player_id).await;
+
+        // Servers the held player switches to have to freeze them as well
+        let hold = hold_service
+            .get_open_hold(player_id)
+            .await
+            .map_err(|e| format!("Failed to get open hold: {}", e))?;
+        if let Some(hold) = hold {
+            let response = GetLivePunishmentsResponse {
+                hold: Some(hold.into()),
+                ..Default::default()
+            };
+            if tx.send(Ok(response)).await.is_err() {
+                return Ok(());
+            }
+        }
+
+        // Subscribing before replaying can deliver an event twice, but never loses one
+        if let Some(resume_from) = request.resume_from {
+            let server = ServerIdentity {
+                proxy: request.proxy,
+                server_group: request.server_group.clone(),
+            };
+            let events = punishment_service
+                .get_missed_events(player_id, resume_from)
+                .await
+                .map_err(|e| format!("Failed to get missed events: {}", e))?;
+
+            for event in events {
+                let punishment = &event.punishment;
+                if !server.covers(&punishment.scope, punishment.server_group.as_deref()) {
+                    continue;
+                }
+
+                let response = Self::create_punishment_response(message_service, &event).await?;
+                if tx.send(Ok(response)).await.is_err() {
+                    break;
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Alerts online staff when the player shares an address with a banned account and,
+    /// depending on the configured action, returns the message to deny the login with.
+    async fn check_ban_evasion(&self, player_uuid: Uuid) -> Result<Option<DisconnectMessage>, Status> {
+        let action = self.alt_service.ban_evasion_action();
+        if action == BanEvasionAction::Off {
+            return Ok(None);
+        }
+
+        let banned_alts = self
+            .alt_service
+            .get_banned_alts(player_uuid)
+            .await
+            .map_err(|e| Status::internal(format!("Failed to get banned alts: {}", e)))?;
+
+        // A login denied for evasion lasts as long as the longest ban, permanent ones first
+        let Some(longest_ban) = banned_alts
+            .iter()
+            .map(|alt| &alt.ban)
+            .max_by_key(|ban| (ban.expires_at.is_none(), ban.expires_at))
+        else {
+            return Ok(None);
+        };
+
+        let alert = StaffAlert {
+            alert_type: "ban_evasion".to_string(),
+            message: format!(
+                "Possible ban evasion: {} shares an address with banned account(s) {}",
+                player_uuid,
+                Self::describe_alts(&banned_alts),
+            ),
+            player_uuid,
+            related_player_uuids: banned_alts.iter().map(|alt| alt.ban.player_uuid).collect(),
+            created_at: OffsetDateTime::now_utc(),
+        };
+
+        match self.player_service.get_staff_uuids().await {
+            Ok(staff) => self.broadcast_service.send_staff_alert(&staff, alert).await,
+            Err(e) => eprintln!("Error sending ban evasion alert: {}", e),
         }
+
+        if action != BanEvasionAction::Deny {
+            return Ok(None);
+        }
+
+        let message = self
+            .message_service
+            .get_ban_message(
+                "Ban evasion: this account is linked to a banned account",
+                OffsetDateTime::now_utc(),
+                longest_ban.expires_at,
+            )
+            .await
+            .map_err(|e| Status::internal(format!("Failed to get ban message: {}", e)))?;
+
+        Ok(Some(DisconnectMessage { message }))
+    }
+
+    /// Tells the online staff members who can approve it about a queued punishment.
+    async fn notify_approvers(player_service: &PlayerService, broadcast_service: &BroadcastService, approval: &PunishmentApproval) {
+        let approvers = match player_service.get_staff_uuids_with_permission(permissions::APPROVE_PUNISHMENTS).await {
+            Ok(approvers) => approvers,
+            Err(e) => {
+                eprintln!("Error sending approval request alert: {}", e);
+                return;
+            }
+        };
+        let approvers: Vec<Uuid> = approvers.into_iter().filter(|uuid| *uuid != approval.requested_by).collect();
+
+        let alert = StaffAlert {
+            alert_type: "approval_request".to_string(),
+            message: format!(
+                "{} requested approval for a {} of {}: {}",
+                approval.requested_by, approval.punishment_type, approval.player_uuid, approval.approval_reason
+            ),
+            player_uuid: approval.player_uuid,
+            related_player_uuids: vec![approval.requested_by],
+            created_at: OffsetDateTime::now_utc(),
+        };
+
+        broadcast_service.send_staff_alert(&approvers, alert).await;
+    }
+
+    /// Tells the staff member who requested it how their approval request was decided.
+    async fn notify_requester(&self, approval: &PunishmentApproval) {
+        let Some(reviewer) = approval.reviewed_by else {
+            return;
+        };
+
+        let alert = StaffAlert {
+            alert_type: "approval_reviewed".to_string(),
+            message: format!(
+                "Your {} of {} was {} by {}{}",
+                approval.punishment_type,
+                approval.player_uuid,
+                approval.status,
+                reviewer,
+                approval.review_comment.as_deref().map(|comment| format!(": {}", comment)).unwrap_or_default()
+            ),
+            player_uuid: approval.player_uuid,
+            related_player_uuids: vec![reviewer],
+            created_at: OffsetDateTime::now_utc(),
+        };
+
+        self.broadcast_service.send_staff_alert(&[approval.requested_by], alert).await;
+    }
+
+    /// Tells the online staff that a held player disconnected before being released, so it is not
+    /// missed when the staff member who held them is offline.
+    async fn alert_hold_disconnect(player_service: &PlayerService, broadcast_service: &BroadcastService, hold: &Hold, queued: bool) {
+        let message = match (hold.outcome.as_deref(), hold.punishment_id) {
+            (Some("punished"), Some(id)) => format!("{} was disconnected by punishment {} while held", hold.player_uuid, id),
+            (_, Some(id)) => format!("{} disconnected while held, punishment {} was issued", hold.player_uuid, id),
+            (_, None) if queued => format!("{} disconnected while held, the punishment awaits approval", hold.player_uuid),
+            (_, None) => format!("{} disconnected while held", hold.player_uuid),
+        };
+
+        let alert = StaffAlert {
+            alert_type: "hold_disconnect".to_string(),
+            message,
+            player_uuid: hold.player_uuid,
+            related_player_uuids: vec![hold.staff_uuid],
+            created_at: OffsetDateTime::now_utc(),
+        };
+
+        match player_service.get_staff_uuids().await {
+            Ok(staff) => broadcast_service.send_staff_alert(&staff, alert).await,
+            Err(e) => eprintln!("Error sending hold disconnect alert: {}", e),
+        }
+    }
+
+    fn describe_alts(banned_alts: &[BannedAlt]) -> String {
+        banned_alts
+            .iter()
+            .map(|alt| alt.username.clone().unwrap_or_else(|| alt.ban.player_uuid.to_string()))
+            .collect::<Vec<_>>()
+            .join(", ")
     }
 
     async fn create_punishment_response(
         message_service: &MessageService,
-        punishment: &PunishmentWithTemplate,
+        event: &PunishmentEvent,
     ) -> Result<GetLivePunishmentsResponse, String> {
+        let punishment = &event.punishment;
         let punishment_type = &punishment.punishment_type;
         let reason = &punishment.reason;
+        // Revoked and expired punishments are only updated, the player must not be told about them again
+        let notifies_player = event.notifies_player(OffsetDateTime::now_utc());
 
-        let disconnect_message = if punishment_type.contains("ban") || punishment_type.contains("kick") {
+        // A kick disconnects the player once, when it is issued. Later changes such as an edited
+        // reason must not kick them again.
+        let disconnect_message = if !notifies_player {
+            None
+        } else if punishment_type == "kick" && event.issued {
+            Some(DisconnectMessage {
+                message: message_service
+                    .get_kick_message(reason)
+                    .await
+                    .map_err(|e| format!("Failed to get kick message: {}", e))?,
+            })
+        } else if punishment_type.contains("ban") {
             Some(DisconnectMessage {
                 message: message_service
                     .get_ban_message(reason, punishment.issued_at, punishment.expires_at)
@@ -65,15 +306,15 @@ impl GrpcPunishmentService {
             None
         };
 
-        let chat_message = if punishment_type.contains("mute") || punishment_type.contains("warn") {
-            Some(ChatMessage {
-                message: message_service
-                    .get_mute_message(reason, punishment.expires_at)
-                    .await
-                    .map_err(|e| format!("Failed to get mute message: {}", e))?,
-            })
-        } else {
+        // Warnings are told once, later changes such as the acknowledgement must not repeat them
+        let chat_message = if !notifies_player || (punishment_type == "warn" && !event.issued) {
             None
+        } else {
+            message_service
+                .get_chat_message(punishment_type, reason, punishment.expires_at)
+                .await
+                .map_err(|e| format!("Failed to get mute message: {}", e))?
+                .map(|message| ChatMessage { message })
         };
 
         Ok(GetLivePunishmentsResponse {
@@ -82,7 +323,10 @@ impl GrpcPunishmentService {
                 disconnect_message,
                 chat_message,
                 punishment: vec![punishment.clone().into()],
+                shadow_muted: punishment_type == "shadow_mute" && notifies_player,
             }),
+            sequence: event.sequence,
+            ..Default::default()
         })
     }
 }
@@ -93,20 +337,59 @@ impl GeneratedPunishmentService for GrpcPunishmentService {
         &self,
         request: Request<GetPlayerLoginRequest>,
     ) -> Result<Response<GetPlayerLoginResponse>, Status> {
+        // The login records the username and address of the player, so only servers may report one
+        self.player_service.verify_server(&request)?;
+
         let request = request.into_inner();
+        let server = ServerIdentity {
+            proxy: request.proxy,
+            server_group: request.server_group.clone(),
+        };
+        let ip_address = request
+            .ip_address
+            .as_deref()
+            .map(IpAddr::from_str)
+            .transpose()
+            .map_err(|e| Status::invalid_argument(format!("Invalid IP address: {}", e)))?;
+
+        let player_uuid = Uuid::from_str(&request.player_id)
+            .map_err(|e| Status::invalid_argument(format!("Invalid player ID: {}", e)))?;
+
+        // A name the backend cannot store must not lock the player out, their login is checked regardless
+        if let Some(username) = &request.username {
+            match self.player_service.record_login(player_uuid, username).await {
+                Err(AppError::CustomValidationError(e)) => eprintln!("Not recording the username of {}: {}", player_uuid, e),
+                result => result?,
+            }
+        }
+
+        if let Some(ip_address) = ip_address {
+            self.player_service
+                .record_player_address(player_uuid, ip_address)
+                .await
+                .map_err(|e| Status::internal(format!("Failed to record player address: {}", e)))?;
+        }
+
+        let sequence = self
+            .punishment_service
+            .get_latest_event_sequence()
+            .await
+            .map_err(|e| Status::internal(format!("Failed to get latest event sequence: {}", e)))?;
+
         let punishments = self
             .punishment_service
-            .get_active_punishments(&request.player_id)
+            .get_active_punishments(&request.player_id, ip_address, &server)
             .await
             .map_err(|e| Status::internal(format!("Failed to get active punishments: {}", e)))?;
 
         let grpc_punishments: Vec<Punishment> = punishments
             .iter()
-            .map(|p| p.clone().into())
+            .map(|p| p.clone().into_login_message(player_uuid))
             .collect();
 
         let mut chat_message = None;
         let mut disconnect_message = None;
+        let shadow_muted = punishments.iter().any(|punishment| punishment.punishment_type == "shadow_mute");
 
         for punishment in &punishments {
             let punishment_type = &punishment.punishment_type;
@@ -121,16 +404,13 @@ impl GeneratedPunishmentService for GrpcPunishmentService {
                 });
             }
 
-            if chat_message.is_none()
-                && (punishment_type.contains("mute") || punishment_type.contains("warn"))
-            {
-                chat_message = Some(ChatMessage {
-                    message: self
-                        .message_service
-                        .get_mute_message(&punishment.reason, punishment.expires_at)
-                        .await
-                        .map_err(|e| Status::internal(format!("Failed to get mute message: {}", e)))?,
-                });
+            if chat_message.is_none() {
+                chat_message = self
+                    .message_service
+                    .get_chat_message(punishment_type, &punishment.reason, punishment.expires_at)
+                    .await
+                    .map_err(|e| Status::internal(format!("Failed to get mute message: {}", e)))?
+                    .map(|message| ChatMessage { message });
             }
 
             if disconnect_message.is_some() {
@@ -138,13 +418,27 @@ impl GeneratedPunishmentService for GrpcPunishmentService {
             }
         }
 
+        // Evasion is only detectable once the address of this login is known
+        if disconnect_message.is_none() && ip_address.is_some() {
+            disconnect_message = self.check_ban_evasion(player_uuid).await?;
+        }
+
+        let hold = self
+            .hold_service
+            .get_open_hold(player_uuid)
+            .await
+            .map_err(|e| Status::internal(format!("Failed to get open hold: {}", e)))?;
+
         Ok(Response::new(GetPlayerLoginResponse {
             punishments: Some(PunishmentsWithDetails {
                 player_id: request.player_id,
                 chat_message,
                 disconnect_message,
                 punishment: grpc_punishments,
+                shadow_muted,
             }),
+            sequence,
+            hold: hold.map(Into::into),
         }))
     }
 
@@ -154,29 +448,64 @@ impl GeneratedPunishmentService for GrpcPunishmentService {
         &self,
         request: Request<Streaming<GetLivePunishmentsRequest>>,
     ) -> Result<Response<Self::GetLivePunishmentsStream>, Status> {
+        // The stream reports sessions and relays staff alerts, so only servers may open one
+        self.player_service.verify_server(&request)?;
+
         let (tx, rx) = mpsc::channel(128);
         let identifier = Uuid::new_v4();
         let message_service = Arc::clone(&self.message_service);
-        let broadcast_handler = self.broadcast_service.punishment.clone();
+        let punishment_service = Arc::clone(&self.punishment_service);
+        let player_service = Arc::clone(&self.player_service);
+        let hold_service = Arc::clone(&self.hold_service);
+        let broadcast_service = Arc::clone(&self.broadcast_service);
+        let broadcast_handler = self.broadcast_service.live.clone();
+        // Updated from the requests so the broadcast task only forwards punishments in the caller's scope
+        let server = Arc::new(RwLock::new(ServerIdentity::default()));
 
         let mut broadcast_rx = broadcast_handler.start_broadcast_listener(&identifier).await;
         let mut request_stream = request.into_inner();
 
         let broadcast_handler_for_requests = broadcast_handler.clone();
-        let tx_for_cleanup = tx.clone();
+        let message_service_for_requests = Arc::clone(&message_service);
+        let tx_for_requests = tx.clone();
+        let server_for_requests = Arc::clone(&server);
+        let player_service_for_requests = Arc::clone(&player_service);
         tokio::spawn(async move {
             while let Some(result) = request_stream.next().await {
                 match result {
                     Ok(req) => {
+                        if let Some(ping) = req.ping {
+                            broadcast_handler_for_requests.record_heartbeat(&identifier).await;
+
+                            let pong = GetLivePunishmentsResponse {
+                                pong: Some(Pong { nonce: ping.nonce }),
+                                ..Default::default()
+                            };
+                            if tx_for_requests.send(Ok(pong)).await.is_err() {
+                                break;
+                            }
+                            continue;
+                        }
+
+                        *server_for_requests.write().await = ServerIdentity {
+                            proxy: req.proxy,
+                            server_group: req.server_group.clone(),
+                        };
+
                         if let Err(e) = Self::handle_player_status_change(
-                            &broadcast_handler_for_requests,
+                            &broadcast_service,
+                            &player_service_for_requests,
+                            &punishment_service,
+                            &message_service_for_requests,
+                            &hold_service,
+                            &tx_for_requests,
                             &identifier,
                             &req,
                         )
                         .await
                         {
                             eprintln!("Error handling player status change: {}", e);
-                            let _ = tx_for_cleanup
+                            let _ = tx_for_requests
                                 .send(Err(Status::internal("Failed to update player status")))
                                 .await;
                             break;
@@ -184,7 +513,7 @@ impl GeneratedPunishmentService for GrpcPunishmentService {
                     }
                     Err(e) => {
                         eprintln!("Error in request stream: {}", e);
-                        let _ = tx_for_cleanup
+                        let _ = tx_for_requests
                             .send(Err(Status::internal("Request stream error")))
                             .await;
                         break;
@@ -195,8 +524,59 @@ impl GeneratedPunishmentService for GrpcPunishmentService {
 
         let tx_for_broadcast = tx.clone();
         tokio::spawn(async move {
-            while let Ok(event) = broadcast_rx.recv().await {
-                match Self::create_punishment_response(&message_service, &event.value).await {
+            loop {
+                let event = match broadcast_rx.recv().await {
+                    Ok(event) => event,
+                    Err(RecvError::Closed) => {
+                        // The listener was evicted after missing heartbeats
+                        let _ = tx_for_broadcast
+                            .send(Err(Status::unavailable("Live stream closed after missed heartbeats")))
+                            .await;
+                        break;
+                    }
+                    Err(RecvError::Lagged(missed)) => {
+                        // Closing the stream makes the server reconnect and replay from its last sequence
+                        let _ = tx_for_broadcast
+                            .send(Err(Status::data_loss(format!(
+                                "Live stream fell {} events behind, reconnect with resume_from",
+                                missed
+                            ))))
+                            .await;
+                        break;
+                    }
+                };
+
+                let event = match event.value {
+                    LiveEvent::Punishment(event) => *event,
+                    LiveEvent::StaffAlert(alert) => {
+                        let response = GetLivePunishmentsResponse {
+                            staff_alert: Some(alert.to_message(event.key)),
+                            ..Default::default()
+                        };
+                        if tx_for_broadcast.send(Ok(response)).await.is_err() {
+                            break;
+                        }
+                        continue;
+                    }
+                    // Holds freeze the player network-wide, whatever the server's scope
+                    LiveEvent::Hold(hold) => {
+                        let response = GetLivePunishmentsResponse {
+                            hold: Some((*hold).into()),
+                            ..Default::default()
+                        };
+                        if tx_for_broadcast.send(Ok(response)).await.is_err() {
+                            break;
+                        }
+                        continue;
+                    }
+                };
+
+                let punishment = &event.punishment;
+                if !server.read().await.covers(&punishment.scope, punishment.server_group.as_deref()) {
+                    continue;
+                }
+
+                match Self::create_punishment_response(&message_service, &event).await {
                     Ok(response) => {
                         if tx_for_broadcast.send(Ok(response)).await.is_err() {
                             break;
@@ -217,8 +597,463 @@ impl GeneratedPunishmentService for GrpcPunishmentService {
         tokio::spawn(async move {
             tx.closed().await;
             broadcast_handler.remove_listener(&identifier).await;
+
+            if let Err(e) = player_service.end_stream_sessions(identifier).await {
+                eprintln!("Error ending sessions of closed live stream: {}", e);
+            }
         });
 
         Ok(Response::new(ReceiverStream::new(rx)))
     }
-}
\ No newline at end of file
+
+    async fn issue_punishment(
+        &self,
+        request: Request<IssuePunishmentRequest>,
+    ) -> Result<Response<IssuePunishmentResponse>, Status> {
+        let claims = self.player_service.verify_request(&request).await?;
+        if !claims.staff {
+            return Err(Status::permission_denied("Only staff members can issue punishments"));
+        }
+
+        let request = request.into_inner();
+        let player_uuid = Uuid::from_str(&request.player_id)
+            .map_err(|e| Status::invalid_argument(format!("Invalid player ID: {}", e)))?;
+        let (scope, server_group) = match request.scope {
+            Some(scope) => (Some(scope.r#type), scope.server_group),
+            None => (None, None),
+        };
+        let ip_range = request
+            .ip_range
+            .as_deref()
+            .map(IpNetwork::from_str)
+            .transpose()
+            .map_err(|e| Status::invalid_argument(format!("Invalid IP range: {}", e)))?;
+
+        let outcome = self
+            .punishment_service
+            .issue_punishment(
+                claims.sub,
+                NewPunishment {
+                    player_uuid,
+                    category_id: request.category_id,
+                    reason: request.reason,
+                    evidence: request.evidence,
+                    note: request.note,
+                    scope,
+                    server_group,
+                    ip_range,
+                },
+                request.request_approval,
+            )
+            .await?;
+
+        let response = match outcome {
+            IssueOutcome::Issued(issued) => {
+                if let Some(approval) = &issued.automatic_ban_approval {
+                    Self::notify_approvers(&self.player_service, &self.broadcast_service, approval).await;
+                }
+
+                IssuePunishmentResponse {
+                    punishment: Some(issued.punishment.clone().into()),
+                    details: Some(issued.punishment.into_details(true)),
+                    automatic_ban: issued.automatic_ban.map(|ban| ban.into_details(true)),
+                    approval: None,
+                    automatic_ban_approval: issued.automatic_ban_approval.map(Into::into),
+                }
+            }
+            IssueOutcome::Queued(approval) => {
+                Self::notify_approvers(&self.player_service, &self.broadcast_service, &approval).await;
+
+                IssuePunishmentResponse {
+                    approval: Some((*approval).into()),
+                    ..Default::default()
+                }
+            }
+        };
+
+        Ok(Response::new(response))
+    }
+
+    async fn revoke_punishment(
+        &self,
+        request: Request<RevokePunishmentRequest>,
+    ) -> Result<Response<RevokePunishmentResponse>, Status> {
+        let claims = self.player_service.verify_request(&request).await?;
+        if !claims.staff {
+            return Err(Status::permission_denied("Only staff members can revoke punishments"));
+        }
+
+        let request = request.into_inner();
+        let punishment_id = Uuid::from_str(&request.punishment_id)
+            .map_err(|e| Status::invalid_argument(format!("Invalid punishment ID: {}", e)))?;
+
+        let punishment = self
+            .punishment_service
+            .revoke_punishment(claims.sub, punishment_id, request.reason)
+            .await?;
+
+        Ok(Response::new(RevokePunishmentResponse {
+            punishment: Some(punishment.into_details(true)),
+        }))
+    }
+
+    async fn acknowledge_warning(
+        &self,
+        request: Request<AcknowledgeWarningRequest>,
+    ) -> Result<Response<AcknowledgeWarningResponse>, Status> {
+        self.player_service.verify_server(&request)?;
+
+        let request = request.into_inner();
+        let player_uuid = Uuid::from_str(&request.player_id)
+            .map_err(|e| Status::invalid_argument(format!("Invalid player ID: {}", e)))?;
+        let punishment_id = Uuid::from_str(&request.punishment_id)
+            .map_err(|e| Status::invalid_argument(format!("Invalid punishment ID: {}", e)))?;
+
+        let acknowledged_at = self
+            .punishment_service
+            .acknowledge_warning(player_uuid, punishment_id)
+            .await?;
+
+        Ok(Response::new(AcknowledgeWarningResponse {
+            acknowledged_at: acknowledged_at.unix_timestamp(),
+        }))
+    }
+
+    async fn create_hold(
+        &self,
+        request: Request<CreateHoldRequest>,
+    ) -> Result<Response<HoldResponse>, Status> {
+        let claims = self.player_service.verify_request(&request).await?;
+        if !claims.staff {
+            return Err(Status::permission_denied("Only staff members can hold players"));
+        }
+
+        let request = request.into_inner();
+        let player_uuid = Uuid::from_str(&request.player_id)
+            .map_err(|e| Status::invalid_argument(format!("Invalid player ID: {}", e)))?;
+        let reason = request.reason.filter(|reason| !reason.trim().is_empty());
+
+        let hold = self.hold_service.create_hold(claims.sub, player_uuid, reason).await?;
+        let _ = self.broadcast_service.live.send_event(player_uuid, LiveEvent::Hold(Box::new(hold.clone()))).await;
+
+        Ok(Response::new(HoldResponse {
+            hold: Some(hold.into()),
+        }))
+    }
+
+    async fn release_hold(
+        &self,
+        request: Request<ReleaseHoldRequest>,
+    ) -> Result<Response<HoldResponse>, Status> {
+        let claims = self.player_service.verify_request(&request).await?;
+        if !claims.staff {
+            return Err(Status::permission_denied("Only staff members can release held players"));
+        }
+
+        let player_uuid = Uuid::from_str(&request.into_inner().player_id)
+            .map_err(|e| Status::invalid_argument(format!("Invalid player ID: {}", e)))?;
+
+        let hold = self.hold_service.release_hold(claims.sub, player_uuid).await?;
+        let _ = self.broadcast_service.live.send_event(player_uuid, LiveEvent::Hold(Box::new(hold.clone()))).await;
+
+        Ok(Response::new(HoldResponse {
+            hold: Some(hold.into()),
+        }))
+    }
+
+    async fn get_hold_settings(
+        &self,
+        request: Request<GetHoldSettingsRequest>,
+    ) -> Result<Response<HoldSettingsResponse>, Status> {
+        self.player_service.verify_permission(&request, permissions::MANAGE_POLICY).await?;
+
+        let settings = self.hold_service.get_hold_settings().await?;
+
+        Ok(Response::new(settings.into()))
+    }
+
+    async fn update_hold_settings(
+        &self,
+        request: Request<UpdateHoldSettingsRequest>,
+    ) -> Result<Response<HoldSettingsResponse>, Status> {
+        let claims = self.player_service.verify_permission(&request, permissions::MANAGE_POLICY).await?;
+
+        let settings = self
+            .hold_service
+            .update_hold_settings(claims.sub, request.into_inner().disconnect_category_id)
+            .await?;
+
+        Ok(Response::new(settings.into()))
+    }
+
+    async fn update_punishment(
+        &self,
+        request: Request<UpdatePunishmentRequest>,
+    ) -> Result<Response<UpdatePunishmentResponse>, Status> {
+        let claims = self.player_service.verify_request(&request).await?;
+        if !claims.staff {
+            return Err(Status::permission_denied("Only staff members can edit punishments"));
+        }
+
+        let request = request.into_inner();
+        let punishment_id = Uuid::from_str(&request.punishment_id)
+            .map_err(|e| Status::invalid_argument(format!("Invalid punishment ID: {}", e)))?;
+        let clear_if_empty = |value: String| Some(value).filter(|value| !value.trim().is_empty());
+
+        let update = PunishmentUpdate {
+            reason: request.reason,
+            evidence: request.evidence.map(clear_if_empty),
+            note: request.note.map(clear_if_empty),
+            expires_at: request
+                .expires_at
+                .map(OffsetDateTime::from_unix_timestamp)
+                .transpose()
+                .map_err(|e| Status::invalid_argument(format!("Invalid expires_at: {}", e)))?,
+        };
+
+        let (punishment, revision) = self
+            .punishment_service
+            .update_punishment(claims.sub, punishment_id, update)
+            .await?;
+
+        Ok(Response::new(UpdatePunishmentResponse {
+            punishment: Some(punishment.into_details(true)),
+            revision: Some(revision.into()),
+        }))
+    }
+
+    async fn list_punishment_revisions(
+        &self,
+        request: Request<ListPunishmentRevisionsRequest>,
+    ) -> Result<Response<ListPunishmentRevisionsResponse>, Status> {
+        let claims = self.player_service.verify_request(&request).await?;
+        if !claims.staff {
+            return Err(Status::permission_denied("Only staff members can list punishment revisions"));
+        }
+
+        let punishment_id = Uuid::from_str(&request.into_inner().punishment_id)
+            .map_err(|e| Status::invalid_argument(format!("Invalid punishment ID: {}", e)))?;
+        let revisions = self.punishment_service.list_revisions(punishment_id).await?;
+
+        Ok(Response::new(ListPunishmentRevisionsResponse {
+            revisions: revisions.into_iter().map(Into::into).collect(),
+        }))
+    }
+
+    async fn list_punishment_approvals(
+        &self,
+        request: Request<ListPunishmentApprovalsRequest>,
+    ) -> Result<Response<ListPunishmentApprovalsResponse>, Status> {
+        self.player_service.verify_permission(&request, permissions::APPROVE_PUNISHMENTS).await?;
+
+        let request = request.into_inner();
+        let approvals = self
+            .punishment_service
+            .list_approvals(request.status.as_deref().unwrap_or("pending"))
+            .await?;
+
+        Ok(Response::new(ListPunishmentApprovalsResponse {
+            approvals: approvals.into_iter().map(Into::into).collect(),
+        }))
+    }
+
+    async fn approve_punishment(
+        &self,
+        request: Request<ReviewPunishmentApprovalRequest>,
+    ) -> Result<Response<ReviewPunishmentApprovalResponse>, Status> {
+        let claims = self.player_service.verify_permission(&request, permissions::APPROVE_PUNISHMENTS).await?;
+
+        let request = request.into_inner();
+        let approval_id = Uuid::from_str(&request.approval_id)
+            .map_err(|e| Status::invalid_argument(format!("Invalid approval ID: {}", e)))?;
+
+        // The issued punishment reaches the player's proxy through the punishment event relay
+        let (approval, issued) = self
+            .punishment_service
+            .approve_punishment(claims.sub, approval_id, request.comment)
+            .await?;
+        self.notify_requester(&approval).await;
+        if let Some(automatic_ban_approval) = &issued.automatic_ban_approval {
+            Self::notify_approvers(&self.player_service, &self.broadcast_service, automatic_ban_approval).await;
+        }
+
+        Ok(Response::new(ReviewPunishmentApprovalResponse {
+            approval: Some(approval.into()),
+            punishment: Some(issued.punishment.into_details(true)),
+            automatic_ban: issued.automatic_ban.map(|ban| ban.into_details(true)),
+            automatic_ban_approval: issued.automatic_ban_approval.map(Into::into),
+        }))
+    }
+
+    async fn reject_punishment(
+        &self,
+        request: Request<ReviewPunishmentApprovalRequest>,
+    ) -> Result<Response<ReviewPunishmentApprovalResponse>, Status> {
+        let claims = self.player_service.verify_permission(&request, permissions::APPROVE_PUNISHMENTS).await?;
+
+        let request = request.into_inner();
+        let approval_id = Uuid::from_str(&request.approval_id)
+            .map_err(|e| Status::invalid_argument(format!("Invalid approval ID: {}", e)))?;
+
+        let approval = self
+            .punishment_service
+            .reject_punishment(claims.sub, approval_id, request.comment.unwrap_or_default())
+            .await?;
+        self.notify_requester(&approval).await;
+
+        Ok(Response::new(ReviewPunishmentApprovalResponse {
+            approval: Some(approval.into()),
+            punishment: None,
+            automatic_ban: None,
+            automatic_ban_approval: None,
+        }))
+    }
+
+    async fn preview_punishment(
+        &self,
+        request: Request<PreviewPunishmentRequest>,
+    ) -> Result<Response<PreviewPunishmentResponse>, Status> {
+        let claims = self.player_service.verify_request(&request).await?;
+        if !claims.staff {
+            return Err(Status::permission_denied("Only staff members can preview punishments"));
+        }
+
+        let request = request.into_inner();
+        let player_uuid = Uuid::from_str(&request.player_id)
+            .map_err(|e| Status::invalid_argument(format!("Invalid player ID: {}", e)))?;
+
+        let plan = self
+            .punishment_service
+            .preview_punishment(player_uuid, request.category_id)
+            .await?;
+        let explanation = plan.explanation();
+        let template = plan.template;
+        let severity = plan.severity;
+
+        Ok(Response::new(PreviewPunishmentResponse {
+            offense_number: template.offense_number,
+            r#type: template.punishment_type,
+            duration_minutes: template.duration_minutes,
+            reason_template: template.reason_template,
+            scope: Some(PunishmentScope {
+                r#type: template.scope,
+                server_group: template.server_group,
+            }),
+            previous_offenses: plan.previous_offenses,
+            counted_offenses: plan.counted_offenses,
+            decay_mode: plan.decay.mode().to_string(),
+            decay_days: plan.decay.days(),
+            ladder_length: plan.ladder_length,
+            policy_version_id: template.policy_version_id,
+            explanation,
+            points: template.points,
+            severity_points: severity.as_ref().map(|severity| severity.current_points),
+            threshold_points: severity.and_then(|severity| severity.threshold).map(|threshold| threshold.points),
+        }))
+    }
+
+    async fn list_punishments(
+        &self,
+        request: Request<ListPunishmentsRequest>,
+    ) -> Result<Response<ListPunishmentsResponse>, Status> {
+        let claims = self.player_service.verify_request(&request).await?;
+        if !claims.staff {
+            return Err(Status::permission_denied("Only staff members can list punishments"));
+        }
+
+        let request = request.into_inner();
+        let parse_uuid = |id: Option<String>, name: &str| {
+            id.as_deref()
+                .map(Uuid::from_str)
+                .transpose()
+                .map_err(|e| Status::invalid_argument(format!("Invalid {}: {}", name, e)))
+        };
+        let parse_timestamp = |timestamp: Option<i64>, name: &str| {
+            timestamp
+                .map(OffsetDateTime::from_unix_timestamp)
+                .transpose()
+                .map_err(|e| Status::invalid_argument(format!("Invalid {}: {}", name, e)))
+        };
+
+        let filter = PunishmentFilter {
+            player_uuid: parse_uuid(request.player_id, "player ID")?,
+            staff_uuid: parse_uuid(request.staff_id, "staff ID")?,
+            category_id: request.category_id,
+            punishment_type: request.r#type,
+            status: request.status.as_deref().map(PunishmentStatus::from_str).transpose()?,
+            issued_after: parse_timestamp(request.issued_after, "issued_after")?,
+            issued_before: parse_timestamp(request.issued_before, "issued_before")?,
+        };
+        let sort = request.sort.as_deref().map(PunishmentSort::from_str).transpose()?.unwrap_or_default();
+        let cursor = request.cursor.as_deref().map(PunishmentCursor::from_str).transpose()?;
+        let limit = request.limit.unwrap_or(Self::DEFAULT_PAGE_SIZE).clamp(1, Self::MAX_PAGE_SIZE) as usize;
+
+        // Fetching one extra punishment tells whether there is another page
+        let mut punishments = self
+            .punishment_service
+            .list_punishments(&filter, sort, cursor.as_ref(), limit as i64 + 1)
+            .await?;
+
+        let next_cursor = if punishments.len() > limit {
+            punishments.truncate(limit);
+            punishments.last().map(|last| PunishmentCursor::after(last).to_string())
+        } else {
+            None
+        };
+
+        Ok(Response::new(ListPunishmentsResponse {
+            punishments: punishments.into_iter().map(|punishment| punishment.into_details(true)).collect(),
+            next_cursor,
+        }))
+    }
+
+    async fn get_punishment(
+        &self,
+        request: Request<GetPunishmentRequest>,
+    ) -> Result<Response<GetPunishmentResponse>, Status> {
+        let claims = self.player_service.verify_request(&request).await?;
+
+        let request = request.into_inner();
+        let punishment_id = Uuid::from_str(&request.punishment_id)
+            .map_err(|e| Status::invalid_argument(format!("Invalid punishment ID: {}", e)))?;
+
+        // Players may look up their own punishments, e.g. to appeal them, without the staff-only fields
+        let punishment = self
+            .punishment_service
+            .get_punishment(punishment_id)
+            .await?
+            .filter(|punishment| claims.staff || punishment.player_uuid == claims.sub)
+            .ok_or_else(|| Status::not_found("Punishment not found"))?;
+
+        Ok(Response::new(GetPunishmentResponse {
+            punishment: Some(punishment.into_details(claims.staff)),
+        }))
+    }
+
+    async fn get_alt_accounts(
+        &self,
+        request: Request<GetAltAccountsRequest>,
+    ) -> Result<Response<GetAltAccountsResponse>, Status> {
+        let claims = self.player_service.verify_request(&request).await?;
+        if !claims.staff {
+            return Err(Status::permission_denied("Only staff members can look up alt accounts"));
+        }
+
+        let request = request.into_inner();
+        let player_uuid = Uuid::from_str(&request.player_id)
+            .map_err(|e| Status::invalid_argument(format!("Invalid player ID: {}", e)))?;
+
+        let alts = self
+            .alt_service
+            .get_alt_accounts(
+                player_uuid,
+                request.window_days.unwrap_or(self.alt_service.window_days()),
+                request.max_depth.unwrap_or(1),
+            )
+            .await?;
+
+        Ok(Response::new(GetAltAccountsResponse {
+            alts: alts.into_iter().map(Into::into).collect(),
+        }))
+    }
+}
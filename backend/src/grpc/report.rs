@@ -1,14 +1,21 @@
 use crate::grpc::generated::report_service_server::ReportService as GeneratedReportService;
-use crate::services::ReportService;
+use crate::grpc::generated::{CreateReportRequest, CreateReportResponse};
+use crate::models::NewReport;
+use crate::services::{PlayerService, ReportService};
+use std::str::FromStr;
 use std::sync::Arc;
+use tonic::{Request, Response, Status};
+use uuid::Uuid;
 
 pub struct GrpcReportService {
+    player_service: Arc<PlayerService>,
     report_service: Arc<ReportService>
 }
 
 impl GrpcReportService {
-    pub fn new(report_service: Arc<ReportService>) -> Self {
+    pub fn new(player_service: Arc<PlayerService>, report_service: Arc<ReportService>) -> Self {
         Self {
+            player_service,
             report_service
         }
     }
@@ -16,5 +23,30 @@ impl GrpcReportService {
 
 #[tonic::async_trait]
 impl GeneratedReportService for GrpcReportService {
+    async fn create_report(
+        &self,
+        request: Request<CreateReportRequest>,
+    ) -> Result<Response<CreateReportResponse>, Status> {
+        self.player_service.verify_server(&request)?;
 
-}
\ No newline at end of file
+        let request = request.into_inner();
+        let reporter_uuid = Uuid::from_str(&request.reporter_id)
+            .map_err(|e| Status::invalid_argument(format!("Invalid reporter ID: {}", e)))?;
+        let reported_uuid = Uuid::from_str(&request.reported_id)
+            .map_err(|e| Status::invalid_argument(format!("Invalid reported player ID: {}", e)))?;
+
+        let report = self
+            .report_service
+            .create_report(NewReport {
+                reporter_uuid,
+                reported_uuid,
+                reason: request.reason,
+                server_name: request.server_name.filter(|name| !name.trim().is_empty()),
+            })
+            .await?;
+
+        Ok(Response::new(CreateReportResponse {
+            report: Some(report.into()),
+        }))
+    }
+}
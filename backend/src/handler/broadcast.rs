@@ -1,5 +1,6 @@
 use std::collections::HashMap;
 use std::sync::Arc;
+use std::time::{Duration, Instant};
 use tokio::sync::broadcast::error::SendError;
 use tokio::sync::broadcast::{channel, Receiver, Sender};
 use tokio::sync::RwLock;
@@ -11,9 +12,16 @@ pub struct KeyValue<T1, T2> {
     pub value: T2,
 }
 
+struct Listener<TK, TV> {
+    sender: Sender<KeyValue<TK, TV>>,
+    keys: Vec<TK>,
+    // Starts when the listener is created, so listeners that never send a heartbeat are evicted too
+    last_heartbeat: Instant,
+}
+
 #[derive(Clone)]
 pub struct BroadcastHandler<TK, TV> {
-    listeners: Arc<RwLock<HashMap<String, (Sender<KeyValue<TK, TV>>, Vec<TK>)>>>,
+    listeners: Arc<RwLock<HashMap<String, Listener<TK, TV>>>>,
 }
 
 impl<TK: Clone + PartialEq, TV: Clone> BroadcastHandler<TK, TV> {
@@ -28,7 +36,11 @@ impl<TK: Clone + PartialEq, TV: Clone> BroadcastHandler<TK, TV> {
         let (tx, rx) = channel::<KeyValue<TK, TV>>(100);
 
         let mut listeners = self.listeners.write().await;
-        listeners.insert(identifier_str, (tx, Vec::new()));
+        listeners.insert(identifier_str, Listener {
+            sender: tx,
+            keys: Vec::new(),
+            last_heartbeat: Instant::now(),
+        });
 
         rx
     }
@@ -37,8 +49,8 @@ impl<TK: Clone + PartialEq, TV: Clone> BroadcastHandler<TK, TV> {
         let listeners = self.listeners.read().await;
         let event = KeyValue { key: key.clone(), value };
 
-        listeners.values().filter(|(_, keys)| keys.contains(&key)).for_each(|(sender, _)| {
-            let _ = sender.send(event.clone());
+        listeners.values().filter(|listener| listener.keys.contains(&key)).for_each(|listener| {
+            let _ = listener.sender.send(event.clone());
         });
 
         Ok(())
@@ -52,18 +64,37 @@ impl<TK: Clone + PartialEq, TV: Clone> BroadcastHandler<TK, TV> {
     pub async fn add_key_to_listener(&self, identifier: &Uuid, key: TK) {
         let mut listeners = self.listeners.write().await;
         let identifier_str = identifier.to_string();
-        if let Some((_, keys)) = listeners.get_mut(&identifier_str) {
-            keys.push(key);
+        if let Some(listener) = listeners.get_mut(&identifier_str) {
+            listener.keys.push(key);
         }
     }
 
     pub async fn remove_key_from_listener(&self, identifier: &Uuid, key: TK) {
         let mut listeners = self.listeners.write().await;
         let identifier_str = identifier.to_string();
-        if let Some((_, keys)) = listeners.get_mut(&identifier_str) {
-            keys.retain(|k| k != &key);
+        if let Some(listener) = listeners.get_mut(&identifier_str) {
+            listener.keys.retain(|k| k != &key);
         }
     }
+
+    pub async fn record_heartbeat(&self, identifier: &Uuid) {
+        let mut listeners = self.listeners.write().await;
+        if let Some(listener) = listeners.get_mut(&identifier.to_string()) {
+            listener.last_heartbeat = Instant::now();
+        }
+    }
+
+    /// Removes listeners whose last heartbeat, or creation when they never sent one, is older than
+    /// `max_idle`, releasing their keys.
+    /// Dropping the sender closes the receiver returned by `start_broadcast_listener`.
+    pub async fn evict_idle_listeners(&self, max_idle: Duration) -> usize {
+        let mut listeners = self.listeners.write().await;
+        let before = listeners.len();
+
+        listeners.retain(|_, listener| listener.last_heartbeat.elapsed() < max_idle);
+
+        before - listeners.len()
+    }
 }
 
 #[cfg(test)]
@@ -115,6 +146,46 @@ mod tests {
         assert!(rx.try_recv().is_err(), "should not receive event for unregistered key");
     }
 
+    #[tokio::test]
+    async fn evict_idle_listener_closes_receiver() {
+        let handler: BroadcastHandler<u32, String> = BroadcastHandler::new();
+        let id = Uuid::new_v4();
+
+        let mut rx = handler.start_broadcast_listener(&id).await;
+        handler.add_key_to_listener(&id, 1u32).await;
+        handler.record_heartbeat(&id).await;
+
+        assert_eq!(handler.evict_idle_listeners(Duration::ZERO).await, 1);
+        assert!(!handler.listeners.read().await.contains_key(&id.to_string()));
+        assert!(matches!(rx.recv().await, Err(tokio::sync::broadcast::error::RecvError::Closed)));
+    }
+
+    #[tokio::test]
+    async fn evict_keeps_recent_listeners() {
+        let handler: BroadcastHandler<u32, String> = BroadcastHandler::new();
+        let id_recent = Uuid::new_v4();
+        let id_new = Uuid::new_v4();
+
+        handler.start_broadcast_listener(&id_recent).await;
+        handler.start_broadcast_listener(&id_new).await;
+        handler.record_heartbeat(&id_recent).await;
+
+        assert_eq!(handler.evict_idle_listeners(Duration::from_secs(60)).await, 0);
+        assert_eq!(handler.listeners.read().await.len(), 2);
+    }
+
+    #[tokio::test]
+    async fn evict_heartbeat_less_listener_once_idle() {
+        let handler: BroadcastHandler<u32, String> = BroadcastHandler::new();
+        let id = Uuid::new_v4();
+
+        let mut rx = handler.start_broadcast_listener(&id).await;
+
+        // The idle timer starts on creation, a listener does not have to ping to be evicted
+        assert_eq!(handler.evict_idle_listeners(Duration::ZERO).await, 1);
+        assert!(matches!(rx.recv().await, Err(tokio::sync::broadcast::error::RecvError::Closed)));
+    }
+
     #[tokio::test]
     async fn add_and_remove_key() {
         let handler: BroadcastHandler<u32, String> = BroadcastHandler::new();
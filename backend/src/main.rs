@@ -1,13 +1,18 @@
+mod config;
 mod database;
 mod error;
 mod grpc;
 mod models;
 mod services;
 mod handler;
+mod storage;
 
 use crate::database::connect_to_db;
 use crate::grpc::start_grpc_server;
-use crate::services::{BroadcastService, MessageService, PlayerService, PunishmentService, ReportService};
+use crate::storage::blob_store_from_env;
+use crate::services::{AltService, AuditService, BroadcastService, ChatFilterService, ChatService, EvidenceService, HoldService, MessageService, PlayerService, PolicyService, PunishmentService, ReportService};
+use std::env::args;
+use std::process::exit;
 use std::sync::Arc;
 use tokio::main;
 
@@ -17,11 +22,28 @@ async fn main() {
     dotenvy::dotenv().expect("Failed to load .env file");
 
     let pg_pool = Arc::new(connect_to_db().await.expect("failed to connect to db"));
+    let audit_service = Arc::new(AuditService::new(pg_pool.as_ref().clone()));
+
+    // `backend verify-audit` checks the audit log instead of starting the server
+    if args().nth(1).as_deref() == Some("verify-audit") {
+        verify_audit_chain(&audit_service).await;
+        return;
+    }
+
     let message_service = Arc::new(MessageService::new(pg_pool.as_ref().clone()));
     let player_service = Arc::new(PlayerService::new(pg_pool.as_ref().clone()));
     let punishment_service = Arc::new(PunishmentService::new(pg_pool.as_ref().clone()));
-    let report_service = Arc::new(ReportService::new());
+    let report_service = Arc::new(ReportService::new(pg_pool.as_ref().clone()));
+    let alt_service = Arc::new(AltService::new(pg_pool.as_ref().clone()));
     let broadcast_service = Arc::new(BroadcastService::new());
+    let policy_service = Arc::new(PolicyService::new(pg_pool.as_ref().clone()));
+    let blob_store = blob_store_from_env().expect("failed to set up the evidence store");
+    let evidence_service = Arc::new(EvidenceService::new(pg_pool.as_ref().clone(), blob_store));
+    let chat_service = Arc::new(ChatService::new(pg_pool.as_ref().clone()));
+    let chat_filter_service = Arc::new(ChatFilterService::new(pg_pool.as_ref().clone(), punishment_service.clone()));
+    let hold_service = Arc::new(HoldService::new(pg_pool.as_ref().clone(), punishment_service.clone()));
+
+    chat_service.maintain_partitions().await.expect("failed to prepare the chat partitions");
 
     let grpc_server = start_grpc_server(
         player_service.clone(),
@@ -29,7 +51,31 @@ async fn main() {
         report_service.clone(),
         message_service.clone(),
         broadcast_service.clone(),
+        alt_service.clone(),
+        policy_service.clone(),
+        audit_service.clone(),
+        evidence_service.clone(),
+        chat_service.clone(),
+        chat_filter_service.clone(),
+        hold_service.clone(),
     );
+    let punishment_relay = broadcast_service.relay_punishment_events(pg_pool.as_ref(), punishment_service.as_ref());
+    let idle_eviction = broadcast_service.evict_idle_listeners();
+    let chat_partitions = chat_service.manage_partitions();
+
+    tokio::try_join!(grpc_server, punishment_relay, idle_eviction, chat_partitions).expect("Server error");
+}
+
+async fn verify_audit_chain(audit_service: &AuditService) {
+    let (verifier, chain_break) = audit_service.verify_chain().await.expect("failed to read the audit log");
+
+    println!("Verified {} audit events", verifier.verified());
+    if let Some(head) = verifier.head() {
+        println!("Latest hash: {}", head);
+    }
 
-    tokio::try_join!(grpc_server).expect("Server error");
+    if let Some(chain_break) = chain_break {
+        eprintln!("The audit log was tampered with at {}", chain_break);
+        exit(1);
+    }
 }
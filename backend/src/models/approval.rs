@@ -0,0 +1,199 @@
+use crate::grpc::generated;
+use crate::models::IssuedPunishment;
+use serde::{Deserialize, Serialize};
+use sqlx::types::ipnetwork::IpNetwork;
+use sqlx::FromRow;
+use time::OffsetDateTime;
+use uuid::Uuid;
+
+/// A punishment queued for a staff member with the punishment.approve permission.
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize)]
+pub struct PunishmentApproval {
+    pub id: Uuid,
+    pub player_uuid: Uuid,
+    pub requested_by: Uuid,
+    pub category_id: i32,
+    pub punishment_type: String,
+    pub duration_minutes: Option<i32>,
+    pub reason: Option<String>,
+    pub evidence: Option<String>,
+    pub note: Option<String>,
+    pub scope: Option<String>,
+    pub server_group: Option<String>,
+    pub ip_range: Option<IpNetwork>,
+    pub approval_reason: String,
+    pub status: String,
+    pub reviewed_by: Option<Uuid>,
+    pub reviewed_at: Option<OffsetDateTime>,
+    pub review_comment: Option<String>,
+    pub punishment_id: Option<Uuid>,
+    pub created_at: OffsetDateTime,
+    // Set when the backend queued the automatic ban of a severity threshold
+    pub threshold_points: Option<i32>,
+    pub offense_id: Option<Uuid>,
+}
+
+impl PunishmentApproval {
+    /// Describes how the step the ladder gives now differs from the one queued, if it does.
+    pub fn step_change(&self, punishment_type: &str, duration_minutes: Option<i32>) -> Option<String> {
+        if self.punishment_type == punishment_type && self.duration_minutes == duration_minutes {
+            return None;
+        }
+
+        Some(format!(
+            "The ladder now gives {} instead of {}",
+            describe_step(punishment_type, duration_minutes),
+            describe_step(&self.punishment_type, self.duration_minutes)
+        ))
+    }
+}
+
+fn describe_step(punishment_type: &str, duration_minutes: Option<i32>) -> String {
+    match duration_minutes {
+        Some(minutes) => format!("a {} of {} minutes", punishment_type, minutes),
+        None => format!("a {}", punishment_type),
+    }
+}
+
+impl From<PunishmentApproval> for generated::PunishmentApproval {
+    fn from(approval: PunishmentApproval) -> Self {
+        generated::PunishmentApproval {
+            id: approval.id.to_string(),
+            player_id: approval.player_uuid.to_string(),
+            requested_by: approval.requested_by.to_string(),
+            category_id: approval.category_id,
+            r#type: approval.punishment_type,
+            duration_minutes: approval.duration_minutes,
+            reason: approval.reason,
+            evidence: approval.evidence,
+            note: approval.note,
+            scope: approval.scope.map(|scope| generated::PunishmentScope {
+                r#type: scope,
+                server_group: approval.server_group,
+            }),
+            ip_range: approval.ip_range.map(|range| range.to_string()),
+            approval_reason: approval.approval_reason,
+            status: approval.status,
+            reviewed_by: approval.reviewed_by.map(|uuid| uuid.to_string()),
+            reviewed_at: approval.reviewed_at.map(|dt| dt.unix_timestamp()),
+            review_comment: approval.review_comment,
+            punishment_id: approval.punishment_id.map(|id| id.to_string()),
+            created_at: approval.created_at.unix_timestamp(),
+            threshold_points: approval.threshold_points,
+        }
+    }
+}
+
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize)]
+pub struct ApprovalSettings {
+    pub required_types: Vec<String>,
+    pub updated_by: Option<Uuid>,
+    pub updated_at: OffsetDateTime,
+}
+
+impl ApprovalSettings {
+    /// Explains why the punishment needs a second staff member whoever issues it, if it does.
+    pub fn required_reason(&self, punishment_type: &str, category_name: &str, category_requires_approval: bool) -> Option<String> {
+        if self.required_types.iter().any(|required| required == punishment_type) {
+            Some(format!("Every {} needs approval", punishment_type))
+        } else if category_requires_approval {
+            Some(format!("Punishments in {} need approval", category_name))
+        } else {
+            None
+        }
+    }
+}
+
+impl From<ApprovalSettings> for generated::ApprovalSettingsResponse {
+    fn from(settings: ApprovalSettings) -> Self {
+        generated::ApprovalSettingsResponse {
+            required_types: settings.required_types,
+            updated_by: settings.updated_by.map(|uuid| uuid.to_string()),
+            updated_at: settings.updated_at.unix_timestamp(),
+        }
+    }
+}
+
+/// Result of `issue_punishment`, either issued right away or queued for approval.
+#[derive(Debug, Clone)]
+pub enum IssueOutcome {
+    Issued(Box<IssuedPunishment>),
+    Queued(Box<PunishmentApproval>),
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn make_settings(required_types: &[&str]) -> ApprovalSettings {
+        ApprovalSettings {
+            required_types: required_types.iter().map(|punishment_type| punishment_type.to_string()).collect(),
+            updated_by: None,
+            updated_at: OffsetDateTime::now_utc(),
+        }
+    }
+
+    // ── step_change ──────────────────────────────────────────────────────────
+
+    fn make_approval(punishment_type: &str, duration_minutes: Option<i32>) -> PunishmentApproval {
+        PunishmentApproval {
+            id: Uuid::new_v4(),
+            player_uuid: Uuid::new_v4(),
+            requested_by: Uuid::new_v4(),
+            category_id: 1,
+            punishment_type: punishment_type.to_string(),
+            duration_minutes,
+            reason: None,
+            evidence: None,
+            note: None,
+            scope: None,
+            server_group: None,
+            ip_range: None,
+            approval_reason: "Every temp_ban needs approval".to_string(),
+            status: "pending".to_string(),
+            reviewed_by: None,
+            reviewed_at: None,
+            review_comment: None,
+            punishment_id: None,
+            created_at: OffsetDateTime::now_utc(),
+            threshold_points: None,
+            offense_id: None,
+        }
+    }
+
+    #[test]
+    fn unchanged_step_needs_no_new_review() {
+        assert_eq!(make_approval("temp_ban", Some(1440)).step_change("temp_ban", Some(1440)), None);
+    }
+
+    #[test]
+    fn describes_a_changed_step() {
+        let approval = make_approval("temp_ban", Some(1440));
+        assert_eq!(
+            approval.step_change("perm_ban", None).as_deref(),
+            Some("The ladder now gives a perm_ban instead of a temp_ban of 1440 minutes")
+        );
+        assert!(approval.step_change("temp_ban", Some(10080)).is_some());
+    }
+
+    // ── required_reason ──────────────────────────────────────────────────────
+
+    #[test]
+    fn configured_types_need_approval() {
+        let settings = make_settings(&["perm_ban"]);
+        assert_eq!(
+            settings.required_reason("perm_ban", "Cheating", false).as_deref(),
+            Some("Every perm_ban needs approval")
+        );
+        assert_eq!(settings.required_reason("temp_ban", "Cheating", false), None);
+    }
+
+    #[test]
+    fn configured_categories_need_approval() {
+        let settings = make_settings(&[]);
+        assert_eq!(
+            settings.required_reason("warn", "Chat Abuse", true).as_deref(),
+            Some("Punishments in Chat Abuse need approval")
+        );
+    }
+}
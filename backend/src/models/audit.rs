@@ -0,0 +1,242 @@
+use crate::grpc::generated;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use sha2::{Digest, Sha256};
+use sqlx::FromRow;
+use std::fmt;
+use time::OffsetDateTime;
+use uuid::Uuid;
+
+/// `prev_hash` of the first event in the chain.
+pub const GENESIS_HASH: &str = "0000000000000000000000000000000000000000000000000000000000000000";
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NewAuditEvent {
+    // None when the backend acts on its own
+    pub actor_uuid: Option<Uuid>,
+    pub action: String,
+    pub target_type: String,
+    pub target_id: Option<String>,
+    pub details: Value,
+}
+
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize)]
+pub struct AuditEvent {
+    pub id: i64,
+    pub actor_uuid: Option<Uuid>,
+    pub action: String,
+    pub target_type: String,
+    pub target_id: Option<String>,
+    pub details: Value,
+    pub prev_hash: String,
+    pub hash: String,
+    pub created_at: OffsetDateTime,
+}
+
+impl From<AuditEvent> for generated::AuditEvent {
+    fn from(event: AuditEvent) -> Self {
+        generated::AuditEvent {
+            id: event.id,
+            actor_id: event.actor_uuid.map(|uuid| uuid.to_string()),
+            action: event.action,
+            target_type: event.target_type,
+            target_id: event.target_id,
+            details: event.details.to_string(),
+            prev_hash: event.prev_hash,
+            hash: event.hash,
+            created_at: event.created_at.unix_timestamp(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct AuditFilter {
+    pub actor_uuid: Option<Uuid>,
+    pub action: Option<String>,
+    pub target_type: Option<String>,
+    pub target_id: Option<String>,
+    pub created_after: Option<OffsetDateTime>,
+    pub created_before: Option<OffsetDateTime>,
+}
+
+/// An audit event as it was hashed, with the details in Postgres' text form of the JSONB value.
+#[derive(Debug, Clone, FromRow)]
+pub struct AuditChainLink {
+    pub id: i64,
+    pub actor_uuid: Option<Uuid>,
+    pub action: String,
+    pub target_type: String,
+    pub target_id: Option<String>,
+    pub details: String,
+    pub prev_hash: String,
+    pub hash: String,
+    pub created_at: OffsetDateTime,
+}
+
+impl AuditChainLink {
+    /// Recomputes the hash independently of the database, the layout is described in the
+    /// audit-chain migration.
+    pub fn expected_hash(&self) -> String {
+        let created_at_micros = self.created_at.unix_timestamp_nanos() / 1000;
+        let payload = [
+            self.prev_hash.clone(),
+            self.actor_uuid.map(|uuid| uuid.to_string()).unwrap_or_default(),
+            self.action.clone(),
+            self.target_type.clone(),
+            self.target_id.clone().unwrap_or_default(),
+            self.details.clone(),
+            created_at_micros.to_string(),
+        ]
+        .join("\n");
+
+        format!("{:x}", Sha256::digest(payload.as_bytes()))
+    }
+}
+
+/// First event at which the chain no longer holds.
+#[derive(Debug, Clone, PartialEq)]
+pub struct AuditChainBreak {
+    pub event_id: i64,
+    pub reason: String,
+}
+
+impl fmt::Display for AuditChainBreak {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "audit event {}: {}", self.event_id, self.reason)
+    }
+}
+
+/// Walks the chain one event at a time, in id order.
+#[derive(Debug, Clone, Default)]
+pub struct AuditChainVerifier {
+    head: Option<String>,
+    verified: u64,
+}
+
+impl AuditChainVerifier {
+    pub fn check(&mut self, link: &AuditChainLink) -> Result<(), AuditChainBreak> {
+        let expected_prev_hash = self.head.as_deref().unwrap_or(GENESIS_HASH);
+        let chain_break = |reason: String| AuditChainBreak {
+            event_id: link.id,
+            reason,
+        };
+
+        if link.prev_hash != expected_prev_hash {
+            return Err(chain_break(format!(
+                "links to {} instead of {}, an earlier event was changed or removed",
+                link.prev_hash, expected_prev_hash
+            )));
+        }
+
+        if link.expected_hash() != link.hash {
+            return Err(chain_break("its contents no longer match its hash".to_string()));
+        }
+
+        self.head = Some(link.hash.clone());
+        self.verified += 1;
+
+        Ok(())
+    }
+
+    /// Hash of the last verified event. Removing events from the end of the chain cannot be
+    /// detected from the chain alone, so it is worth noting down outside of the database.
+    pub fn head(&self) -> Option<&str> {
+        self.head.as_deref()
+    }
+
+    pub fn verified(&self) -> u64 {
+        self.verified
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Hashed by the audit-chain migration in Postgres
+    fn make_link() -> AuditChainLink {
+        AuditChainLink {
+            id: 1,
+            actor_uuid: None,
+            action: "legacy.one".to_string(),
+            target_type: "x".to_string(),
+            target_id: Some("1".to_string()),
+            details: r#"{"a": [1.5, "é"], "b": 1}"#.to_string(),
+            prev_hash: GENESIS_HASH.to_string(),
+            hash: "4298c28e0b375aaf853ac2e1c94b5bab3e07d8c2dde3dfa558e09c59c20f5938".to_string(),
+            created_at: OffsetDateTime::from_unix_timestamp_nanos(1_792_400_512_951_878_000).unwrap(),
+        }
+    }
+
+    fn make_second_link() -> AuditChainLink {
+        AuditChainLink {
+            id: 2,
+            action: "legacy.two".to_string(),
+            target_id: None,
+            details: "{}".to_string(),
+            prev_hash: make_link().hash,
+            hash: "bcaa370931f554a4c08427c2ae4cb9d922c5c8fd2a28484cd32cade61a3df2c7".to_string(),
+            ..make_link()
+        }
+    }
+
+    fn make_third_link() -> AuditChainLink {
+        AuditChainLink {
+            id: 4,
+            actor_uuid: Some(Uuid::parse_str("e0251b43-351c-4318-a742-aa350627df60").unwrap()),
+            action: "test.three".to_string(),
+            target_type: "player".to_string(),
+            target_id: Some("abc".to_string()),
+            details: "{\"z\": null, \"aa\": \"x\\ny\"}".to_string(),
+            prev_hash: make_second_link().hash,
+            hash: "ee24cd76415fa573388ef33a21cfed5ad00ebdb22ec2a0c95eb68a2dd5a35e29".to_string(),
+            created_at: OffsetDateTime::from_unix_timestamp_nanos(1_792_400_513_102_444_000).unwrap(),
+        }
+    }
+
+    // ── AuditChainLink ───────────────────────────────────────────────────────
+
+    #[test]
+    fn expected_hash_matches_the_database() {
+        for link in [make_link(), make_second_link(), make_third_link()] {
+            assert_eq!(link.expected_hash(), link.hash);
+        }
+    }
+
+    // ── AuditChainVerifier ───────────────────────────────────────────────────
+
+    #[test]
+    fn verifies_an_intact_chain() {
+        let mut verifier = AuditChainVerifier::default();
+
+        for link in [make_link(), make_second_link(), make_third_link()] {
+            assert_eq!(verifier.check(&link), Ok(()));
+        }
+        assert_eq!(verifier.verified(), 3);
+        assert_eq!(verifier.head(), Some(make_third_link().hash.as_str()));
+    }
+
+    #[test]
+    fn detects_changed_contents() {
+        let mut link = make_link();
+        link.details = r#"{"a": [1.5, "é"], "b": 2}"#.to_string();
+
+        let chain_break = AuditChainVerifier::default().check(&link).unwrap_err();
+        assert_eq!(chain_break.event_id, 1);
+        assert_eq!(chain_break.reason, "its contents no longer match its hash");
+    }
+
+    #[test]
+    fn detects_removed_events() {
+        let mut verifier = AuditChainVerifier::default();
+
+        assert_eq!(verifier.check(&make_link()), Ok(()));
+        assert_eq!(verifier.check(&make_third_link()).unwrap_err().event_id, 4);
+        assert_eq!(verifier.verified(), 1);
+    }
+
+    #[test]
+    fn the_first_event_links_to_the_genesis_hash() {
+        assert!(AuditChainVerifier::default().check(&make_second_link()).is_err());
+    }
+}
@@ -0,0 +1,215 @@
+use crate::error::{AppError, AppResult};
+use crate::grpc::generated;
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use std::str::FromStr;
+use time::{Date, Duration, Month, OffsetDateTime};
+use uuid::Uuid;
+
+/// Longest message kept, far above what Minecraft clients can send.
+pub const MAX_CHAT_MESSAGE_LENGTH: usize = 4096;
+
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize)]
+pub struct ChatLine {
+    pub id: i64,
+    pub player_uuid: Uuid,
+    // Joined from players, unknown for players who never logged in through the backend
+    pub username: Option<String>,
+    pub server_name: String,
+    pub server_group: Option<String>,
+    pub message: String,
+    pub sent_at: OffsetDateTime,
+}
+
+impl From<ChatLine> for generated::ChatLine {
+    fn from(line: ChatLine) -> Self {
+        generated::ChatLine {
+            player_id: line.player_uuid.to_string(),
+            server_name: line.server_name,
+            server_group: line.server_group,
+            message: line.message,
+            sent_at_millis: (line.sent_at.unix_timestamp_nanos() / 1_000_000) as i64,
+            username: line.username,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct NewChatLine {
+    pub player_uuid: Uuid,
+    pub server_name: String,
+    pub server_group: Option<String>,
+    pub message: String,
+    pub sent_at: OffsetDateTime,
+}
+
+impl NewChatLine {
+    /// Validates a forwarded line. Lines from the future are moved to `now`, lines that would
+    /// already be past the retention are refused.
+    pub fn from_message(line: generated::ChatLine, now: OffsetDateTime, retention: Duration) -> AppResult<Self> {
+        let invalid = |message: String| AppError::CustomValidationError(message);
+
+        let player_uuid = Uuid::from_str(&line.player_id)?;
+        if line.server_name.trim().is_empty() || line.server_name.len() > 64 {
+            return Err(invalid(format!("Invalid server name: {}", line.server_name)));
+        }
+        if line.server_group.as_ref().is_some_and(|group| group.len() > 64) {
+            return Err(invalid("Server groups can be at most 64 characters long".to_string()));
+        }
+        if line.message.is_empty() || line.message.len() > MAX_CHAT_MESSAGE_LENGTH {
+            return Err(invalid(format!(
+                "Chat messages have to be between 1 and {} bytes long",
+                MAX_CHAT_MESSAGE_LENGTH
+            )));
+        }
+
+        let sent_at = OffsetDateTime::from_unix_timestamp_nanos(line.sent_at_millis as i128 * 1_000_000)
+            .map_err(|e| invalid(format!("Invalid sent_at_millis: {}", e)))?;
+        if sent_at < now - retention {
+            return Err(invalid("The line is older than the chat retention".to_string()));
+        }
+
+        Ok(Self {
+            player_uuid,
+            server_name: line.server_name,
+            server_group: line.server_group,
+            message: line.message,
+            sent_at: sent_at.min(now),
+        })
+    }
+}
+
+/// Name of the partition holding the chat of a UTC day.
+pub fn chat_partition_name(day: Date) -> String {
+    format!("chat_messages_p{:04}{:02}{:02}", day.year(), day.month() as u8, day.day())
+}
+
+/// Day of a partition named by `chat_partition_name`, None for any other table.
+pub fn chat_partition_day(name: &str) -> Option<Date> {
+    let digits = name.strip_prefix("chat_messages_p")?;
+    if digits.len() != 8 || !digits.chars().all(|c| c.is_ascii_digit()) {
+        return None;
+    }
+
+    let year = digits[..4].parse().ok()?;
+    let month = Month::try_from(digits[4..6].parse::<u8>().ok()?).ok()?;
+    let day = digits[6..].parse().ok()?;
+
+    Date::from_calendar_date(year, month, day).ok()
+}
+
+/// Renders chat lines as a plain text log, e.g. for evidence.
+pub fn format_chat_log(lines: &[ChatLine]) -> String {
+    lines
+        .iter()
+        .map(|line| {
+            let sent_at = line.sent_at;
+            format!(
+                "[{:04}-{:02}-{:02} {:02}:{:02}:{:02} UTC] [{}] {}: {}\n",
+                sent_at.year(),
+                sent_at.month() as u8,
+                sent_at.day(),
+                sent_at.hour(),
+                sent_at.minute(),
+                sent_at.second(),
+                line.server_name,
+                line.username.clone().unwrap_or_else(|| line.player_uuid.to_string()),
+                line.message
+            )
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn make_message(sent_at_millis: i64) -> generated::ChatLine {
+        generated::ChatLine {
+            player_id: "00000000-0000-0000-0000-000000000002".to_string(),
+            server_name: "lobby-1".to_string(),
+            server_group: Some("lobby".to_string()),
+            message: "hello".to_string(),
+            sent_at_millis,
+            username: None,
+        }
+    }
+
+    fn now() -> OffsetDateTime {
+        OffsetDateTime::from_unix_timestamp(1_700_000_000).unwrap()
+    }
+
+    // ── NewChatLine ──────────────────────────────────────────────────────────
+
+    #[test]
+    fn keeps_millisecond_timestamps() {
+        let line = NewChatLine::from_message(make_message(1_699_999_999_123), now(), Duration::days(30)).unwrap();
+        assert_eq!(line.sent_at.unix_timestamp_nanos(), 1_699_999_999_123_000_000);
+    }
+
+    #[test]
+    fn moves_future_lines_to_now() {
+        let line = NewChatLine::from_message(make_message(1_700_000_600_000), now(), Duration::days(30)).unwrap();
+        assert_eq!(line.sent_at, now());
+    }
+
+    #[test]
+    fn refuses_invalid_lines() {
+        let retention = Duration::days(30);
+        assert!(NewChatLine::from_message(make_message(1_600_000_000_000), now(), retention).is_err());
+
+        let mut message = make_message(1_700_000_000_000);
+        message.player_id = "steve".to_string();
+        assert!(NewChatLine::from_message(message, now(), retention).is_err());
+
+        let mut message = make_message(1_700_000_000_000);
+        message.message = String::new();
+        assert!(NewChatLine::from_message(message, now(), retention).is_err());
+
+        let mut message = make_message(1_700_000_000_000);
+        message.server_name = " ".to_string();
+        assert!(NewChatLine::from_message(message, now(), retention).is_err());
+    }
+
+    // ── Partitions ───────────────────────────────────────────────────────────
+
+    #[test]
+    fn partition_names_round_trip() {
+        let day = Date::from_calendar_date(2026, Month::March, 7).unwrap();
+        assert_eq!(chat_partition_name(day), "chat_messages_p20260307");
+        assert_eq!(chat_partition_day("chat_messages_p20260307"), Some(day));
+    }
+
+    #[test]
+    fn other_tables_are_not_partitions() {
+        assert_eq!(chat_partition_day("chat_messages_default"), None);
+        assert_eq!(chat_partition_day("chat_messages_p20261345"), None);
+        assert_eq!(chat_partition_day("players"), None);
+    }
+
+    // ── format_chat_log ──────────────────────────────────────────────────────
+
+    #[test]
+    fn formats_one_line_per_message() {
+        let line = ChatLine {
+            id: 1,
+            player_uuid: Uuid::nil(),
+            username: None,
+            server_name: "lobby-1".to_string(),
+            server_group: None,
+            message: "hello".to_string(),
+            sent_at: now(),
+        };
+
+        let named = ChatLine {
+            username: Some("Steve".to_string()),
+            ..line.clone()
+        };
+
+        assert_eq!(
+            format_chat_log(&[line, named]),
+            "[2023-11-14 22:13:20 UTC] [lobby-1] 00000000-0000-0000-0000-000000000000: hello\n\
+             [2023-11-14 22:13:20 UTC] [lobby-1] Steve: hello\n"
+        );
+    }
+}
@@ -0,0 +1,826 @@
+use crate::error::{AppError, AppResult};
+use crate::grpc::generated;
+use regex::{Regex, RegexBuilder};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use std::collections::VecDeque;
+use std::ops::Range;
+use std::sync::LazyLock;
+use time::{Duration, OffsetDateTime};
+use uuid::Uuid;
+
+// A plain dot only counts without spaces around it, "gg. nice" is chat and not a domain
+const ADDRESS_SEPARATOR: &str = r"(?:\.|\s*[(\[{]dot[)\]}]\s*|\s+dot\s+)";
+const ADVERTISED_TLDS: &str = "com|net|org|io|gg|me|co|de|uk|eu|us|fr|nl|ru|info|xyz|club|fun|pro|online|site|store|tk|ml|ga|cf|gq|pw|cc|tv|biz|host|games|network";
+
+static ADVERTISING: LazyLock<Regex> = LazyLock::new(|| {
+    let ip = format!(r"\b(?:\d{{1,3}}{0}){{3}}\d{{1,3}}\b", ADDRESS_SEPARATOR);
+    let domain = format!(r"\b(?:[a-z0-9](?:[a-z0-9-]*[a-z0-9])?{0})+(?:{1})\b", ADDRESS_SEPARATOR, ADVERTISED_TLDS);
+
+    RegexBuilder::new(&format!(r"https?://\S+|(?:{}|{})(?::\d{{1,5}})?", ip, domain))
+        .case_insensitive(true)
+        .build()
+        .expect("the advertising pattern is valid")
+});
+
+static SEPARATOR: LazyLock<Regex> = LazyLock::new(|| Regex::new(ADDRESS_SEPARATOR).expect("the separator pattern is valid"));
+
+/// What happens to a message, ordered from mildest to harshest so the harshest match wins.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum FilterAction {
+    Allow,
+    Censor,
+    Block,
+}
+
+impl FilterAction {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            FilterAction::Allow => "allow",
+            FilterAction::Censor => "censor",
+            FilterAction::Block => "block",
+        }
+    }
+
+    /// Parses the action of a rule, which has to do something.
+    fn parse(action: &str) -> AppResult<Self> {
+        match action {
+            "censor" => Ok(FilterAction::Censor),
+            "block" => Ok(FilterAction::Block),
+            _ => Err(AppError::CustomValidationError(format!(
+                "Unknown filter action: {}, expected block or censor",
+                action
+            ))),
+        }
+    }
+}
+
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize)]
+pub struct ChatFilterSettings {
+    pub enabled: bool,
+    pub advertising_action: Option<String>,
+    pub allowed_domains: Vec<String>,
+    pub spam_max_repeats: Option<i32>,
+    pub spam_window_seconds: i32,
+    pub punish_category_id: Option<i32>,
+    pub punish_after_violations: i32,
+    pub violation_window_minutes: i32,
+    pub updated_by: Option<Uuid>,
+    pub updated_at: OffsetDateTime,
+}
+
+impl ChatFilterSettings {
+    pub fn into_message(self, rules: Vec<ChatFilterRule>) -> generated::ChatFilterResponse {
+        generated::ChatFilterResponse {
+            enabled: self.enabled,
+            advertising_action: self.advertising_action,
+            allowed_domains: self.allowed_domains,
+            spam_max_repeats: self.spam_max_repeats,
+            spam_window_seconds: self.spam_window_seconds,
+            punish_category_id: self.punish_category_id,
+            punish_after_violations: self.punish_after_violations,
+            violation_window_minutes: self.violation_window_minutes,
+            rules: rules.into_iter().map(Into::into).collect(),
+            updated_by: self.updated_by.map(|uuid| uuid.to_string()),
+            updated_at: self.updated_at.unix_timestamp(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize)]
+pub struct ChatFilterRule {
+    pub id: i32,
+    pub kind: String,
+    pub pattern: String,
+    pub action: String,
+    pub created_at: OffsetDateTime,
+}
+
+impl From<ChatFilterRule> for generated::ChatFilterRule {
+    fn from(rule: ChatFilterRule) -> Self {
+        generated::ChatFilterRule {
+            id: Some(rule.id),
+            kind: rule.kind,
+            pattern: rule.pattern,
+            action: rule.action,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NewChatFilterRule {
+    pub kind: String,
+    pub pattern: String,
+    pub action: String,
+}
+
+impl From<generated::ChatFilterRule> for NewChatFilterRule {
+    fn from(rule: generated::ChatFilterRule) -> Self {
+        NewChatFilterRule {
+            kind: rule.kind,
+            pattern: rule.pattern,
+            action: rule.action,
+        }
+    }
+}
+
+/// Replaces the whole filter configuration, rules included.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ChatFilterUpdate {
+    pub enabled: bool,
+    pub advertising_action: Option<String>,
+    pub allowed_domains: Vec<String>,
+    pub spam_max_repeats: Option<i32>,
+    pub spam_window_seconds: i32,
+    pub punish_category_id: Option<i32>,
+    pub punish_after_violations: i32,
+    pub violation_window_minutes: i32,
+    pub rules: Vec<NewChatFilterRule>,
+}
+
+impl From<generated::UpdateChatFilterRequest> for ChatFilterUpdate {
+    fn from(request: generated::UpdateChatFilterRequest) -> Self {
+        ChatFilterUpdate {
+            enabled: request.enabled,
+            advertising_action: request.advertising_action,
+            allowed_domains: request
+                .allowed_domains
+                .iter()
+                .map(|domain| domain.trim().trim_start_matches("*.").to_lowercase())
+                .collect(),
+            spam_max_repeats: request.spam_max_repeats,
+            spam_window_seconds: request.spam_window_seconds,
+            punish_category_id: request.punish_category_id,
+            punish_after_violations: request.punish_after_violations,
+            violation_window_minutes: request.violation_window_minutes,
+            rules: request.rules.into_iter().map(Into::into).collect(),
+        }
+    }
+}
+
+impl ChatFilterUpdate {
+    /// Checks the configuration up front, compiling every rule, so staff get a readable error
+    /// instead of a violated CHECK or a filter that fails on the next message.
+    pub fn validate(&self) -> AppResult<()> {
+        let invalid = |message: &str| Err(AppError::CustomValidationError(message.to_string()));
+
+        if let Some(action) = &self.advertising_action {
+            FilterAction::parse(action)?;
+        }
+        if self.allowed_domains.iter().any(|domain| domain.is_empty() || domain.contains(char::is_whitespace)) {
+            return invalid("Allowed domains cannot be empty or contain spaces");
+        }
+        if self.spam_max_repeats.is_some_and(|repeats| repeats <= 0) {
+            return invalid("At least one message has to be allowed within the spam window");
+        }
+        if self.spam_window_seconds <= 0 {
+            return invalid("The spam window has to last at least one second");
+        }
+        if self.punish_after_violations <= 0 {
+            return invalid("Players can only be punished after at least one violation");
+        }
+        if self.violation_window_minutes <= 0 {
+            return invalid("The violation window has to last at least one minute");
+        }
+
+        for rule in &self.rules {
+            CompiledRule::compile(&rule.kind, &rule.pattern, &rule.action)?;
+        }
+
+        Ok(())
+    }
+}
+
+enum Matcher {
+    // Matches normalised words, or every word starting with it when the pattern ends in *
+    Word { word: String, prefix: bool },
+    Regex(Regex),
+}
+
+struct CompiledRule {
+    reason: String,
+    matcher: Matcher,
+    action: FilterAction,
+}
+
+impl CompiledRule {
+    fn compile(kind: &str, pattern: &str, action: &str) -> AppResult<Self> {
+        let action = FilterAction::parse(action)?;
+
+        let matcher = match kind {
+            "word" => {
+                let (word, prefix) = match pattern.trim().strip_suffix('*') {
+                    Some(word) => (word, true),
+                    None => (pattern.trim(), false),
+                };
+                let normalised: Option<String> = word.chars().map(normalise_char).collect();
+
+                match normalised {
+                    Some(word) if !word.is_empty() => Matcher::Word { word, prefix },
+                    _ => {
+                        return Err(AppError::CustomValidationError(format!(
+                            "Word rules match a single word, use a regex rule for {}",
+                            pattern
+                        )));
+                    }
+                }
+            }
+            "regex" => {
+                let regex = RegexBuilder::new(pattern)
+                    .case_insensitive(true)
+                    .size_limit(1 << 20)
+                    .build()
+                    .map_err(|e| AppError::CustomValidationError(format!("Invalid regex {}: {}", pattern, e)))?;
+                Matcher::Regex(regex)
+            }
+            _ => {
+                return Err(AppError::CustomValidationError(format!(
+                    "Unknown rule kind: {}, expected word or regex",
+                    kind
+                )));
+            }
+        };
+
+        Ok(Self {
+            reason: format!("{} rule {}", kind, pattern),
+            matcher,
+            action,
+        })
+    }
+
+    fn matches(&self, message: &str, words: &[Word]) -> Vec<Range<usize>> {
+        match &self.matcher {
+            Matcher::Word { word, prefix } => words
+                .iter()
+                .filter(|candidate| match prefix {
+                    true => candidate.normalised.starts_with(word.as_str()),
+                    false => candidate.normalised == *word,
+                })
+                .map(|candidate| candidate.span.clone())
+                .collect(),
+            Matcher::Regex(regex) => regex
+                .find_iter(message)
+                .filter(|found| !found.is_empty())
+                .map(|found| found.range())
+                .collect(),
+        }
+    }
+}
+
+/// Maps leetspeak to the letters it stands for and lowercases, None for characters between words.
+fn normalise_char(c: char) -> Option<char> {
+    match c {
+        '0' => Some('o'),
+        '1' => Some('i'),
+        '3' => Some('e'),
+        '4' | '@' => Some('a'),
+        '5' | '$' => Some('s'),
+        '7' => Some('t'),
+        '8' => Some('b'),
+        c if c.is_alphanumeric() => c.to_lowercase().next(),
+        _ => None,
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct Word {
+    span: Range<usize>,
+    normalised: String,
+}
+
+/// Splits the message into normalised words. Runs of single characters are joined as well, so
+/// "f u c k" and "f.u.c.k" are checked as one word.
+fn split_words(message: &str) -> Vec<Word> {
+    let mut words: Vec<Word> = Vec::new();
+    let mut current: Option<Word> = None;
+
+    for (index, c) in message.char_indices() {
+        match (normalise_char(c), current.as_mut()) {
+            (Some(normalised), Some(word)) => {
+                word.span.end = index + c.len_utf8();
+                word.normalised.push(normalised);
+            }
+            (Some(normalised), None) => {
+                current = Some(Word {
+                    span: index..index + c.len_utf8(),
+                    normalised: normalised.to_string(),
+                });
+            }
+            (None, _) => words.extend(current.take()),
+        }
+    }
+    words.extend(current);
+
+    let mut joined = Vec::new();
+    let mut run: Option<Word> = None;
+    let mut run_length = 0;
+
+    for word in words.iter().chain(std::iter::once(&Word { span: 0..0, normalised: String::new() })) {
+        if word.normalised.chars().count() == 1 {
+            match run.as_mut() {
+                Some(run) => {
+                    run.span.end = word.span.end;
+                    run.normalised.push_str(&word.normalised);
+                }
+                None => run = Some(word.clone()),
+            }
+            run_length += 1;
+        } else {
+            if run_length > 1 {
+                joined.extend(run.take());
+            }
+            run = None;
+            run_length = 0;
+        }
+    }
+
+    words.extend(joined);
+    words
+}
+
+/// The host an advertising match points to, with spelled out dots, the scheme, port and path removed.
+fn advertised_host(found: &str) -> String {
+    let lowercase = found.to_lowercase();
+    let without_scheme = lowercase
+        .strip_prefix("https://")
+        .or_else(|| lowercase.strip_prefix("http://"))
+        .unwrap_or(&lowercase);
+    let host = SEPARATOR.replace_all(without_scheme, ".");
+
+    host.split(['/', ':', '?', '#']).next().unwrap_or_default().to_string()
+}
+
+fn is_ip_address(host: &str) -> bool {
+    let octets: Vec<&str> = host.split('.').collect();
+    octets.len() == 4 && octets.iter().all(|octet| octet.parse::<u8>().is_ok())
+}
+
+/// Key identical messages share for spam detection, ignoring case, spacing and punctuation.
+fn spam_key(message: &str) -> String {
+    let key: String = message
+        .chars()
+        .filter(|c| c.is_alphanumeric())
+        .flat_map(char::to_lowercase)
+        .collect();
+
+    match key.is_empty() {
+        true => message.trim().to_string(),
+        false => key,
+    }
+}
+
+/// Replaces every character inside the spans with an asterisk.
+fn censor(message: &str, spans: &[Range<usize>]) -> String {
+    message
+        .char_indices()
+        .map(|(index, c)| match spans.iter().any(|span| span.contains(&index)) {
+            true => '*',
+            false => c,
+        })
+        .collect()
+}
+
+/// What a player recently sent and how often the filter caught them, kept in memory by the backend.
+#[derive(Debug, Default)]
+pub struct PlayerChatState {
+    messages: VecDeque<(OffsetDateTime, String)>,
+    violations: VecDeque<OffsetDateTime>,
+}
+
+impl PlayerChatState {
+    /// Records the message and returns how often it was sent since `since`, this time included.
+    fn record_message(&mut self, key: String, now: OffsetDateTime, since: OffsetDateTime) -> usize {
+        while self.messages.front().is_some_and(|(sent_at, _)| *sent_at < since) {
+            self.messages.pop_front();
+        }
+
+        let repeats = self.messages.iter().filter(|(_, sent)| *sent == key).count() + 1;
+        self.messages.push_back((now, key));
+        repeats
+    }
+
+    /// Records a violation and returns the violations since `since`, this one included.
+    fn record_violation(&mut self, now: OffsetDateTime, since: OffsetDateTime) -> usize {
+        while self.violations.front().is_some_and(|caught_at| *caught_at < since) {
+            self.violations.pop_front();
+        }
+
+        self.violations.push_back(now);
+        self.violations.len()
+    }
+
+    /// Whether nothing in the state happened since `cutoff`, so it can be dropped.
+    pub fn is_stale(&self, cutoff: OffsetDateTime) -> bool {
+        self.messages.back().is_none_or(|(sent_at, _)| *sent_at < cutoff)
+            && self.violations.back().is_none_or(|caught_at| *caught_at < cutoff)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct FilterVerdict {
+    pub action: FilterAction,
+    // The message to send, censored when the action is Censor
+    pub message: String,
+    pub reasons: Vec<String>,
+    // Violations within the window, this message included
+    pub violations: usize,
+    // Set once the violations reach the threshold, the count starts over afterwards
+    pub punish_category_id: Option<i32>,
+}
+
+/// The configured filter, compiled once and shared by every CheckChat call.
+pub struct ChatFilter {
+    enabled: bool,
+    rules: Vec<CompiledRule>,
+    advertising_action: Option<FilterAction>,
+    allowed_domains: Vec<String>,
+    spam_max_repeats: Option<usize>,
+    spam_window: Duration,
+    punish_category_id: Option<i32>,
+    punish_after_violations: usize,
+    violation_window: Duration,
+}
+
+impl ChatFilter {
+    pub fn new(settings: &ChatFilterSettings, rules: &[ChatFilterRule]) -> AppResult<Self> {
+        Ok(Self {
+            enabled: settings.enabled,
+            rules: rules
+                .iter()
+                .map(|rule| CompiledRule::compile(&rule.kind, &rule.pattern, &rule.action))
+                .collect::<AppResult<_>>()?,
+            advertising_action: settings.advertising_action.as_deref().map(FilterAction::parse).transpose()?,
+            allowed_domains: settings.allowed_domains.clone(),
+            spam_max_repeats: settings.spam_max_repeats.map(|repeats| repeats as usize),
+            spam_window: Duration::seconds(settings.spam_window_seconds as i64),
+            punish_category_id: settings.punish_category_id,
+            punish_after_violations: settings.punish_after_violations as usize,
+            violation_window: Duration::minutes(settings.violation_window_minutes as i64),
+        })
+    }
+
+    /// How long a player's state matters after their last message.
+    pub fn memory(&self) -> Duration {
+        self.spam_window.max(self.violation_window)
+    }
+
+    pub fn check(&self, message: &str, state: &mut PlayerChatState, now: OffsetDateTime) -> FilterVerdict {
+        let mut action = FilterAction::Allow;
+        let mut reasons = Vec::new();
+        let mut censored = Vec::new();
+
+        if !self.enabled {
+            return FilterVerdict {
+                action,
+                message: message.to_string(),
+                reasons,
+                violations: 0,
+                punish_category_id: None,
+            };
+        }
+
+        let words = split_words(message);
+        for rule in &self.rules {
+            let spans = rule.matches(message, &words);
+            if !spans.is_empty() {
+                action = action.max(rule.action);
+                reasons.push(rule.reason.clone());
+                censored.extend(spans);
+            }
+        }
+
+        if let Some(advertising_action) = self.advertising_action {
+            let spans: Vec<_> = ADVERTISING
+                .find_iter(message)
+                .filter(|found| {
+                    let host = advertised_host(found.as_str());
+                    let looks_like_ip = host.chars().all(|c| c.is_ascii_digit() || c == '.');
+
+                    (!looks_like_ip || is_ip_address(&host))
+                        && !self.allowed_domains.iter().any(|allowed| {
+                            host == *allowed || host.ends_with(&format!(".{}", allowed))
+                        })
+                })
+                .map(|found| found.range())
+                .collect();
+
+            if !spans.is_empty() {
+                action = action.max(advertising_action);
+                reasons.push("advertising".to_string());
+                censored.extend(spans);
+            }
+        }
+
+        if let Some(max_repeats) = self.spam_max_repeats {
+            let repeats = state.record_message(spam_key(message), now, now - self.spam_window);
+            if repeats > max_repeats {
+                action = FilterAction::Block;
+                reasons.push("spam".to_string());
+            }
+        }
+
+        let mut violations = 0;
+        let mut punish_category_id = None;
+        if action != FilterAction::Allow {
+            violations = state.record_violation(now, now - self.violation_window);
+
+            if violations >= self.punish_after_violations && self.punish_category_id.is_some() {
+                punish_category_id = self.punish_category_id;
+                state.violations.clear();
+            }
+        }
+
+        FilterVerdict {
+            action,
+            message: match action {
+                FilterAction::Censor => censor(message, &censored),
+                _ => message.to_string(),
+            },
+            reasons,
+            violations,
+            punish_category_id,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn make_settings() -> ChatFilterSettings {
+        ChatFilterSettings {
+            enabled: true,
+            advertising_action: None,
+            allowed_domains: Vec::new(),
+            spam_max_repeats: None,
+            spam_window_seconds: 30,
+            punish_category_id: None,
+            punish_after_violations: 3,
+            violation_window_minutes: 10,
+            updated_by: None,
+            updated_at: OffsetDateTime::UNIX_EPOCH,
+        }
+    }
+
+    fn make_rule(kind: &str, pattern: &str, action: &str) -> ChatFilterRule {
+        ChatFilterRule {
+            id: 1,
+            kind: kind.to_string(),
+            pattern: pattern.to_string(),
+            action: action.to_string(),
+            created_at: OffsetDateTime::UNIX_EPOCH,
+        }
+    }
+
+    fn make_update() -> ChatFilterUpdate {
+        ChatFilterUpdate {
+            enabled: true,
+            advertising_action: Some("block".to_string()),
+            allowed_domains: vec!["example.net".to_string()],
+            spam_max_repeats: Some(2),
+            spam_window_seconds: 30,
+            punish_category_id: None,
+            punish_after_violations: 3,
+            violation_window_minutes: 10,
+            rules: Vec::new(),
+        }
+    }
+
+    fn check(filter: &ChatFilter, message: &str) -> FilterVerdict {
+        filter.check(message, &mut PlayerChatState::default(), OffsetDateTime::UNIX_EPOCH)
+    }
+
+    fn advertising_filter(action: &str, allowed_domains: &[&str]) -> ChatFilter {
+        let settings = ChatFilterSettings {
+            advertising_action: Some(action.to_string()),
+            allowed_domains: allowed_domains.iter().map(|domain| domain.to_string()).collect(),
+            ..make_settings()
+        };
+        ChatFilter::new(&settings, &[]).unwrap()
+    }
+
+    // ── Word rules ───────────────────────────────────────────────────────────
+
+    #[test]
+    fn censors_words_written_in_leetspeak() {
+        let filter = ChatFilter::new(&make_settings(), &[make_rule("word", "noob", "censor")]).unwrap();
+
+        let verdict = check(&filter, "you N00B, go away");
+        assert_eq!(verdict.action, FilterAction::Censor);
+        assert_eq!(verdict.message, "you ****, go away");
+        assert_eq!(verdict.reasons, vec!["word rule noob".to_string()]);
+    }
+
+    #[test]
+    fn word_rules_match_whole_words_only() {
+        let filter = ChatFilter::new(&make_settings(), &[make_rule("word", "ass", "block")]).unwrap();
+
+        assert_eq!(check(&filter, "nice class, I pass").action, FilterAction::Allow);
+        assert_eq!(check(&filter, "you a$$").action, FilterAction::Block);
+    }
+
+    #[test]
+    fn trailing_asterisk_matches_prefixes() {
+        let filter = ChatFilter::new(&make_settings(), &[make_rule("word", "idiot*", "censor")]).unwrap();
+
+        assert_eq!(check(&filter, "such idiots here").message, "such ****** here");
+        assert_eq!(check(&filter, "an idiom").action, FilterAction::Allow);
+    }
+
+    #[test]
+    fn spaced_out_letters_are_joined() {
+        let filter = ChatFilter::new(&make_settings(), &[make_rule("word", "noob", "censor")]).unwrap();
+
+        assert_eq!(check(&filter, "hi n o o b").message, "hi *******");
+        assert_eq!(check(&filter, "n.0.o.b!").message, "*******!");
+    }
+
+    #[test]
+    fn splits_words_with_byte_spans() {
+        assert_eq!(
+            split_words("héllo w0rld"),
+            vec![
+                Word { span: 0..6, normalised: "héllo".to_string() },
+                Word { span: 7..12, normalised: "world".to_string() },
+            ]
+        );
+    }
+
+    // ── Regex rules ──────────────────────────────────────────────────────────
+
+    #[test]
+    fn regex_rules_ignore_case() {
+        let filter = ChatFilter::new(&make_settings(), &[make_rule("regex", r"free\s+rank", "censor")]).unwrap();
+
+        assert_eq!(check(&filter, "get a FREE  Rank now").message, "get a ********** now");
+    }
+
+    #[test]
+    fn harshest_rule_wins() {
+        let rules = [make_rule("word", "noob", "censor"), make_rule("regex", "discord", "block")];
+        let filter = ChatFilter::new(&make_settings(), &rules).unwrap();
+
+        let verdict = check(&filter, "noob, join my discord");
+        assert_eq!(verdict.action, FilterAction::Block);
+        assert_eq!(verdict.message, "noob, join my discord");
+        assert_eq!(verdict.reasons.len(), 2);
+    }
+
+    // ── Advertising ──────────────────────────────────────────────────────────
+
+    #[test]
+    fn detects_links_and_domains() {
+        let filter = advertising_filter("block", &[]);
+
+        assert_eq!(check(&filter, "join https://example.com/vote").action, FilterAction::Block);
+        assert_eq!(check(&filter, "join play.coolserver.net").action, FilterAction::Block);
+        assert_eq!(check(&filter, "join coolserver dot net").action, FilterAction::Block);
+        assert_eq!(check(&filter, "join coolserver(dot)gg").action, FilterAction::Block);
+    }
+
+    #[test]
+    fn detects_ip_addresses() {
+        let filter = advertising_filter("censor", &[]);
+
+        assert_eq!(check(&filter, "ip: 203.0.113.7:25565 !").message, "ip: ***************** !");
+        assert_eq!(check(&filter, "203 dot 0 dot 113 dot 7").action, FilterAction::Censor);
+        assert_eq!(check(&filter, "version 1.20.999.4").action, FilterAction::Allow);
+    }
+
+    #[test]
+    fn ordinary_chat_is_not_advertising() {
+        let filter = advertising_filter("block", &[]);
+
+        assert_eq!(check(&filter, "I won. gg").action, FilterAction::Allow);
+        assert_eq!(check(&filter, "meet me at 5.30").action, FilterAction::Allow);
+    }
+
+    #[test]
+    fn allowed_domains_include_subdomains() {
+        let filter = advertising_filter("block", &["example.net"]);
+
+        assert_eq!(check(&filter, "vote at https://vote.example.net/today").action, FilterAction::Allow);
+        assert_eq!(check(&filter, "store.example(dot)net").action, FilterAction::Allow);
+        assert_eq!(check(&filter, "see notexample.net").action, FilterAction::Block);
+    }
+
+    #[test]
+    fn advertising_is_ignored_without_an_action() {
+        let filter = ChatFilter::new(&make_settings(), &[]).unwrap();
+        assert_eq!(check(&filter, "join play.coolserver.net").action, FilterAction::Allow);
+    }
+
+    // ── Spam ─────────────────────────────────────────────────────────────────
+
+    #[test]
+    fn blocks_repeats_within_the_window() {
+        let settings = ChatFilterSettings {
+            spam_max_repeats: Some(2),
+            ..make_settings()
+        };
+        let filter = ChatFilter::new(&settings, &[]).unwrap();
+        let mut state = PlayerChatState::default();
+        let start = OffsetDateTime::UNIX_EPOCH;
+
+        assert_eq!(filter.check("Hello!", &mut state, start).action, FilterAction::Allow);
+        assert_eq!(filter.check("hello", &mut state, start + Duration::seconds(1)).action, FilterAction::Allow);
+
+        let verdict = filter.check("HELLO", &mut state, start + Duration::seconds(2));
+        assert_eq!(verdict.action, FilterAction::Block);
+        assert_eq!(verdict.reasons, vec!["spam".to_string()]);
+
+        assert_eq!(filter.check("hello", &mut state, start + Duration::seconds(40)).action, FilterAction::Allow);
+    }
+
+    // ── Violations ───────────────────────────────────────────────────────────
+
+    #[test]
+    fn punishes_once_the_violations_reach_the_threshold() {
+        let settings = ChatFilterSettings {
+            punish_category_id: Some(4),
+            punish_after_violations: 2,
+            ..make_settings()
+        };
+        let filter = ChatFilter::new(&settings, &[make_rule("word", "noob", "censor")]).unwrap();
+        let mut state = PlayerChatState::default();
+        let start = OffsetDateTime::UNIX_EPOCH;
+
+        let first = filter.check("noob", &mut state, start);
+        assert_eq!((first.violations, first.punish_category_id), (1, None));
+
+        assert_eq!(filter.check("fine", &mut state, start).violations, 0);
+
+        let second = filter.check("noob", &mut state, start + Duration::minutes(1));
+        assert_eq!((second.violations, second.punish_category_id), (2, Some(4)));
+
+        // The count starts over once the player was punished
+        assert_eq!(filter.check("noob", &mut state, start + Duration::minutes(2)).violations, 1);
+    }
+
+    #[test]
+    fn violations_expire_after_the_window() {
+        let settings = ChatFilterSettings {
+            punish_category_id: Some(4),
+            punish_after_violations: 2,
+            ..make_settings()
+        };
+        let filter = ChatFilter::new(&settings, &[make_rule("word", "noob", "censor")]).unwrap();
+        let mut state = PlayerChatState::default();
+        let start = OffsetDateTime::UNIX_EPOCH;
+
+        filter.check("noob", &mut state, start);
+        let later = filter.check("noob", &mut state, start + Duration::minutes(11));
+        assert_eq!((later.violations, later.punish_category_id), (1, None));
+        assert!(!state.is_stale(start + Duration::minutes(5)));
+        assert!(state.is_stale(start + Duration::minutes(12)));
+    }
+
+    #[test]
+    fn disabled_filter_allows_everything() {
+        let settings = ChatFilterSettings {
+            enabled: false,
+            ..make_settings()
+        };
+        let filter = ChatFilter::new(&settings, &[make_rule("word", "noob", "block")]).unwrap();
+
+        assert_eq!(check(&filter, "noob").action, FilterAction::Allow);
+    }
+
+    // ── ChatFilterUpdate ─────────────────────────────────────────────────────
+
+    #[test]
+    fn accepts_a_valid_update() {
+        let update = ChatFilterUpdate {
+            rules: vec![
+                NewChatFilterRule { kind: "word".to_string(), pattern: "noob*".to_string(), action: "censor".to_string() },
+                NewChatFilterRule { kind: "regex".to_string(), pattern: r"free\s+rank".to_string(), action: "block".to_string() },
+            ],
+            ..make_update()
+        };
+        assert!(update.validate().is_ok());
+    }
+
+    #[test]
+    fn rejects_invalid_rules() {
+        let with_rule = |kind: &str, pattern: &str, action: &str| ChatFilterUpdate {
+            rules: vec![NewChatFilterRule { kind: kind.to_string(), pattern: pattern.to_string(), action: action.to_string() }],
+            ..make_update()
+        };
+
+        assert!(with_rule("word", "two words", "block").validate().is_err());
+        assert!(with_rule("word", "*", "block").validate().is_err());
+        assert!(with_rule("regex", "(unclosed", "block").validate().is_err());
+        assert!(with_rule("regex", "fine", "allow").validate().is_err());
+        assert!(with_rule("phrase", "fine", "block").validate().is_err());
+    }
+
+    #[test]
+    fn rejects_invalid_settings() {
+        assert!(ChatFilterUpdate { advertising_action: Some("warn".to_string()), ..make_update() }.validate().is_err());
+        assert!(ChatFilterUpdate { allowed_domains: vec![String::new()], ..make_update() }.validate().is_err());
+        assert!(ChatFilterUpdate { spam_max_repeats: Some(0), ..make_update() }.validate().is_err());
+        assert!(ChatFilterUpdate { spam_window_seconds: 0, ..make_update() }.validate().is_err());
+        assert!(ChatFilterUpdate { punish_after_violations: 0, ..make_update() }.validate().is_err());
+        assert!(ChatFilterUpdate { violation_window_minutes: -1, ..make_update() }.validate().is_err());
+    }
+}
@@ -0,0 +1,234 @@
+use crate::error::{AppError, AppResult};
+use crate::grpc::generated;
+use crate::grpc::generated::evidence_target::Target;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use sqlx::FromRow;
+use std::str::FromStr;
+use time::OffsetDateTime;
+use uuid::Uuid;
+
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize)]
+pub struct Evidence {
+    pub id: Uuid,
+    pub kind: String,
+    pub file_name: Option<String>,
+    pub content_type: String,
+    pub size_bytes: i64,
+    pub sha256: String,
+    pub uploaded_by: Uuid,
+    pub created_at: OffsetDateTime,
+}
+
+impl From<Evidence> for generated::Evidence {
+    fn from(evidence: Evidence) -> Self {
+        generated::Evidence {
+            id: evidence.id.to_string(),
+            kind: evidence.kind,
+            file_name: evidence.file_name,
+            content_type: evidence.content_type,
+            size_bytes: evidence.size_bytes,
+            sha256: evidence.sha256,
+            uploaded_by: evidence.uploaded_by.to_string(),
+            created_at: evidence.created_at.unix_timestamp(),
+        }
+    }
+}
+
+/// What evidence can be attached to.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum EvidenceTarget {
+    Punishment(Uuid),
+    Appeal(Uuid),
+    Report(Uuid),
+}
+
+impl EvidenceTarget {
+    /// Table of the target and the evidence_links column pointing at it.
+    pub fn table_and_column(&self) -> (&'static str, &'static str) {
+        match self {
+            Self::Punishment(_) => ("punishments", "punishment_id"),
+            Self::Appeal(_) => ("appeals", "appeal_id"),
+            Self::Report(_) => ("reports", "report_id"),
+        }
+    }
+
+    pub fn id(&self) -> Uuid {
+        match self {
+            Self::Punishment(id) | Self::Appeal(id) | Self::Report(id) => *id,
+        }
+    }
+}
+
+impl TryFrom<generated::EvidenceTarget> for EvidenceTarget {
+    type Error = AppError;
+
+    fn try_from(target: generated::EvidenceTarget) -> Result<Self, Self::Error> {
+        match target.target {
+            Some(Target::PunishmentId(id)) => Ok(Self::Punishment(Uuid::from_str(&id)?)),
+            Some(Target::AppealId(id)) => Ok(Self::Appeal(Uuid::from_str(&id)?)),
+            Some(Target::ReportId(id)) => Ok(Self::Report(Uuid::from_str(&id)?)),
+            None => Err(AppError::CustomValidationError("The evidence target is missing".to_string())),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct NewEvidence {
+    pub kind: String,
+    pub file_name: Option<String>,
+    pub content_type: String,
+}
+
+impl NewEvidence {
+    /// Fills in the content type the kind implies when the uploader left it out.
+    pub fn new(kind: String, file_name: Option<String>, content_type: Option<String>) -> AppResult<Self> {
+        let default_content_type = match kind.as_str() {
+            "file" => "application/octet-stream",
+            "text" => "text/plain; charset=utf-8",
+            _ => return Err(AppError::CustomValidationError(format!("Unknown evidence kind: {}", kind))),
+        };
+
+        let file_name = file_name.map(|name| name.trim().to_string()).filter(|name| !name.is_empty());
+        if file_name.as_ref().is_some_and(|name| name.chars().count() > 255) {
+            return Err(AppError::CustomValidationError("File names can be at most 255 characters long".to_string()));
+        }
+
+        let content_type = content_type.filter(|content_type| !content_type.trim().is_empty());
+        if content_type.as_ref().is_some_and(|content_type| content_type.len() > 100) {
+            return Err(AppError::CustomValidationError("Content types can be at most 100 characters long".to_string()));
+        }
+
+        Ok(Self {
+            kind,
+            file_name,
+            content_type: content_type.unwrap_or_else(|| default_content_type.to_string()),
+        })
+    }
+}
+
+/// Collects the chunks of an upload, hashing them as they arrive and enforcing the size limit.
+pub struct EvidenceUpload {
+    data: Vec<u8>,
+    hasher: Sha256,
+    max_bytes: usize,
+}
+
+impl EvidenceUpload {
+    pub fn new(max_bytes: usize) -> Self {
+        Self {
+            data: Vec::new(),
+            hasher: Sha256::new(),
+            max_bytes,
+        }
+    }
+
+    pub fn push(&mut self, chunk: &[u8]) -> AppResult<()> {
+        if self.data.len() + chunk.len() > self.max_bytes {
+            return Err(AppError::CustomValidationError(format!(
+                "Evidence can be at most {} bytes",
+                self.max_bytes
+            )));
+        }
+
+        self.hasher.update(chunk);
+        self.data.extend_from_slice(chunk);
+
+        Ok(())
+    }
+
+    /// Returns the contents and their digest, checking them against what the uploader expects.
+    pub fn finish(self, kind: &str, expected_sha256: Option<&str>) -> AppResult<(Vec<u8>, String)> {
+        if self.data.is_empty() {
+            return Err(AppError::CustomValidationError("The evidence is empty".to_string()));
+        }
+
+        if kind == "text" && std::str::from_utf8(&self.data).is_err() {
+            return Err(AppError::CustomValidationError("Text evidence has to be valid UTF-8".to_string()));
+        }
+
+        let sha256 = format!("{:x}", self.hasher.finalize());
+        if let Some(expected) = expected_sha256
+            && !expected.eq_ignore_ascii_case(&sha256)
+        {
+            return Err(AppError::CustomValidationError(format!(
+                "The upload has the digest {}, not {}, it was damaged on the way",
+                sha256, expected
+            )));
+        }
+
+        Ok((self.data, sha256))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // SHA-256 of "hello world"
+    const HELLO_WORLD: &str = "b94d27b9934d3e08a52e52d7da7dabfac484efe37a5380ee9088f7ace2efcde9";
+
+    // ── EvidenceUpload ───────────────────────────────────────────────────────
+
+    #[test]
+    fn hashes_the_chunks_together() {
+        let mut upload = EvidenceUpload::new(1024);
+        upload.push(b"hello ").unwrap();
+        upload.push(b"world").unwrap();
+
+        let (data, sha256) = upload.finish("text", Some(&HELLO_WORLD.to_uppercase())).unwrap();
+        assert_eq!(data, b"hello world");
+        assert_eq!(sha256, HELLO_WORLD);
+    }
+
+    #[test]
+    fn rejects_uploads_over_the_limit() {
+        let mut upload = EvidenceUpload::new(8);
+        upload.push(b"hello ").unwrap();
+        assert!(upload.push(b"world").is_err());
+    }
+
+    #[test]
+    fn rejects_damaged_uploads() {
+        let mut upload = EvidenceUpload::new(1024);
+        upload.push(b"hello world!").unwrap();
+        assert!(upload.finish("file", Some(HELLO_WORLD)).is_err());
+    }
+
+    #[test]
+    fn rejects_empty_uploads_and_binary_text() {
+        assert!(EvidenceUpload::new(1024).finish("file", None).is_err());
+
+        let mut upload = EvidenceUpload::new(1024);
+        upload.push(&[0xff, 0xfe]).unwrap();
+        assert!(upload.finish("text", None).is_err());
+    }
+
+    // ── EvidenceTarget ───────────────────────────────────────────────────────
+
+    #[test]
+    fn parses_report_targets() {
+        let id = Uuid::new_v4();
+        let target = EvidenceTarget::try_from(generated::EvidenceTarget {
+            target: Some(Target::ReportId(id.to_string())),
+        })
+        .unwrap();
+
+        assert_eq!(target, EvidenceTarget::Report(id));
+        assert_eq!(target.table_and_column(), ("reports", "report_id"));
+    }
+
+    // ── NewEvidence ──────────────────────────────────────────────────────────
+
+    #[test]
+    fn defaults_the_content_type_by_kind() {
+        let text = NewEvidence::new("text".to_string(), Some("  ".to_string()), None).unwrap();
+        assert_eq!(text.content_type, "text/plain; charset=utf-8");
+        assert_eq!(text.file_name, None);
+
+        let file = NewEvidence::new("file".to_string(), Some("clip.mp4".to_string()), Some("video/mp4".to_string())).unwrap();
+        assert_eq!(file.content_type, "video/mp4");
+
+        assert!(NewEvidence::new("link".to_string(), None, None).is_err());
+    }
+}
@@ -0,0 +1,137 @@
+use crate::grpc::generated;
+use crate::models::PunishmentApproval;
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use time::OffsetDateTime;
+use uuid::Uuid;
+
+/// A player frozen by staff, e.g. for a screenshare, until released or disconnected.
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize)]
+pub struct Hold {
+    pub id: Uuid,
+    pub player_uuid: Uuid,
+    pub staff_uuid: Uuid,
+    pub reason: Option<String>,
+    pub created_at: OffsetDateTime,
+    pub ended_at: Option<OffsetDateTime>,
+    pub ended_by: Option<Uuid>,
+    pub outcome: Option<String>,
+    pub punishment_id: Option<Uuid>,
+}
+
+impl Hold {
+    pub fn is_active(&self) -> bool {
+        self.ended_at.is_none()
+    }
+
+    /// Note on the punishment issued because the player disconnected while held.
+    pub fn disconnect_note(&self, disconnected_at: OffsetDateTime) -> String {
+        let held_for = (disconnected_at - self.created_at).whole_seconds().max(0);
+        let note = format!(
+            "Issued automatically: disconnected {}m {}s into a hold by {}",
+            held_for / 60,
+            held_for % 60,
+            self.staff_uuid
+        );
+
+        match &self.reason {
+            Some(reason) => format!("{} ({})", note, reason),
+            None => note,
+        }
+    }
+}
+
+impl From<Hold> for generated::Hold {
+    fn from(hold: Hold) -> Self {
+        generated::Hold {
+            active: hold.is_active(),
+            id: hold.id.to_string(),
+            player_id: hold.player_uuid.to_string(),
+            staff_id: hold.staff_uuid.to_string(),
+            reason: hold.reason,
+            created_at: hold.created_at.unix_timestamp(),
+            ended_at: hold.ended_at.map(|ended_at| ended_at.unix_timestamp()),
+            ended_by: hold.ended_by.map(|uuid| uuid.to_string()),
+            outcome: hold.outcome,
+            punishment_id: hold.punishment_id.map(|id| id.to_string()),
+        }
+    }
+}
+
+/// The hold of a player who left the network, with the punishment queued for approval when the
+/// disconnect punishment, or the automatic ban it caused, needs approval.
+#[derive(Debug, Clone)]
+pub struct HoldDisconnect {
+    pub hold: Hold,
+    pub approval: Option<PunishmentApproval>,
+}
+
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize)]
+pub struct HoldSettings {
+    pub disconnect_category_id: Option<i32>,
+    pub updated_by: Option<Uuid>,
+    pub updated_at: OffsetDateTime,
+}
+
+impl From<HoldSettings> for generated::HoldSettingsResponse {
+    fn from(settings: HoldSettings) -> Self {
+        generated::HoldSettingsResponse {
+            disconnect_category_id: settings.disconnect_category_id,
+            updated_by: settings.updated_by.map(|uuid| uuid.to_string()),
+            updated_at: settings.updated_at.unix_timestamp(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use time::Duration;
+
+    fn make_hold(reason: Option<&str>) -> Hold {
+        Hold {
+            id: Uuid::new_v4(),
+            player_uuid: Uuid::new_v4(),
+            staff_uuid: Uuid::nil(),
+            reason: reason.map(str::to_string),
+            created_at: OffsetDateTime::now_utc(),
+            ended_at: None,
+            ended_by: None,
+            outcome: None,
+            punishment_id: None,
+        }
+    }
+
+    // ── disconnect_note ──────────────────────────────────────────────────────
+
+    #[test]
+    fn note_names_the_time_held_and_the_staff_member() {
+        let hold = make_hold(None);
+        let note = hold.disconnect_note(hold.created_at + Duration::seconds(135));
+
+        assert_eq!(
+            note,
+            "Issued automatically: disconnected 2m 15s into a hold by 00000000-0000-0000-0000-000000000000"
+        );
+    }
+
+    #[test]
+    fn note_includes_the_reason() {
+        let hold = make_hold(Some("Suspected killaura"));
+        let note = hold.disconnect_note(hold.created_at);
+
+        assert!(note.ends_with("0s into a hold by 00000000-0000-0000-0000-000000000000 (Suspected killaura)"));
+    }
+
+    // ── Hold ─────────────────────────────────────────────────────────────────
+
+    #[test]
+    fn ended_holds_are_inactive() {
+        let mut hold = make_hold(None);
+        assert!(generated::Hold::from(hold.clone()).active);
+
+        hold.ended_at = Some(OffsetDateTime::now_utc());
+        hold.outcome = Some("released".to_string());
+        assert!(!generated::Hold::from(hold).active);
+    }
+}
@@ -0,0 +1,35 @@
+use crate::grpc::generated;
+use crate::models::{Hold, PunishmentEvent};
+use serde::{Deserialize, Serialize};
+use time::OffsetDateTime;
+use uuid::Uuid;
+
+/// Everything delivered to the proxies over the live punishment stream.
+#[derive(Debug, Clone)]
+pub enum LiveEvent {
+    Punishment(Box<PunishmentEvent>),
+    StaffAlert(StaffAlert),
+    Hold(Box<Hold>),
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StaffAlert {
+    pub alert_type: String,
+    pub message: String,
+    pub player_uuid: Uuid,
+    pub related_player_uuids: Vec<Uuid>,
+    pub created_at: OffsetDateTime,
+}
+
+impl StaffAlert {
+    pub fn to_message(&self, recipient: Uuid) -> generated::StaffAlert {
+        generated::StaffAlert {
+            recipient_id: recipient.to_string(),
+            r#type: self.alert_type.clone(),
+            message: self.message.clone(),
+            player_id: self.player_uuid.to_string(),
+            related_player_ids: self.related_player_uuids.iter().map(Uuid::to_string).collect(),
+            created_at: self.created_at.unix_timestamp(),
+        }
+    }
+}
@@ -1,6 +1,30 @@
 pub mod player;
 pub mod punishment;
 pub mod message;
+pub mod live;
+pub mod role;
+pub mod policy;
+pub mod audit;
+pub mod approval;
+pub mod severity;
+pub mod revision;
+pub mod evidence;
+pub mod chat;
+pub mod chat_filter;
+pub mod hold;
+pub mod report;
+pub use approval::*;
+pub use audit::*;
+pub use chat::*;
+pub use chat_filter::*;
+pub use evidence::*;
+pub use hold::*;
+pub use live::*;
 pub use message::*;
 pub use player::*;
-pub use punishment::*;
\ No newline at end of file
+pub use policy::*;
+pub use punishment::*;
+pub use report::*;
+pub use revision::*;
+pub use role::*;
+pub use severity::*;
\ No newline at end of file
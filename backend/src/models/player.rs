@@ -1,8 +1,17 @@
+use crate::error::{AppError, AppResult};
+use crate::grpc::generated;
+use crate::models::PunishmentWithTemplate;
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
+use sqlx::types::ipnetwork::IpNetwork;
 use sqlx::FromRow;
+use std::fmt;
+use std::str::FromStr;
 use uuid::Uuid;
 
+/// The backend's own account, the staff member of punishments it issues by itself.
+pub const SYSTEM_PLAYER_UUID: Uuid = Uuid::nil();
+
 #[derive(Debug, Clone, FromRow, Serialize, Deserialize)]
 pub struct Player {
     pub uuid: Uuid,
@@ -15,6 +24,7 @@ pub struct Player {
     pub tokens_invalidated_before: Option<DateTime<Utc>>,
 
     pub staff: bool,
+    pub role_id: Option<i32>,
 
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
@@ -46,4 +56,202 @@ pub struct RefreshRequest {
 pub struct RefreshResponse {
     pub access_token: String,
     pub refresh_token: String,
-}
\ No newline at end of file
+}
+
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize)]
+pub struct AltAccount {
+    pub player_uuid: Uuid,
+    pub username: Option<String>,
+    pub depth: i32,
+    pub linked_player_uuid: Uuid,
+    pub shared_address: IpNetwork,
+    pub last_seen: DateTime<Utc>,
+    pub banned: bool,
+}
+
+impl From<AltAccount> for generated::AltAccount {
+    fn from(alt: AltAccount) -> Self {
+        generated::AltAccount {
+            player_id: alt.player_uuid.to_string(),
+            username: alt.username,
+            depth: alt.depth,
+            linked_player_id: alt.linked_player_uuid.to_string(),
+            shared_address: alt.shared_address.ip().to_string(),
+            last_seen: alt.last_seen.timestamp(),
+            banned: alt.banned,
+        }
+    }
+}
+
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize)]
+pub struct BannedAlt {
+    pub username: Option<String>,
+    #[sqlx(flatten)]
+    pub ban: PunishmentWithTemplate,
+}
+
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize)]
+pub struct PlayerNameHistory {
+    pub username: String,
+    pub first_seen: DateTime<Utc>,
+    pub last_seen: DateTime<Utc>,
+}
+
+impl From<PlayerNameHistory> for generated::NameHistoryEntry {
+    fn from(entry: PlayerNameHistory) -> Self {
+        generated::NameHistoryEntry {
+            username: entry.username,
+            first_seen: entry.first_seen.timestamp(),
+            last_seen: entry.last_seen.timestamp(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize)]
+pub struct PlayerActivity {
+    pub first_seen: Option<DateTime<Utc>>,
+    pub last_seen: Option<DateTime<Utc>>,
+    pub total_playtime_seconds: i64,
+    pub online: bool,
+}
+
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize)]
+pub struct PlayerSearchResult {
+    pub uuid: Uuid,
+    pub username: String,
+    pub matched_name: Option<String>,
+    pub staff: bool,
+}
+
+impl From<PlayerSearchResult> for generated::PlayerSearchResult {
+    fn from(result: PlayerSearchResult) -> Self {
+        generated::PlayerSearchResult {
+            player_id: result.uuid.to_string(),
+            username: result.username,
+            matched_name: result.matched_name,
+            staff: result.staff,
+        }
+    }
+}
+
+/// Position after the last result of a search page, results are ordered by lowercase username then UUID.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PlayerCursor {
+    pub username: String,
+    pub uuid: Uuid,
+}
+
+impl PlayerCursor {
+    pub fn after(result: &PlayerSearchResult) -> Self {
+        Self {
+            username: result.username.to_lowercase(),
+            uuid: result.uuid,
+        }
+    }
+}
+
+impl fmt::Display for PlayerCursor {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}:{}", self.username, self.uuid)
+    }
+}
+
+impl FromStr for PlayerCursor {
+    type Err = AppError;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        let invalid = || AppError::CustomValidationError(format!("Invalid cursor: {}", value));
+
+        let (username, uuid) = value.split_once(':').ok_or_else(invalid)?;
+        let uuid = Uuid::parse_str(uuid).map_err(|_| invalid())?;
+
+        Ok(Self {
+            username: username.to_string(),
+            uuid,
+        })
+    }
+}
+
+/// Turns a username prefix into a case-insensitive `LIKE` pattern.
+pub fn username_prefix_pattern(prefix: &str) -> AppResult<String> {
+    let valid = (1..=16).contains(&prefix.len())
+        && prefix.chars().all(|c| c.is_ascii_alphanumeric() || c == '_');
+
+    if !valid {
+        return Err(AppError::CustomValidationError(format!("Invalid username search: {}", prefix)));
+    }
+
+    // Underscores are valid in usernames but a wildcard in LIKE
+    Ok(format!("{}%", prefix.to_lowercase().replace('_', "\\_")))
+}
+
+/// Minecraft usernames are 3 to 16 characters of letters, digits and underscores.
+pub fn validate_username(username: &str) -> AppResult<()> {
+    let valid = (3..=16).contains(&username.len())
+        && username.chars().all(|c| c.is_ascii_alphanumeric() || c == '_');
+
+    if valid {
+        Ok(())
+    } else {
+        Err(AppError::CustomValidationError(format!("Invalid Minecraft username: {}", username)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // ── validate_username ────────────────────────────────────────────────────
+
+    #[test]
+    fn accepts_valid_usernames() {
+        assert!(validate_username("FishiGames").is_ok());
+        assert!(validate_username("a_b").is_ok());
+        assert!(validate_username("Sixteen_Chars_12").is_ok());
+    }
+
+    #[test]
+    fn rejects_invalid_usernames() {
+        assert!(validate_username("ab").is_err());
+        assert!(validate_username("Seventeen_Chars_1").is_err());
+        assert!(validate_username("has space").is_err());
+        assert!(validate_username("dash-name").is_err());
+    }
+
+    // ── username_prefix_pattern ──────────────────────────────────────────────
+
+    #[test]
+    fn prefix_pattern_is_lowercase_with_trailing_wildcard() {
+        assert_eq!(username_prefix_pattern("Fishi").unwrap(), "fishi%");
+    }
+
+    #[test]
+    fn prefix_pattern_escapes_underscores() {
+        assert_eq!(username_prefix_pattern("a_b").unwrap(), "a\\_b%");
+    }
+
+    #[test]
+    fn prefix_pattern_rejects_like_wildcards_and_empty_input() {
+        assert!(username_prefix_pattern("").is_err());
+        assert!(username_prefix_pattern("fish%").is_err());
+        assert!(username_prefix_pattern("Seventeen_Chars_1").is_err());
+    }
+
+    // ── PlayerCursor ─────────────────────────────────────────────────────────
+
+    #[test]
+    fn cursor_round_trips() {
+        let cursor = PlayerCursor {
+            username: "fishigames".to_string(),
+            uuid: Uuid::new_v4(),
+        };
+
+        assert_eq!(cursor.to_string().parse::<PlayerCursor>().unwrap(), cursor);
+    }
+
+    #[test]
+    fn cursor_rejects_malformed_input() {
+        assert!("fishigames".parse::<PlayerCursor>().is_err());
+        assert!("fishigames:not-a-uuid".parse::<PlayerCursor>().is_err());
+    }
+}
@@ -0,0 +1,653 @@
+use crate::error::{AppError, AppResult};
+use crate::grpc::generated;
+use crate::models::{validate_punishment_type, validate_scope, PunishmentTemplate, SeverityPlan};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use time::OffsetDateTime;
+use uuid::Uuid;
+
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize)]
+pub struct PunishmentCategory {
+    pub id: i32,
+    pub name: String,
+    pub description: Option<String>,
+    pub color_hex: String,
+    pub active: bool,
+    pub position: i32,
+    pub decay_mode: String,
+    pub decay_days: Option<i32>,
+    pub requires_approval: bool,
+    pub created_at: OffsetDateTime,
+    pub updated_at: OffsetDateTime,
+    // Version numbers, only selected when listing categories
+    #[sqlx(default)]
+    pub active_version: Option<i32>,
+    #[sqlx(default)]
+    pub draft_version: Option<i32>,
+}
+
+impl PunishmentCategory {
+    pub fn into_message(self, templates: Vec<PunishmentTemplate>) -> generated::Category {
+        generated::Category {
+            id: self.id,
+            name: self.name,
+            description: self.description,
+            color_hex: self.color_hex,
+            active: self.active,
+            position: self.position,
+            steps: templates.into_iter().map(Into::into).collect(),
+            active_version: self.active_version,
+            draft_version: self.draft_version,
+            decay_mode: self.decay_mode,
+            decay_days: self.decay_days,
+            requires_approval: self.requires_approval,
+        }
+    }
+}
+
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize)]
+pub struct PolicyVersion {
+    pub id: i32,
+    pub category_id: i32,
+    pub version_number: i32,
+    pub status: String,
+    pub created_by: Option<Uuid>,
+    pub created_at: OffsetDateTime,
+    pub published_by: Option<Uuid>,
+    pub published_at: Option<OffsetDateTime>,
+    pub activates_at: Option<OffsetDateTime>,
+}
+
+impl PolicyVersion {
+    pub fn into_message(self, templates: Vec<PunishmentTemplate>) -> generated::PolicyVersion {
+        generated::PolicyVersion {
+            id: self.id,
+            category_id: self.category_id,
+            version_number: self.version_number,
+            status: self.status,
+            created_by: self.created_by.map(|uuid| uuid.to_string()),
+            created_at: self.created_at.unix_timestamp(),
+            published_by: self.published_by.map(|uuid| uuid.to_string()),
+            published_at: self.published_at.map(|dt| dt.unix_timestamp()),
+            activates_at: self.activates_at.map(|dt| dt.unix_timestamp()),
+            steps: templates.into_iter().map(Into::into).collect(),
+        }
+    }
+}
+
+/// One step that differs between two ladders.
+#[derive(Debug, Clone)]
+pub struct StepChange {
+    pub offense_number: i32,
+    pub before: Option<PunishmentTemplate>,
+    pub after: Option<PunishmentTemplate>,
+    pub changed_fields: Vec<&'static str>,
+}
+
+impl StepChange {
+    pub fn kind(&self) -> &'static str {
+        match (&self.before, &self.after) {
+            (None, _) => "added",
+            (_, None) => "removed",
+            _ => "changed",
+        }
+    }
+}
+
+impl From<StepChange> for generated::EscalationStepDiff {
+    fn from(change: StepChange) -> Self {
+        generated::EscalationStepDiff {
+            offense_number: change.offense_number,
+            change: change.kind().to_string(),
+            before: change.before.map(Into::into),
+            after: change.after.map(Into::into),
+            changed_fields: change.changed_fields.into_iter().map(str::to_string).collect(),
+        }
+    }
+}
+
+/// Compares two ladders step by step, leaving out steps that are the same in both.
+pub fn diff_ladders(before: &[PunishmentTemplate], after: &[PunishmentTemplate]) -> Vec<StepChange> {
+    let find = |ladder: &[PunishmentTemplate], offense_number: i32| {
+        ladder.iter().find(|template| template.offense_number == offense_number).cloned()
+    };
+    let last_offense = before
+        .iter()
+        .chain(after)
+        .map(|template| template.offense_number)
+        .max()
+        .unwrap_or(0);
+
+    (1..=last_offense)
+        .filter_map(|offense_number| {
+            let before = find(before, offense_number);
+            let after = find(after, offense_number);
+
+            let changed_fields = match (&before, &after) {
+                (Some(before), Some(after)) => changed_step_fields(before, after),
+                (None, None) => return None,
+                _ => Vec::new(),
+            };
+            if before.is_some() && after.is_some() && changed_fields.is_empty() {
+                return None;
+            }
+
+            Some(StepChange {
+                offense_number,
+                before,
+                after,
+                changed_fields,
+            })
+        })
+        .collect()
+}
+
+fn changed_step_fields(before: &PunishmentTemplate, after: &PunishmentTemplate) -> Vec<&'static str> {
+    let mut fields = Vec::new();
+    if before.punishment_type != after.punishment_type {
+        fields.push("type");
+    }
+    if before.duration_minutes != after.duration_minutes {
+        fields.push("duration_minutes");
+    }
+    if before.reason_template != after.reason_template {
+        fields.push("reason_template");
+    }
+    if before.scope != after.scope || before.server_group != after.server_group {
+        fields.push("scope");
+    }
+    if before.points != after.points {
+        fields.push("points");
+    }
+    if before.requires_acknowledgement != after.requires_acknowledgement {
+        fields.push("requires_acknowledgement");
+    }
+    fields
+}
+
+impl From<PunishmentTemplate> for generated::EscalationStep {
+    fn from(template: PunishmentTemplate) -> Self {
+        generated::EscalationStep {
+            offense_number: template.offense_number,
+            r#type: template.punishment_type,
+            duration_minutes: template.duration_minutes,
+            reason_template: template.reason_template,
+            scope: Some(generated::PunishmentScope {
+                r#type: template.scope,
+                server_group: template.server_group,
+            }),
+            points: template.points,
+            requires_acknowledgement: template.requires_acknowledgement,
+        }
+    }
+}
+
+/// How old offenses stop counting towards the next escalation step of a category.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum DecayRule {
+    None,
+    Expire { days: i32 },     // Offenses older than `days` are ignored
+    StepDown { days: i32 },   // Every `days` without an offense moves one step back down
+}
+
+impl DecayRule {
+    pub fn from_columns(mode: &str, days: Option<i32>) -> AppResult<Self> {
+        match (mode, days) {
+            ("none", None) => Ok(Self::None),
+            ("expire", Some(days)) if days > 0 => Ok(Self::Expire { days }),
+            ("step_down", Some(days)) if days > 0 => Ok(Self::StepDown { days }),
+            ("none", Some(_)) => Err(AppError::CustomValidationError(
+                "The none decay mode does not take a number of days".to_string(),
+            )),
+            ("expire" | "step_down", _) => Err(AppError::CustomValidationError(format!(
+                "The {} decay mode needs a number of days of at least one",
+                mode
+            ))),
+            _ => Err(AppError::CustomValidationError(format!("Unknown decay mode: {}", mode))),
+        }
+    }
+
+    pub fn mode(&self) -> &'static str {
+        match self {
+            Self::None => "none",
+            Self::Expire { .. } => "expire",
+            Self::StepDown { .. } => "step_down",
+        }
+    }
+
+    pub fn days(&self) -> Option<i32> {
+        match self {
+            Self::None => None,
+            Self::Expire { days } | Self::StepDown { days } => Some(*days),
+        }
+    }
+
+    /// Returns how many of the previous offenses, issued at the given times, still count.
+    pub fn count_offenses(&self, offenses: &[OffsetDateTime], now: OffsetDateTime) -> i64 {
+        let mut offenses = offenses.to_vec();
+        offenses.sort();
+
+        match *self {
+            Self::None => offenses.len() as i64,
+            Self::Expire { days } => {
+                let cutoff = now - time::Duration::days(days as i64);
+                offenses.iter().filter(|issued_at| **issued_at >= cutoff).count() as i64
+            }
+            Self::StepDown { days } => {
+                let clean_periods = |from: OffsetDateTime, to: OffsetDateTime| (to - from).whole_days() / days as i64;
+
+                let mut level = 0;
+                let mut previous: Option<OffsetDateTime> = None;
+                for issued_at in &offenses {
+                    if let Some(previous) = previous {
+                        level = (level - clean_periods(previous, *issued_at)).max(0);
+                    }
+                    level += 1;
+                    previous = Some(*issued_at);
+                }
+
+                match previous {
+                    Some(previous) => (level - clean_periods(previous, now)).max(0),
+                    None => 0,
+                }
+            }
+        }
+    }
+
+    /// Explains to staff which offenses counted, e.g. for `PreviewPunishment`.
+    pub fn explain(&self, previous_offenses: i64, counted_offenses: i64, offense_number: i32, ladder_length: i32) -> String {
+        let counting = match self {
+            Self::None => format!("{} previous offense(s) in this category", previous_offenses),
+            Self::Expire { days } => format!(
+                "{} previous offense(s) in this category, {} within the last {} days",
+                previous_offenses, counted_offenses, days
+            ),
+            Self::StepDown { days } => format!(
+                "{} previous offense(s) in this category, {} after stepping down once per {} days without an offense",
+                previous_offenses, counted_offenses, days
+            ),
+        };
+
+        if counted_offenses + 1 > ladder_length as i64 {
+            format!("{}, so the last step {} of {} repeats", counting, offense_number, ladder_length)
+        } else {
+            format!("{}, so step {} of {} applies", counting, offense_number, ladder_length)
+        }
+    }
+}
+
+/// The step the next punishment in a category would get, and why.
+#[derive(Debug, Clone)]
+pub struct EscalationPlan {
+    pub template: PunishmentTemplate,
+    pub decay: DecayRule,
+    pub previous_offenses: i64,
+    pub counted_offenses: i64,
+    pub ladder_length: i32,
+    // Only planned while severity points are enabled
+    pub severity: Option<SeverityPlan>,
+}
+
+impl EscalationPlan {
+    pub fn explanation(&self) -> String {
+        let escalation = self.decay.explain(self.previous_offenses, self.counted_offenses, self.template.offense_number, self.ladder_length);
+
+        match &self.severity {
+            Some(severity) => format!("{}. {}", escalation, severity.explain()),
+            None => escalation,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NewCategory {
+    pub name: String,
+    pub description: Option<String>,
+    pub color_hex: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct CategoryUpdate {
+    pub name: Option<String>,
+    pub description: Option<String>,
+    pub color_hex: Option<String>,
+    pub active: Option<bool>,
+    pub decay: Option<DecayRule>,
+    pub requires_approval: Option<bool>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EscalationStep {
+    pub punishment_type: String,
+    pub duration_minutes: Option<i32>,
+    pub reason_template: String,
+    pub scope: String,
+    pub server_group: Option<String>,
+    pub points: i32,
+    pub requires_acknowledgement: bool,
+}
+
+impl From<generated::EscalationStep> for EscalationStep {
+    fn from(step: generated::EscalationStep) -> Self {
+        let (scope, server_group) = match step.scope {
+            Some(scope) => (scope.r#type, scope.server_group),
+            None => ("global".to_string(), None),
+        };
+
+        EscalationStep {
+            punishment_type: step.r#type,
+            duration_minutes: step.duration_minutes,
+            reason_template: step.reason_template,
+            scope,
+            server_group,
+            points: step.points,
+            requires_acknowledgement: step.requires_acknowledgement,
+        }
+    }
+}
+
+pub fn validate_category_name(name: &str) -> AppResult<()> {
+    if name.trim().is_empty() || name.chars().count() > 50 {
+        return Err(AppError::CustomValidationError(
+            "Category names must be between 1 and 50 characters".to_string(),
+        ));
+    }
+
+    Ok(())
+}
+
+/// Mirrors the `valid_color_hex` CHECK of `punishment_categories`.
+pub fn validate_color_hex(color_hex: &str) -> AppResult<()> {
+    let valid = color_hex.len() == 7
+        && color_hex.starts_with('#')
+        && color_hex[1..].chars().all(|c| c.is_ascii_hexdigit());
+
+    if !valid {
+        return Err(AppError::CustomValidationError(format!(
+            "Invalid color {}, expected a hex color like #FF6B6B",
+            color_hex
+        )));
+    }
+
+    Ok(())
+}
+
+/// Checks a whole ladder up front so staff get a readable error instead of a violated CHECK.
+pub fn validate_escalation_ladder(steps: &[EscalationStep]) -> AppResult<()> {
+    if steps.is_empty() {
+        return Err(AppError::CustomValidationError(
+            "An escalation ladder needs at least one step".to_string(),
+        ));
+    }
+
+    for (index, step) in steps.iter().enumerate() {
+        let offense_number = index + 1;
+        let step_error = |message: String| AppError::CustomValidationError(format!("Step {}: {}", offense_number, message));
+
+        validate_punishment_type(&step.punishment_type).map_err(|_| {
+            step_error(format!("unknown punishment type {}", step.punishment_type))
+        })?;
+
+        // Same rule as the duration_required_for_timed CHECK, durations on other types would be ignored
+        let timed = matches!(step.punishment_type.as_str(), "mute" | "shadow_mute" | "temp_ban");
+        match (timed, step.duration_minutes) {
+            (true, Some(minutes)) if minutes > 0 => {}
+            (true, _) => return Err(step_error(format!("a {} needs a duration of at least one minute", step.punishment_type))),
+            (false, Some(_)) => return Err(step_error(format!("a {} cannot have a duration", step.punishment_type))),
+            (false, None) => {}
+        }
+
+        if step.reason_template.trim().is_empty() {
+            return Err(step_error("the reason cannot be empty".to_string()));
+        }
+
+        if step.points < 0 {
+            return Err(step_error("points cannot be negative".to_string()));
+        }
+
+        if step.requires_acknowledgement && step.punishment_type != "warn" {
+            return Err(step_error(format!("only warns can require acknowledgement, not a {}", step.punishment_type)));
+        }
+
+        validate_scope(&step.scope, step.server_group.as_deref()).map_err(|e| match e {
+            AppError::CustomValidationError(message) => step_error(message),
+            other => other,
+        })?;
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn make_step(punishment_type: &str, duration_minutes: Option<i32>) -> EscalationStep {
+        EscalationStep {
+            punishment_type: punishment_type.to_string(),
+            duration_minutes,
+            reason_template: "Muted for spamming".to_string(),
+            scope: "global".to_string(),
+            server_group: None,
+            points: 0,
+            requires_acknowledgement: false,
+        }
+    }
+
+    fn make_template(offense_number: i32, punishment_type: &str, duration_minutes: Option<i32>) -> PunishmentTemplate {
+        PunishmentTemplate {
+            id: offense_number,
+            category_id: 1,
+            policy_version_id: 1,
+            offense_number,
+            punishment_type: punishment_type.to_string(),
+            duration_minutes,
+            reason_template: "Muted for spamming".to_string(),
+            scope: "global".to_string(),
+            server_group: None,
+            points: 0,
+            requires_acknowledgement: false,
+        }
+    }
+
+    fn error_message(result: AppResult<()>) -> String {
+        match result {
+            Err(AppError::CustomValidationError(message)) => message,
+            other => panic!("expected a validation error, got {:?}", other),
+        }
+    }
+
+    // ── validate_color_hex ───────────────────────────────────────────────────
+
+    #[test]
+    fn accepts_hex_colors_in_any_case() {
+        assert!(validate_color_hex("#FF6B6B").is_ok());
+        assert!(validate_color_hex("#8b0000").is_ok());
+    }
+
+    #[test]
+    fn rejects_malformed_colors() {
+        assert!(validate_color_hex("FF6B6B").is_err());
+        assert!(validate_color_hex("#FFF").is_err());
+        assert!(validate_color_hex("#GG0000").is_err());
+        assert!(validate_color_hex("#FF6B6B0").is_err());
+    }
+
+    // ── validate_category_name ───────────────────────────────────────────────
+
+    #[test]
+    fn rejects_blank_and_long_category_names() {
+        assert!(validate_category_name("Chat Abuse").is_ok());
+        assert!(validate_category_name("   ").is_err());
+        assert!(validate_category_name(&"x".repeat(51)).is_err());
+    }
+
+    // ── validate_escalation_ladder ───────────────────────────────────────────
+
+    #[test]
+    fn accepts_a_typical_ladder() {
+        let ladder = vec![
+            make_step("warn", None),
+            make_step("mute", Some(120)),
+            make_step("temp_ban", Some(10080)),
+            make_step("perm_ban", None),
+        ];
+        assert!(validate_escalation_ladder(&ladder).is_ok());
+    }
+
+    #[test]
+    fn rejects_an_empty_ladder() {
+        assert!(validate_escalation_ladder(&[]).is_err());
+    }
+
+    #[test]
+    fn timed_steps_need_a_positive_duration() {
+        let message = error_message(validate_escalation_ladder(&[make_step("warn", None), make_step("mute", None)]));
+        assert_eq!(message, "Step 2: a mute needs a duration of at least one minute");
+        assert!(validate_escalation_ladder(&[make_step("temp_ban", Some(0))]).is_err());
+        assert!(validate_escalation_ladder(&[make_step("shadow_mute", None)]).is_err());
+        assert!(validate_escalation_ladder(&[make_step("shadow_mute", Some(1440))]).is_ok());
+    }
+
+    #[test]
+    fn untimed_steps_cannot_have_a_duration() {
+        let message = error_message(validate_escalation_ladder(&[make_step("perm_ban", Some(60))]));
+        assert_eq!(message, "Step 1: a perm_ban cannot have a duration");
+    }
+
+    #[test]
+    fn reports_unknown_types_and_scopes_per_step() {
+        let message = error_message(validate_escalation_ladder(&[make_step("shadow_ban", None)]));
+        assert_eq!(message, "Step 1: unknown punishment type shadow_ban");
+
+        let mut step = make_step("warn", None);
+        step.scope = "server_group".to_string();
+        assert!(error_message(validate_escalation_ladder(&[step])).starts_with("Step 1: "));
+    }
+
+    #[test]
+    fn only_warns_can_require_acknowledgement() {
+        let mut warn = make_step("warn", None);
+        warn.requires_acknowledgement = true;
+        assert!(validate_escalation_ladder(&[warn]).is_ok());
+
+        let mut mute = make_step("mute", Some(60));
+        mute.requires_acknowledgement = true;
+        assert_eq!(
+            error_message(validate_escalation_ladder(&[mute])),
+            "Step 1: only warns can require acknowledgement, not a mute"
+        );
+    }
+
+    #[test]
+    fn steps_cannot_award_negative_points() {
+        let mut step = make_step("warn", None);
+        step.points = -5;
+        assert_eq!(error_message(validate_escalation_ladder(&[step])), "Step 1: points cannot be negative");
+    }
+
+    // ── diff_ladders ─────────────────────────────────────────────────────────
+
+    #[test]
+    fn identical_ladders_have_no_changes() {
+        let ladder = vec![make_template(1, "warn", None), make_template(2, "mute", Some(120))];
+        assert!(diff_ladders(&ladder, &ladder).is_empty());
+    }
+
+    #[test]
+    fn reports_changed_fields_of_a_step() {
+        let before = vec![make_template(1, "warn", None), make_template(2, "mute", Some(120))];
+        let mut after = before.clone();
+        after[1].duration_minutes = Some(240);
+        after[1].scope = "proxy".to_string();
+
+        let changes = diff_ladders(&before, &after);
+        assert_eq!(changes.len(), 1);
+        assert_eq!(changes[0].offense_number, 2);
+        assert_eq!(changes[0].kind(), "changed");
+        assert_eq!(changes[0].changed_fields, vec!["duration_minutes", "scope"]);
+    }
+
+    #[test]
+    fn reports_added_and_removed_steps() {
+        let short = vec![make_template(1, "warn", None)];
+        let long = vec![make_template(1, "warn", None), make_template(2, "perm_ban", None)];
+
+        let added = diff_ladders(&short, &long);
+        assert_eq!(added.len(), 1);
+        assert_eq!(added[0].kind(), "added");
+        assert!(added[0].changed_fields.is_empty());
+
+        let removed = diff_ladders(&long, &short);
+        assert_eq!(removed[0].kind(), "removed");
+        assert_eq!(removed[0].before.as_ref().map(|step| step.punishment_type.as_str()), Some("perm_ban"));
+    }
+
+    // ── DecayRule ────────────────────────────────────────────────────────────
+
+    fn days_ago(now: OffsetDateTime, days: i64) -> OffsetDateTime {
+        now - time::Duration::days(days)
+    }
+
+    #[test]
+    fn decay_rule_round_trips_through_columns() {
+        for rule in [DecayRule::None, DecayRule::Expire { days: 365 }, DecayRule::StepDown { days: 90 }] {
+            assert_eq!(DecayRule::from_columns(rule.mode(), rule.days()).unwrap(), rule);
+        }
+    }
+
+    #[test]
+    fn decay_rule_rejects_inconsistent_columns() {
+        assert!(DecayRule::from_columns("none", Some(30)).is_err());
+        assert!(DecayRule::from_columns("expire", None).is_err());
+        assert!(DecayRule::from_columns("step_down", Some(0)).is_err());
+        assert!(DecayRule::from_columns("forgive", Some(30)).is_err());
+    }
+
+    #[test]
+    fn without_decay_every_offense_counts() {
+        let now = OffsetDateTime::now_utc();
+        let offenses = [days_ago(now, 1000), days_ago(now, 10)];
+        assert_eq!(DecayRule::None.count_offenses(&offenses, now), 2);
+    }
+
+    #[test]
+    fn expire_ignores_offenses_outside_the_window() {
+        let now = OffsetDateTime::now_utc();
+        let offenses = [days_ago(now, 3 * 365), days_ago(now, 200), days_ago(now, 10)];
+        assert_eq!(DecayRule::Expire { days: 365 }.count_offenses(&offenses, now), 2);
+        assert_eq!(DecayRule::Expire { days: 30 }.count_offenses(&offenses, now), 1);
+    }
+
+    #[test]
+    fn step_down_drops_one_level_per_clean_period() {
+        let now = OffsetDateTime::now_utc();
+        let rule = DecayRule::StepDown { days: 90 };
+
+        // Three offenses in a row, then 200 clean days: two periods, so one level is left
+        let offenses = [days_ago(now, 220), days_ago(now, 210), days_ago(now, 200)];
+        assert_eq!(rule.count_offenses(&offenses, now), 1);
+
+        // Clean periods between offenses count too and never go below zero
+        let offenses = [days_ago(now, 1000), days_ago(now, 500), days_ago(now, 5)];
+        assert_eq!(rule.count_offenses(&offenses, now), 1);
+    }
+
+    #[test]
+    fn step_down_without_offenses_counts_nothing() {
+        let now = OffsetDateTime::now_utc();
+        assert_eq!(DecayRule::StepDown { days: 30 }.count_offenses(&[], now), 0);
+    }
+
+    #[test]
+    fn explains_which_step_applies() {
+        assert_eq!(
+            DecayRule::Expire { days: 365 }.explain(3, 1, 2, 5),
+            "3 previous offense(s) in this category, 1 within the last 365 days, so step 2 of 5 applies"
+        );
+        assert_eq!(
+            DecayRule::None.explain(6, 6, 5, 5),
+            "6 previous offense(s) in this category, so the last step 5 of 5 repeats"
+        );
+    }
+}
@@ -1,8 +1,13 @@
+use crate::error::{AppError, AppResult};
+use crate::models::PunishmentApproval;
 use serde::{Deserialize, Serialize};
+use sqlx::types::ipnetwork::IpNetwork;
 use sqlx::FromRow;
+use std::fmt;
+use std::str::FromStr;
 use time::OffsetDateTime;
 use uuid::Uuid;
-use crate::grpc::generated::Punishment;
+use crate::grpc::generated::{self, Punishment, PunishmentDetails, PunishmentScope};
 
 #[derive(Debug, Clone, FromRow, Serialize, Deserialize)]
 pub struct PunishmentWithTemplate {
@@ -24,10 +29,279 @@ pub struct PunishmentWithTemplate {
     pub revoke_reason: Option<String>,
     pub created_at: OffsetDateTime,
     pub updated_at: OffsetDateTime,
+    pub scope: String,
+    pub server_group: Option<String>,
+    pub ip_range: Option<IpNetwork>,
+    pub template_id: Option<i32>,
+    pub points: i32,
+    pub threshold_points: Option<i32>,
+    pub requires_acknowledgement: bool,
+    pub acknowledged_at: Option<OffsetDateTime>,
     // Category fields (joined)
     pub category_name: String,
 }
 
+impl PunishmentWithTemplate {
+    pub fn status(&self, now: OffsetDateTime) -> PunishmentStatus {
+        if self.revoked {
+            PunishmentStatus::Revoked
+        } else if self.active && self.expires_at.is_none_or(|expires_at| expires_at > now) {
+            PunishmentStatus::Active
+        } else {
+            PunishmentStatus::Expired
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum PunishmentStatus {
+    Active,
+    Revoked,
+    Expired,    // Ran out or was deactivated without a revocation
+}
+
+impl PunishmentStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Active => "active",
+            Self::Revoked => "revoked",
+            Self::Expired => "expired",
+        }
+    }
+}
+
+impl FromStr for PunishmentStatus {
+    type Err = AppError;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value {
+            "active" => Ok(Self::Active),
+            "revoked" => Ok(Self::Revoked),
+            "expired" => Ok(Self::Expired),
+            _ => Err(AppError::CustomValidationError(format!("Unknown punishment status: {}", value))),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub enum PunishmentSort {
+    #[default]
+    NewestFirst,
+    OldestFirst,
+}
+
+impl FromStr for PunishmentSort {
+    type Err = AppError;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value {
+            "newest" => Ok(Self::NewestFirst),
+            "oldest" => Ok(Self::OldestFirst),
+            _ => Err(AppError::CustomValidationError(format!("Unknown punishment sort order: {}", value))),
+        }
+    }
+}
+
+/// Filters of `ListPunishments`, every field that is set has to match.
+#[derive(Debug, Clone, Default)]
+pub struct PunishmentFilter {
+    pub player_uuid: Option<Uuid>,
+    pub staff_uuid: Option<Uuid>,
+    pub category_id: Option<i32>,
+    pub punishment_type: Option<String>,
+    pub status: Option<PunishmentStatus>,
+    pub issued_after: Option<OffsetDateTime>,
+    pub issued_before: Option<OffsetDateTime>,
+}
+
+/// Position after the last punishment of a page, punishments are ordered by issue time then ID.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PunishmentCursor {
+    pub issued_at: OffsetDateTime,
+    pub id: Uuid,
+}
+
+impl PunishmentCursor {
+    pub fn after(punishment: &PunishmentWithTemplate) -> Self {
+        Self {
+            issued_at: punishment.issued_at,
+            id: punishment.id,
+        }
+    }
+}
+
+impl fmt::Display for PunishmentCursor {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        // Postgres stores microseconds, so nothing is lost
+        write!(f, "{}:{}", self.issued_at.unix_timestamp_nanos() / 1_000, self.id)
+    }
+}
+
+impl FromStr for PunishmentCursor {
+    type Err = AppError;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        let invalid = || AppError::CustomValidationError(format!("Invalid cursor: {}", value));
+
+        let (micros, id) = value.split_once(':').ok_or_else(invalid)?;
+        let micros = micros.parse::<i128>().map_err(|_| invalid())?;
+        let issued_at = OffsetDateTime::from_unix_timestamp_nanos(micros * 1_000).map_err(|_| invalid())?;
+        let id = Uuid::parse_str(id).map_err(|_| invalid())?;
+
+        Ok(Self { issued_at, id })
+    }
+}
+
+pub fn validate_punishment_type(punishment_type: &str) -> AppResult<()> {
+    match punishment_type {
+        "warn" | "mute" | "shadow_mute" | "kick" | "temp_ban" | "perm_ban" => Ok(()),
+        _ => Err(AppError::CustomValidationError(format!("Unknown punishment type: {}", punishment_type))),
+    }
+}
+
+/// Whether the punished player is told about the punishment in chat. Shadow mutes only work as long
+/// as the player does not notice them.
+pub fn notifies_in_chat(punishment_type: &str) -> bool {
+    matches!(punishment_type, "mute" | "warn")
+}
+
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize)]
+pub struct PunishmentTemplate {
+    pub id: i32,
+    pub category_id: i32,
+    pub policy_version_id: i32,
+    pub offense_number: i32,
+    pub punishment_type: String,
+    pub duration_minutes: Option<i32>,
+    pub reason_template: String,
+    pub scope: String,
+    pub server_group: Option<String>,
+    pub points: i32,
+    pub requires_acknowledgement: bool,
+}
+
+/// Result of `issue_punishment`.
+#[derive(Debug, Clone)]
+pub struct IssuedPunishment {
+    pub punishment: PunishmentWithTemplate,
+    // Issued on top when a severity threshold was reached
+    pub automatic_ban: Option<PunishmentWithTemplate>,
+    // Queued instead when the automatic ban needs approval
+    pub automatic_ban_approval: Option<PunishmentApproval>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NewPunishment {
+    pub player_uuid: Uuid,
+    pub category_id: i32,
+    pub reason: Option<String>,
+    pub evidence: Option<String>,
+    pub note: Option<String>,
+    // Overrides the template scope when set
+    pub scope: Option<String>,
+    pub server_group: Option<String>,
+    pub ip_range: Option<IpNetwork>,
+}
+
+/// Where a server calling the backend sits in the network, used to pick the punishments that apply to it.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ServerIdentity {
+    pub proxy: bool,
+    pub server_group: Option<String>,
+}
+
+impl ServerIdentity {
+    pub fn covers(&self, scope: &str, server_group: Option<&str>) -> bool {
+        match scope {
+            "global" => true,
+            "proxy" => self.proxy,
+            "server_group" => server_group.is_some() && self.server_group.as_deref() == server_group,
+            _ => false,
+        }
+    }
+}
+
+pub fn validate_scope(scope: &str, server_group: Option<&str>) -> AppResult<()> {
+    match (scope, server_group) {
+        ("global" | "proxy", None) => Ok(()),
+        ("server_group", Some(group)) if !group.is_empty() && group.len() <= 50 => Ok(()),
+        ("server_group", _) => Err(AppError::CustomValidationError(
+            "The server_group scope requires a server group of at most 50 characters".to_string(),
+        )),
+        ("global" | "proxy", Some(_)) => Err(AppError::CustomValidationError(
+            format!("The {} scope does not take a server group", scope),
+        )),
+        _ => Err(AppError::CustomValidationError(format!("Unknown punishment scope: {}", scope))),
+    }
+}
+
+/// Narrowest prefixes an IP punishment may use, wider ranges reach too many unrelated players.
+pub const MIN_IPV4_PREFIX: u8 = 16;
+pub const MIN_IPV6_PREFIX: u8 = 32;
+
+pub fn validate_ip_range(range: &IpNetwork) -> AppResult<()> {
+    let min_prefix = match range {
+        IpNetwork::V4(_) => MIN_IPV4_PREFIX,
+        IpNetwork::V6(_) => MIN_IPV6_PREFIX,
+    };
+
+    if range.prefix() < min_prefix {
+        return Err(AppError::CustomValidationError(format!(
+            "The IP range {} is too broad, it needs a prefix of at least /{}",
+            range, min_prefix
+        )));
+    }
+
+    Ok(())
+}
+
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize)]
+pub struct PunishmentEvent {
+    pub sequence: i64,
+    // Whether the event was written when the punishment was issued rather than changed
+    pub issued: bool,
+    #[sqlx(flatten)]
+    pub punishment: PunishmentWithTemplate,
+}
+
+impl PunishmentEvent {
+    /// Whether the player is told about the event with a disconnect or chat message. Events of
+    /// punishments no longer in effect, e.g. a revocation, only update the punishment.
+    pub fn notifies_player(&self, now: OffsetDateTime) -> bool {
+        match self.punishment.punishment_type.as_str() {
+            // Kicks are stored inactive, they only take effect when issued
+            "kick" => self.issued && !self.punishment.revoked,
+            _ => self.punishment.status(now) == PunishmentStatus::Active,
+        }
+    }
+}
+
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize)]
+pub struct CategoryPunishmentCount {
+    pub category_id: i32,
+    pub category_name: String,
+    pub total: i64,
+    pub active: i64,
+}
+
+impl From<CategoryPunishmentCount> for generated::CategoryPunishmentCount {
+    fn from(count: CategoryPunishmentCount) -> Self {
+        generated::CategoryPunishmentCount {
+            category_id: count.category_id,
+            category_name: count.category_name,
+            total: count.total,
+            active: count.active,
+        }
+    }
+}
+
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize)]
+pub struct AppealCounts {
+    pub total: i64,
+    // Pending or under review
+    pub open: i64,
+}
+
 impl From<PunishmentWithTemplate> for Punishment {
     fn from(p: PunishmentWithTemplate) -> Self {
         Punishment {
@@ -38,6 +312,68 @@ impl From<PunishmentWithTemplate> for Punishment {
             expires_at: p.expires_at.map(|dt| dt.unix_timestamp()),
             active: p.active,
             reason: p.reason,
+            scope: Some(PunishmentScope {
+                r#type: p.scope,
+                server_group: p.server_group,
+            }),
+            ip_range: p.ip_range.map(|range| range.to_string()),
+            awaiting_acknowledgement: p.requires_acknowledgement && p.acknowledged_at.is_none(),
+            ip_match: false,
+        }
+    }
+}
+
+impl PunishmentWithTemplate {
+    /// Converts for the login of `player_uuid`. An IP punishment of another account only applies
+    /// through the shared address, so it is marked as such and does not identify that account.
+    pub fn into_login_message(self, player_uuid: Uuid) -> Punishment {
+        if self.player_uuid == player_uuid {
+            return self.into();
+        }
+
+        Punishment {
+            id: String::new(),
+            player_id: String::new(),
+            ip_match: true,
+            ..self.into()
+        }
+    }
+
+    /// Converts to the full proto message, leaving out internal fields unless the viewer is staff.
+    pub fn into_details(self, staff_view: bool) -> PunishmentDetails {
+        let status = self.status(OffsetDateTime::now_utc());
+
+        PunishmentDetails {
+            id: self.id.to_string(),
+            r#type: self.punishment_type,
+            reason: self.reason,
+            player_id: self.player_uuid.to_string(),
+            staff_id: self.staff_uuid.to_string(),
+            category_id: self.category_id,
+            category_name: self.category_name,
+            offense_number: self.offense_number,
+            evidence: self.evidence,
+            note: self.note.filter(|_| staff_view),
+            issued_at: self.issued_at.unix_timestamp(),
+            expires_at: self.expires_at.map(|dt| dt.unix_timestamp()),
+            status: status.as_str().to_string(),
+            active: self.active,
+            revoked: self.revoked,
+            revoked_by: self.revoked_by.map(|uuid| uuid.to_string()),
+            revoked_at: self.revoked_at.map(|dt| dt.unix_timestamp()),
+            revoke_reason: self.revoke_reason,
+            scope: Some(PunishmentScope {
+                r#type: self.scope,
+                server_group: self.server_group,
+            }),
+            ip_range: self.ip_range.filter(|_| staff_view).map(|range| range.to_string()),
+            created_at: self.created_at.unix_timestamp(),
+            updated_at: self.updated_at.unix_timestamp(),
+            template_id: self.template_id,
+            points: self.points,
+            threshold_points: self.threshold_points,
+            requires_acknowledgement: self.requires_acknowledgement,
+            acknowledged_at: self.acknowledged_at.map(|dt| dt.unix_timestamp()),
         }
     }
 }
@@ -67,6 +403,14 @@ mod tests {
             revoke_reason: None,
             created_at: OffsetDateTime::now_utc(),
             updated_at: OffsetDateTime::now_utc(),
+            scope: "global".to_string(),
+            server_group: None,
+            ip_range: None,
+            template_id: Some(1),
+            points: 0,
+            threshold_points: None,
+            requires_acknowledgement: false,
+            acknowledged_at: None,
             category_name: "Cheating/Hacking".to_string(),
         }
     }
@@ -101,4 +445,232 @@ mod tests {
         assert!(proto.expires_at.is_none());
         assert_eq!(proto.r#type, "perm_ban");
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn into_punishment_maps_scope() {
+        let mut p = make_punishment("temp_ban", "Griefing", None);
+        p.scope = "server_group".to_string();
+        p.server_group = Some("skyblock".to_string());
+        let proto: Punishment = p.into();
+        let scope = proto.scope.expect("scope should be set");
+        assert_eq!(scope.r#type, "server_group");
+        assert_eq!(scope.server_group.as_deref(), Some("skyblock"));
+    }
+
+    #[test]
+    fn into_punishment_maps_ip_range() {
+        let mut p = make_punishment("perm_ban", "Ban evasion", None);
+        p.ip_range = Some("203.0.113.0/24".parse().unwrap());
+        let proto: Punishment = p.into();
+        assert_eq!(proto.ip_range.as_deref(), Some("203.0.113.0/24"));
+    }
+
+    #[test]
+    fn into_details_preserves_staff_fields() {
+        let mut p = make_punishment("temp_ban", "Griefing", None);
+        let staff_id = p.staff_uuid.to_string();
+        p.evidence = Some("https://example.com/clip".to_string());
+        p.note = Some("Second report this week".to_string());
+        let details = p.into_details(true);
+        assert_eq!(details.staff_id, staff_id);
+        assert_eq!(details.category_name, "Cheating/Hacking");
+        assert_eq!(details.evidence.as_deref(), Some("https://example.com/clip"));
+        assert_eq!(details.note.as_deref(), Some("Second report this week"));
+        assert_eq!(details.status, "active");
+    }
+
+    #[test]
+    fn into_details_hides_internal_fields_from_players() {
+        let mut p = make_punishment("perm_ban", "Ban evasion", None);
+        p.note = Some("Alt of a banned account".to_string());
+        p.ip_range = Some("203.0.113.7/32".parse().unwrap());
+        let details = p.into_details(false);
+        assert!(details.note.is_none());
+        assert!(details.ip_range.is_none());
+        assert_eq!(details.reason, "Ban evasion");
+    }
+
+    #[test]
+    fn into_details_preserves_revocation() {
+        let mut p = make_punishment("mute", "Spam", None);
+        let revoked_by = Uuid::new_v4();
+        p.active = false;
+        p.revoked = true;
+        p.revoked_by = Some(revoked_by);
+        p.revoked_at = Some(OffsetDateTime::now_utc());
+        p.revoke_reason = Some("Appeal accepted".to_string());
+        let details = p.into_details(false);
+        assert_eq!(details.status, "revoked");
+        assert_eq!(details.revoked_by, Some(revoked_by.to_string()));
+        assert!(details.revoked_at.is_some());
+        assert_eq!(details.revoke_reason.as_deref(), Some("Appeal accepted"));
+    }
+
+    // ── PunishmentStatus ─────────────────────────────────────────────────────
+
+    #[test]
+    fn status_is_active_until_expiry() {
+        let now = OffsetDateTime::now_utc();
+        assert_eq!(make_punishment("perm_ban", "Cheating", None).status(now), PunishmentStatus::Active);
+        assert_eq!(
+            make_punishment("mute", "Spam", Some(now + time::Duration::hours(1))).status(now),
+            PunishmentStatus::Active
+        );
+        assert_eq!(
+            make_punishment("mute", "Spam", Some(now - time::Duration::hours(1))).status(now),
+            PunishmentStatus::Expired
+        );
+    }
+
+    #[test]
+    fn status_prefers_revoked_over_expired() {
+        let now = OffsetDateTime::now_utc();
+        let mut p = make_punishment("mute", "Spam", Some(now - time::Duration::hours(1)));
+        p.active = false;
+        p.revoked = true;
+        assert_eq!(p.status(now), PunishmentStatus::Revoked);
+    }
+
+    #[test]
+    fn inactive_punishment_without_revocation_is_expired() {
+        let mut p = make_punishment("perm_ban", "Cheating", None);
+        p.active = false;
+        assert_eq!(p.status(OffsetDateTime::now_utc()), PunishmentStatus::Expired);
+    }
+
+    // ── PunishmentEvent ──────────────────────────────────────────────────────
+
+    fn make_event(punishment: PunishmentWithTemplate, issued: bool) -> PunishmentEvent {
+        PunishmentEvent {
+            sequence: 1,
+            issued,
+            punishment,
+        }
+    }
+
+    #[test]
+    fn events_of_active_punishments_notify_the_player() {
+        let now = OffsetDateTime::now_utc();
+        let mute = make_punishment("mute", "Spam", Some(now + time::Duration::hours(1)));
+        assert!(make_event(mute, false).notifies_player(now));
+    }
+
+    #[test]
+    fn revoked_punishments_do_not_notify_the_player() {
+        let now = OffsetDateTime::now_utc();
+        let mut mute = make_punishment("mute", "Spam", Some(now + time::Duration::hours(1)));
+        mute.active = false;
+        mute.revoked = true;
+        assert!(!make_event(mute, false).notifies_player(now));
+    }
+
+    #[test]
+    fn only_issuing_a_kick_notifies_the_player() {
+        let now = OffsetDateTime::now_utc();
+        let mut kick = make_punishment("kick", "Spam", None);
+        kick.active = false;
+        assert!(make_event(kick.clone(), true).notifies_player(now));
+        assert!(!make_event(kick, false).notifies_player(now));
+    }
+
+    #[test]
+    fn expired_punishments_do_not_notify_the_player() {
+        let now = OffsetDateTime::now_utc();
+        let ban = make_punishment("temp_ban", "Griefing", Some(now - time::Duration::minutes(1)));
+        assert!(!make_event(ban, false).notifies_player(now));
+    }
+
+    // ── PunishmentCursor ─────────────────────────────────────────────────────
+
+    #[test]
+    fn cursor_round_trips_with_microsecond_precision() {
+        let cursor = PunishmentCursor {
+            issued_at: OffsetDateTime::from_unix_timestamp_nanos(1_700_000_000_123_456_000).unwrap(),
+            id: Uuid::new_v4(),
+        };
+        assert_eq!(cursor.to_string().parse::<PunishmentCursor>().unwrap(), cursor);
+    }
+
+    #[test]
+    fn cursor_rejects_malformed_input() {
+        assert!("1700000000".parse::<PunishmentCursor>().is_err());
+        assert!("soon:00000000-0000-0000-0000-000000000000".parse::<PunishmentCursor>().is_err());
+        assert!("1700000000:not-a-uuid".parse::<PunishmentCursor>().is_err());
+    }
+
+    #[test]
+    fn validate_punishment_type_rejects_unknown_types() {
+        assert!(validate_punishment_type("temp_ban").is_ok());
+        assert!(validate_punishment_type("shadow_ban").is_err());
+    }
+
+    #[test]
+    fn login_message_hides_other_accounts_behind_the_address() {
+        let mut p = make_punishment("perm_ban", "Ban evasion", None);
+        p.ip_range = Some("203.0.113.7/32".parse().unwrap());
+        let owner = p.player_uuid;
+
+        let own = p.clone().into_login_message(owner);
+        assert_eq!(own.id, p.id.to_string());
+        assert!(!own.ip_match);
+
+        let other = p.into_login_message(Uuid::new_v4());
+        assert!(other.ip_match);
+        assert!(other.id.is_empty());
+        assert!(other.player_id.is_empty());
+        assert_eq!(other.ip_range.as_deref(), Some("203.0.113.7/32"));
+    }
+
+    #[test]
+    fn shadow_mutes_are_not_announced() {
+        assert!(notifies_in_chat("mute"));
+        assert!(notifies_in_chat("warn"));
+        assert!(!notifies_in_chat("shadow_mute"));
+        assert!(!notifies_in_chat("temp_ban"));
+    }
+
+    // ── ServerIdentity ───────────────────────────────────────────────────────
+
+    #[test]
+    fn global_scope_covers_every_server() {
+        assert!(ServerIdentity::default().covers("global", None));
+        assert!(ServerIdentity { proxy: true, server_group: None }.covers("global", None));
+    }
+
+    #[test]
+    fn proxy_scope_only_covers_proxies() {
+        let proxy = ServerIdentity { proxy: true, server_group: None };
+        let backend = ServerIdentity { proxy: false, server_group: Some("skyblock".to_string()) };
+        assert!(proxy.covers("proxy", None));
+        assert!(!backend.covers("proxy", None));
+    }
+
+    #[test]
+    fn server_group_scope_only_covers_matching_group() {
+        let skyblock = ServerIdentity { proxy: false, server_group: Some("skyblock".to_string()) };
+        let lobby = ServerIdentity { proxy: false, server_group: Some("lobby".to_string()) };
+        assert!(skyblock.covers("server_group", Some("skyblock")));
+        assert!(!lobby.covers("server_group", Some("skyblock")));
+        assert!(!ServerIdentity::default().covers("server_group", Some("skyblock")));
+    }
+
+    #[test]
+    fn validate_scope_requires_group_only_for_server_group() {
+        assert!(validate_scope("global", None).is_ok());
+        assert!(validate_scope("proxy", None).is_ok());
+        assert!(validate_scope("server_group", Some("skyblock")).is_ok());
+        assert!(validate_scope("server_group", None).is_err());
+        assert!(validate_scope("global", Some("skyblock")).is_err());
+        assert!(validate_scope("everywhere", None).is_err());
+    }
+
+    #[test]
+    fn validate_ip_range_rejects_broad_ranges() {
+        assert!(validate_ip_range(&"203.0.113.7/32".parse().unwrap()).is_ok());
+        assert!(validate_ip_range(&"203.0.0.0/16".parse().unwrap()).is_ok());
+        assert!(validate_ip_range(&"2001:db8::/32".parse().unwrap()).is_ok());
+        assert!(validate_ip_range(&"0.0.0.0/0".parse().unwrap()).is_err());
+        assert!(validate_ip_range(&"10.0.0.0/8".parse().unwrap()).is_err());
+        assert!(validate_ip_range(&"::/0".parse().unwrap()).is_err());
+    }
+}
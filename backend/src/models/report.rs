@@ -0,0 +1,98 @@
+use crate::error::{AppError, AppResult};
+use crate::grpc::generated;
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use time::OffsetDateTime;
+use uuid::Uuid;
+
+pub const MAX_REPORT_REASON_LENGTH: usize = 256;
+
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize)]
+pub struct Report {
+    pub id: Uuid,
+    pub reporter_uuid: Uuid,
+    pub reported_uuid: Uuid,
+    pub reason: String,
+    pub server_name: Option<String>,
+    pub created_at: OffsetDateTime,
+}
+
+impl From<Report> for generated::Report {
+    fn from(report: Report) -> Self {
+        generated::Report {
+            id: report.id.to_string(),
+            reporter_id: report.reporter_uuid.to_string(),
+            reported_id: report.reported_uuid.to_string(),
+            reason: report.reason,
+            server_name: report.server_name,
+            created_at: report.created_at.unix_timestamp(),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct NewReport {
+    pub reporter_uuid: Uuid,
+    pub reported_uuid: Uuid,
+    pub reason: String,
+    pub server_name: Option<String>,
+}
+
+impl NewReport {
+    pub fn validate(&self) -> AppResult<()> {
+        if self.reporter_uuid == self.reported_uuid {
+            return Err(AppError::CustomValidationError("Players cannot report themselves".to_string()));
+        }
+
+        if self.reason.trim().is_empty() || self.reason.len() > MAX_REPORT_REASON_LENGTH {
+            return Err(AppError::CustomValidationError(format!(
+                "The reason has to be between 1 and {} bytes long",
+                MAX_REPORT_REASON_LENGTH
+            )));
+        }
+
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, FromRow)]
+pub struct ReportCounts {
+    // Reports filed against the player
+    pub received: i64,
+    // Reports the player filed against others
+    pub filed: i64,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn make_report(reason: &str) -> NewReport {
+        NewReport {
+            reporter_uuid: Uuid::new_v4(),
+            reported_uuid: Uuid::new_v4(),
+            reason: reason.to_string(),
+            server_name: None,
+        }
+    }
+
+    // ── NewReport ────────────────────────────────────────────────────────────
+
+    #[test]
+    fn accepts_a_typical_report() {
+        assert!(make_report("Flying in survival").validate().is_ok());
+    }
+
+    #[test]
+    fn rejects_blank_and_oversized_reasons() {
+        assert!(make_report("  ").validate().is_err());
+        assert!(make_report(&"a".repeat(MAX_REPORT_REASON_LENGTH + 1)).validate().is_err());
+    }
+
+    #[test]
+    fn rejects_reporting_yourself() {
+        let mut report = make_report("Testing");
+        report.reported_uuid = report.reporter_uuid;
+        assert!(report.validate().is_err());
+    }
+}
@@ -0,0 +1,238 @@
+use crate::error::{AppError, AppResult};
+use crate::grpc::generated;
+use crate::models::PunishmentWithTemplate;
+use serde::{Deserialize, Serialize};
+use sqlx::types::Json;
+use sqlx::FromRow;
+use time::OffsetDateTime;
+use uuid::Uuid;
+
+/// Changes staff can make to an issued punishment, unset fields stay as they are.
+#[derive(Debug, Clone, Default)]
+pub struct PunishmentUpdate {
+    pub reason: Option<String>,
+    // Some(None) removes the evidence or note
+    pub evidence: Option<Option<String>>,
+    pub note: Option<Option<String>>,
+    pub expires_at: Option<OffsetDateTime>,
+}
+
+impl PunishmentUpdate {
+    /// Checks the update against the punishment and lists the fields it actually changes.
+    pub fn changes(&self, punishment: &PunishmentWithTemplate) -> AppResult<Vec<FieldChange>> {
+        if punishment.revoked {
+            return Err(AppError::CustomValidationError("Revoked punishments cannot be edited".to_string()));
+        }
+
+        let mut changes = Vec::new();
+
+        if let Some(reason) = &self.reason {
+            if reason.trim().is_empty() {
+                return Err(AppError::CustomValidationError("The reason cannot be empty".to_string()));
+            }
+            FieldChange::push(&mut changes, "reason", Some(punishment.reason.clone()), Some(reason.trim().to_string()));
+        }
+
+        if let Some(evidence) = &self.evidence {
+            FieldChange::push(&mut changes, "evidence", punishment.evidence.clone(), evidence.clone());
+        }
+
+        if let Some(note) = &self.note {
+            FieldChange::push(&mut changes, "note", punishment.note.clone(), note.clone());
+        }
+
+        if let Some(expires_at) = self.expires_at {
+            // Turning a permanent punishment into a temporary one would contradict its type
+            let Some(current) = punishment.expires_at else {
+                return Err(AppError::CustomValidationError(format!(
+                    "Only punishments that expire can get a new expiry, this {} does not",
+                    punishment.punishment_type
+                )));
+            };
+
+            if expires_at <= punishment.issued_at {
+                return Err(AppError::CustomValidationError(
+                    "The punishment cannot expire before it was issued".to_string(),
+                ));
+            }
+
+            FieldChange::push(
+                &mut changes,
+                "expires_at",
+                Some(current.unix_timestamp().to_string()),
+                Some(expires_at.unix_timestamp().to_string()),
+            );
+        }
+
+        if changes.is_empty() {
+            return Err(AppError::CustomValidationError("The update does not change anything".to_string()));
+        }
+
+        Ok(changes)
+    }
+}
+
+/// One changed field, timestamps are given in Unix seconds.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FieldChange {
+    pub field: String,
+    pub before: Option<String>,
+    pub after: Option<String>,
+}
+
+impl FieldChange {
+    fn push(changes: &mut Vec<FieldChange>, field: &str, before: Option<String>, after: Option<String>) {
+        if before != after {
+            changes.push(FieldChange {
+                field: field.to_string(),
+                before,
+                after,
+            });
+        }
+    }
+}
+
+impl From<FieldChange> for generated::FieldChange {
+    fn from(change: FieldChange) -> Self {
+        generated::FieldChange {
+            field: change.field,
+            before: change.before,
+            after: change.after,
+        }
+    }
+}
+
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize)]
+pub struct PunishmentRevision {
+    pub id: i64,
+    pub punishment_id: Uuid,
+    pub revision: i32,
+    pub edited_by: Uuid,
+    pub changes: Json<Vec<FieldChange>>,
+    pub created_at: OffsetDateTime,
+}
+
+impl From<PunishmentRevision> for generated::PunishmentRevision {
+    fn from(revision: PunishmentRevision) -> Self {
+        generated::PunishmentRevision {
+            punishment_id: revision.punishment_id.to_string(),
+            revision: revision.revision,
+            edited_by: revision.edited_by.to_string(),
+            changes: revision.changes.0.into_iter().map(Into::into).collect(),
+            created_at: revision.created_at.unix_timestamp(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use time::Duration;
+
+    fn make_punishment(expires_at: Option<OffsetDateTime>) -> PunishmentWithTemplate {
+        let issued_at = issued_at();
+
+        PunishmentWithTemplate {
+            id: Uuid::new_v4(),
+            player_uuid: Uuid::new_v4(),
+            staff_uuid: Uuid::new_v4(),
+            category_id: 1,
+            offense_number: 1,
+            punishment_type: if expires_at.is_some() { "temp_ban" } else { "perm_ban" }.to_string(),
+            reason: "Cheating".to_string(),
+            evidence: None,
+            note: Some("Seen by two moderators".to_string()),
+            issued_at,
+            expires_at,
+            active: true,
+            revoked: false,
+            revoked_by: None,
+            revoked_at: None,
+            revoke_reason: None,
+            created_at: issued_at,
+            updated_at: issued_at,
+            scope: "global".to_string(),
+            server_group: None,
+            ip_range: None,
+            template_id: Some(1),
+            points: 0,
+            threshold_points: None,
+            requires_acknowledgement: false,
+            acknowledged_at: None,
+            category_name: "Cheating".to_string(),
+        }
+    }
+
+    fn issued_at() -> OffsetDateTime {
+        OffsetDateTime::from_unix_timestamp(1_700_000_000).unwrap()
+    }
+
+    // ── PunishmentUpdate::changes ────────────────────────────────────────────
+
+    #[test]
+    fn lists_only_fields_that_change() {
+        let punishment = make_punishment(Some(issued_at() + Duration::days(7)));
+        let update = PunishmentUpdate {
+            reason: Some("Cheating ".to_string()),
+            evidence: Some(Some("https://example.com/clip".to_string())),
+            note: Some(None),
+            expires_at: Some(issued_at() + Duration::days(1)),
+        };
+
+        let changes = update.changes(&punishment).unwrap();
+        let fields: Vec<&str> = changes.iter().map(|change| change.field.as_str()).collect();
+        assert_eq!(fields, ["evidence", "note", "expires_at"]);
+        assert_eq!(changes[2].before.as_deref(), Some("1700604800"));
+        assert_eq!(changes[2].after.as_deref(), Some("1700086400"));
+    }
+
+    #[test]
+    fn rejects_updates_without_changes() {
+        let punishment = make_punishment(None);
+        let update = PunishmentUpdate {
+            reason: Some("Cheating".to_string()),
+            ..PunishmentUpdate::default()
+        };
+
+        assert!(update.changes(&punishment).is_err());
+        assert!(PunishmentUpdate::default().changes(&punishment).is_err());
+    }
+
+    #[test]
+    fn rejects_empty_reasons_and_revoked_punishments() {
+        let mut punishment = make_punishment(None);
+        let update = PunishmentUpdate {
+            reason: Some("  ".to_string()),
+            ..PunishmentUpdate::default()
+        };
+        assert!(update.changes(&punishment).is_err());
+
+        punishment.revoked = true;
+        let update = PunishmentUpdate {
+            evidence: Some(Some("https://example.com/clip".to_string())),
+            ..PunishmentUpdate::default()
+        };
+        assert!(update.changes(&punishment).is_err());
+    }
+
+    #[test]
+    fn expiry_must_follow_the_issue_time() {
+        let punishment = make_punishment(Some(issued_at() + Duration::days(7)));
+        let update = PunishmentUpdate {
+            expires_at: Some(issued_at()),
+            ..PunishmentUpdate::default()
+        };
+
+        assert!(update.changes(&punishment).is_err());
+    }
+
+    #[test]
+    fn permanent_punishments_keep_no_expiry() {
+        let update = PunishmentUpdate {
+            expires_at: Some(issued_at() + Duration::days(1)),
+            ..PunishmentUpdate::default()
+        };
+
+        assert!(update.changes(&make_punishment(None)).is_err());
+    }
+}
@@ -0,0 +1,337 @@
+use crate::error::{AppError, AppResult};
+use crate::grpc::generated;
+use crate::models::validate_punishment_type;
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+
+/// Permissions a staff role can grant on top of the staff flag.
+pub mod permissions {
+    pub const ALL: &str = "*";
+    pub const MANAGE_POLICY: &str = "policy.manage";
+    pub const APPROVE_PUNISHMENTS: &str = "punishment.approve";
+    pub const OVERRIDE_HIERARCHY: &str = "punishment.override_hierarchy";
+    pub const VIEW_AUDIT_LOG: &str = "audit.view";
+}
+
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize)]
+pub struct StaffRole {
+    pub id: i32,
+    pub name: String,
+    pub permissions: Vec<String>,
+    pub allowed_types: Option<Vec<String>>,
+    pub max_duration_minutes: Option<i32>,
+    pub daily_limit: Option<i32>,
+    pub weight: i32,
+}
+
+impl StaffRole {
+    pub fn has_permission(&self, permission: &str) -> bool {
+        self.permissions.iter().any(|granted| granted == permissions::ALL || granted == permission)
+    }
+
+    /// Whether members of this role may act on members of `other`, e.g. punish them or revoke their punishments.
+    pub fn outranks(&self, other: &StaffRole) -> bool {
+        self.weight > other.weight || self.has_permission(permissions::OVERRIDE_HIERARCHY)
+    }
+
+    /// Describes why members of this role may not edit `role`, if they may not. Only roles weighing
+    /// less than their own can be edited, so nobody can loosen the limits of a higher rank.
+    pub fn edit_denied(&self, role: &StaffRole) -> Option<String> {
+        if self.outranks(role) {
+            return None;
+        }
+
+        Some(format!(
+            "The {} role can only edit roles weighing less than {}, {} weighs {}",
+            self.name, self.weight, role.name, role.weight
+        ))
+    }
+
+    /// Like `edit_denied`, and the new weight has to stay below this role's, so nobody can raise a
+    /// role, their own included, above themselves.
+    pub fn weight_change_denied(&self, role: &StaffRole, weight: i32) -> Option<String> {
+        if let Some(denied) = self.edit_denied(role) {
+            return Some(denied);
+        }
+
+        if weight >= self.weight && !self.has_permission(permissions::OVERRIDE_HIERARCHY) {
+            return Some(format!("The {} role can only set weights below {}", self.name, self.weight));
+        }
+
+        None
+    }
+
+    /// Checks a punishment against the limits of the role and describes the first limit it hits.
+    /// `issued_today` counts the member's punishments of the last 24 hours.
+    pub fn exceeded_limit(&self, punishment_type: &str, duration_minutes: Option<i32>, issued_today: i64) -> Option<String> {
+        if let Some(allowed_types) = &self.allowed_types
+            && !allowed_types.iter().any(|allowed| allowed == punishment_type)
+        {
+            return Some(format!("The {} role cannot issue a {}", self.name, punishment_type));
+        }
+
+        if let Some(max_duration_minutes) = self.max_duration_minutes {
+            let exceeds = match punishment_type {
+                "perm_ban" => true,
+                _ => duration_minutes.is_some_and(|minutes| minutes > max_duration_minutes),
+            };
+
+            if exceeds {
+                return Some(format!(
+                    "The {} role can issue punishments of at most {} minutes, this {} lasts {}",
+                    self.name,
+                    max_duration_minutes,
+                    punishment_type,
+                    duration_minutes.map_or("forever".to_string(), |minutes| format!("{} minutes", minutes))
+                ));
+            }
+        }
+
+        if let Some(daily_limit) = self.daily_limit
+            && issued_today >= daily_limit as i64
+        {
+            return Some(format!(
+                "The {} role can issue at most {} punishments per 24 hours",
+                self.name, daily_limit
+            ));
+        }
+
+        None
+    }
+}
+
+impl From<StaffRole> for generated::StaffRole {
+    fn from(role: StaffRole) -> Self {
+        generated::StaffRole {
+            id: role.id,
+            name: role.name,
+            permissions: role.permissions,
+            restrict_types: role.allowed_types.is_some(),
+            allowed_types: role.allowed_types.unwrap_or_default(),
+            max_duration_minutes: role.max_duration_minutes,
+            daily_limit: role.daily_limit,
+            weight: role.weight,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct RoleLimits {
+    // None allows every type
+    pub allowed_types: Option<Vec<String>>,
+    pub max_duration_minutes: Option<i32>,
+    pub daily_limit: Option<i32>,
+}
+
+pub fn validate_role_limits(limits: &RoleLimits) -> AppResult<()> {
+    for punishment_type in limits.allowed_types.iter().flatten() {
+        validate_punishment_type(punishment_type)?;
+    }
+
+    if limits.max_duration_minutes.is_some_and(|minutes| minutes <= 0) {
+        return Err(AppError::CustomValidationError(
+            "The maximum duration must be at least one minute".to_string(),
+        ));
+    }
+
+    if limits.daily_limit.is_some_and(|limit| limit <= 0) {
+        return Err(AppError::CustomValidationError(
+            "The daily limit must be at least one punishment".to_string(),
+        ));
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn make_role(permissions: &[&str]) -> StaffRole {
+        StaffRole {
+            id: 1,
+            name: "Moderator".to_string(),
+            permissions: permissions.iter().map(|permission| permission.to_string()).collect(),
+            allowed_types: None,
+            max_duration_minutes: None,
+            daily_limit: None,
+            weight: 50,
+        }
+    }
+
+    fn make_trial_role() -> StaffRole {
+        StaffRole {
+            name: "Trial Moderator".to_string(),
+            allowed_types: Some(vec!["warn".to_string(), "mute".to_string(), "temp_ban".to_string()]),
+            max_duration_minutes: Some(1440),
+            daily_limit: Some(10),
+            ..make_role(&[])
+        }
+    }
+
+    // ── has_permission ───────────────────────────────────────────────────────
+
+    #[test]
+    fn grants_listed_permissions_only() {
+        let role = make_role(&[permissions::MANAGE_POLICY]);
+        assert!(role.has_permission(permissions::MANAGE_POLICY));
+        assert!(!role.has_permission("punishment.override"));
+    }
+
+    #[test]
+    fn wildcard_grants_every_permission() {
+        let role = make_role(&[permissions::ALL]);
+        assert!(role.has_permission(permissions::MANAGE_POLICY));
+        assert!(role.has_permission("punishment.override"));
+    }
+
+    #[test]
+    fn empty_role_grants_nothing() {
+        assert!(!make_role(&[]).has_permission(permissions::MANAGE_POLICY));
+    }
+
+    // ── outranks ─────────────────────────────────────────────────────────────
+
+    #[test]
+    fn only_heavier_roles_outrank() {
+        let moderator = make_role(&[]);
+        let administrator = StaffRole {
+            weight: 100,
+            ..make_role(&[])
+        };
+
+        assert!(administrator.outranks(&moderator));
+        assert!(!moderator.outranks(&administrator));
+        assert!(!moderator.outranks(&moderator));
+    }
+
+    #[test]
+    fn override_permission_outranks_everyone() {
+        let moderator = make_role(&[permissions::OVERRIDE_HIERARCHY]);
+        let administrator = StaffRole {
+            weight: 100,
+            ..make_role(&[])
+        };
+
+        assert!(moderator.outranks(&administrator));
+    }
+
+    // ── exceeded_limit ───────────────────────────────────────────────────────
+
+    #[test]
+    fn unlimited_role_can_issue_anything() {
+        let role = make_role(&[]);
+        assert_eq!(role.exceeded_limit("perm_ban", None, 1000), None);
+    }
+
+    #[test]
+    fn punishments_within_the_limits_pass() {
+        let role = make_trial_role();
+        assert_eq!(role.exceeded_limit("mute", Some(1440), 9), None);
+        assert_eq!(role.exceeded_limit("warn", None, 0), None);
+    }
+
+    #[test]
+    fn reports_types_the_role_cannot_issue() {
+        assert_eq!(
+            make_trial_role().exceeded_limit("kick", None, 0).unwrap(),
+            "The Trial Moderator role cannot issue a kick"
+        );
+    }
+
+    #[test]
+    fn reports_durations_over_the_maximum() {
+        assert_eq!(
+            make_trial_role().exceeded_limit("mute", Some(2880), 0).unwrap(),
+            "The Trial Moderator role can issue punishments of at most 1440 minutes, this mute lasts 2880 minutes"
+        );
+    }
+
+    #[test]
+    fn permanent_bans_exceed_any_maximum_duration() {
+        let role = StaffRole {
+            max_duration_minutes: Some(1440),
+            ..make_role(&[])
+        };
+        assert!(role.exceeded_limit("perm_ban", None, 0).is_some());
+        assert_eq!(role.exceeded_limit("kick", None, 0), None);
+    }
+
+    #[test]
+    fn reports_punishments_over_the_daily_limit() {
+        assert_eq!(
+            make_trial_role().exceeded_limit("warn", None, 10).unwrap(),
+            "The Trial Moderator role can issue at most 10 punishments per 24 hours"
+        );
+    }
+
+    // ── weight_change_denied ─────────────────────────────────────────────────
+
+    #[test]
+    fn roles_can_move_lower_roles_below_themselves() {
+        let moderator = make_role(&[permissions::MANAGE_POLICY]);
+        let trial = StaffRole { weight: 10, ..make_trial_role() };
+
+        assert_eq!(moderator.weight_change_denied(&trial, 49), None);
+    }
+
+    #[test]
+    fn roles_cannot_raise_a_role_to_their_own_weight() {
+        let moderator = make_role(&[permissions::MANAGE_POLICY]);
+        let trial = StaffRole { weight: 10, ..make_trial_role() };
+
+        assert_eq!(
+            moderator.weight_change_denied(&trial, 50).as_deref(),
+            Some("The Moderator role can only set weights below 50")
+        );
+    }
+
+    #[test]
+    fn roles_cannot_edit_their_own_or_higher_roles() {
+        let moderator = make_role(&[permissions::MANAGE_POLICY]);
+        let administrator = StaffRole {
+            name: "Administrator".to_string(),
+            weight: 100,
+            ..make_role(&[])
+        };
+
+        assert!(moderator.weight_change_denied(&moderator, 10).is_some());
+        assert_eq!(
+            moderator.edit_denied(&administrator).as_deref(),
+            Some("The Moderator role can only edit roles weighing less than 50, Administrator weighs 100")
+        );
+    }
+
+    #[test]
+    fn overriding_the_hierarchy_allows_any_weight() {
+        let owner = make_role(&[permissions::ALL]);
+        let administrator = StaffRole {
+            weight: 100,
+            ..make_role(&[])
+        };
+
+        assert_eq!(owner.weight_change_denied(&administrator, 500), None);
+    }
+
+    // ── validate_role_limits ─────────────────────────────────────────────────
+
+    #[test]
+    fn rejects_unknown_types_and_non_positive_limits() {
+        assert!(validate_role_limits(&RoleLimits::default()).is_ok());
+        assert!(validate_role_limits(&RoleLimits {
+            allowed_types: Some(vec!["shadow_ban".to_string()]),
+            ..RoleLimits::default()
+        })
+        .is_err());
+        assert!(validate_role_limits(&RoleLimits {
+            max_duration_minutes: Some(0),
+            ..RoleLimits::default()
+        })
+        .is_err());
+        assert!(validate_role_limits(&RoleLimits {
+            daily_limit: Some(-1),
+            ..RoleLimits::default()
+        })
+        .is_err());
+    }
+}
@@ -0,0 +1,278 @@
+use crate::error::{AppError, AppResult};
+use crate::grpc::generated;
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use time::OffsetDateTime;
+use uuid::Uuid;
+
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize)]
+pub struct SeveritySettings {
+    pub enabled: bool,
+    pub decay_days: Option<i32>,
+    pub updated_by: Option<Uuid>,
+    pub updated_at: OffsetDateTime,
+}
+
+impl SeveritySettings {
+    pub fn into_message(self, thresholds: Vec<SeverityThreshold>) -> generated::SeveritySettingsResponse {
+        generated::SeveritySettingsResponse {
+            enabled: self.enabled,
+            decay_days: self.decay_days,
+            thresholds: thresholds.into_iter().map(Into::into).collect(),
+            updated_by: self.updated_by.map(|uuid| uuid.to_string()),
+            updated_at: self.updated_at.unix_timestamp(),
+        }
+    }
+
+    /// Points a punishment still contributes, shrinking linearly to zero over `decay_days`.
+    pub fn remaining_points(&self, points: i32, issued_at: OffsetDateTime, now: OffsetDateTime) -> f64 {
+        let Some(decay_days) = self.decay_days else {
+            return points as f64;
+        };
+
+        let age_days = (now - issued_at).as_seconds_f64() / 86_400.0;
+        let remaining = 1.0 - age_days.max(0.0) / decay_days as f64;
+
+        points as f64 * remaining.max(0.0)
+    }
+
+    /// Sums the remaining points of the player's punishments, given as points and issue time.
+    pub fn total_points(&self, punishments: &[(i32, OffsetDateTime)], now: OffsetDateTime) -> f64 {
+        punishments
+            .iter()
+            .map(|(points, issued_at)| self.remaining_points(*points, *issued_at, now))
+            .sum()
+    }
+}
+
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize)]
+pub struct SeverityThreshold {
+    pub id: i32,
+    pub points: i32,
+    pub punishment_type: String,
+    pub duration_minutes: Option<i32>,
+    pub reason: String,
+    pub created_at: OffsetDateTime,
+}
+
+impl From<SeverityThreshold> for generated::SeverityThreshold {
+    fn from(threshold: SeverityThreshold) -> Self {
+        generated::SeverityThreshold {
+            points: threshold.points,
+            r#type: threshold.punishment_type,
+            duration_minutes: threshold.duration_minutes,
+            reason: threshold.reason,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NewSeverityThreshold {
+    pub points: i32,
+    pub punishment_type: String,
+    pub duration_minutes: Option<i32>,
+    pub reason: String,
+}
+
+impl From<generated::SeverityThreshold> for NewSeverityThreshold {
+    fn from(threshold: generated::SeverityThreshold) -> Self {
+        NewSeverityThreshold {
+            points: threshold.points,
+            punishment_type: threshold.r#type,
+            duration_minutes: threshold.duration_minutes,
+            reason: threshold.reason,
+        }
+    }
+}
+
+/// Where the player's severity points stand before their next punishment.
+#[derive(Debug, Clone)]
+pub struct SeverityPlan {
+    pub current_points: f64,
+    pub awarded_points: i32,
+    // Highest threshold the awarded points cross, if any
+    pub threshold: Option<SeverityThreshold>,
+}
+
+impl SeverityPlan {
+    pub fn new(current_points: f64, awarded_points: i32, thresholds: Vec<SeverityThreshold>) -> Self {
+        let after = current_points + awarded_points as f64;
+        let threshold = thresholds
+            .into_iter()
+            .filter(|threshold| current_points < threshold.points as f64 && after >= threshold.points as f64)
+            .max_by_key(|threshold| threshold.points);
+
+        Self {
+            current_points,
+            awarded_points,
+            threshold,
+        }
+    }
+
+    pub fn explain(&self) -> String {
+        let points = format!(
+            "{:.1} severity points plus {} for this punishment",
+            self.current_points, self.awarded_points
+        );
+
+        match &self.threshold {
+            Some(threshold) => format!("{}, reaching the {} point threshold for a {}", points, threshold.points, threshold.punishment_type),
+            None => points,
+        }
+    }
+}
+
+/// Checks the thresholds up front so staff get a readable error instead of a violated CHECK.
+pub fn validate_severity_thresholds(thresholds: &[NewSeverityThreshold]) -> AppResult<()> {
+    for threshold in thresholds {
+        let threshold_error = |message: String| {
+            AppError::CustomValidationError(format!("Threshold {}: {}", threshold.points, message))
+        };
+
+        if threshold.points <= 0 {
+            return Err(threshold_error("points must be at least one".to_string()));
+        }
+
+        match (threshold.punishment_type.as_str(), threshold.duration_minutes) {
+            ("temp_ban", Some(minutes)) if minutes > 0 => {}
+            ("temp_ban", _) => return Err(threshold_error("a temp_ban needs a duration of at least one minute".to_string())),
+            ("perm_ban", None) => {}
+            ("perm_ban", Some(_)) => return Err(threshold_error("a perm_ban cannot have a duration".to_string())),
+            (other, _) => return Err(threshold_error(format!("thresholds can only issue bans, not {}", other))),
+        }
+
+        if threshold.reason.trim().is_empty() {
+            return Err(threshold_error("the reason cannot be empty".to_string()));
+        }
+    }
+
+    let mut points: Vec<i32> = thresholds.iter().map(|threshold| threshold.points).collect();
+    points.sort();
+    if let Some(duplicate) = points.windows(2).find(|pair| pair[0] == pair[1]) {
+        return Err(AppError::CustomValidationError(format!(
+            "There is more than one threshold at {} points",
+            duplicate[0]
+        )));
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use time::Duration;
+
+    fn make_settings(decay_days: Option<i32>) -> SeveritySettings {
+        SeveritySettings {
+            enabled: true,
+            decay_days,
+            updated_by: None,
+            updated_at: OffsetDateTime::now_utc(),
+        }
+    }
+
+    fn make_threshold(points: i32, punishment_type: &str, duration_minutes: Option<i32>) -> SeverityThreshold {
+        SeverityThreshold {
+            id: points,
+            points,
+            punishment_type: punishment_type.to_string(),
+            duration_minutes,
+            reason: "Too many offenses".to_string(),
+            created_at: OffsetDateTime::now_utc(),
+        }
+    }
+
+    fn make_new_threshold(points: i32, punishment_type: &str, duration_minutes: Option<i32>) -> NewSeverityThreshold {
+        NewSeverityThreshold {
+            points,
+            punishment_type: punishment_type.to_string(),
+            duration_minutes,
+            reason: "Too many offenses".to_string(),
+        }
+    }
+
+    // ── SeveritySettings ─────────────────────────────────────────────────────
+
+    #[test]
+    fn points_without_decay_count_in_full() {
+        let now = OffsetDateTime::now_utc();
+        let settings = make_settings(None);
+
+        assert_eq!(settings.remaining_points(10, now - Duration::days(365), now), 10.0);
+    }
+
+    #[test]
+    fn points_decay_linearly_to_zero() {
+        let now = OffsetDateTime::now_utc();
+        let settings = make_settings(Some(30));
+
+        assert_eq!(settings.remaining_points(10, now, now), 10.0);
+        assert_eq!(settings.remaining_points(10, now - Duration::days(15), now), 5.0);
+        assert_eq!(settings.remaining_points(10, now - Duration::days(30), now), 0.0);
+        assert_eq!(settings.remaining_points(10, now - Duration::days(90), now), 0.0);
+    }
+
+    #[test]
+    fn total_points_sums_across_punishments() {
+        let now = OffsetDateTime::now_utc();
+        let settings = make_settings(Some(10));
+        let punishments = [(4, now), (6, now - Duration::days(5)), (20, now - Duration::days(20))];
+
+        assert_eq!(settings.total_points(&punishments, now), 7.0);
+    }
+
+    // ── SeverityPlan ─────────────────────────────────────────────────────────
+
+    #[test]
+    fn plan_picks_the_highest_crossed_threshold() {
+        let thresholds = vec![
+            make_threshold(10, "temp_ban", Some(1440)),
+            make_threshold(20, "temp_ban", Some(10080)),
+            make_threshold(50, "perm_ban", None),
+        ];
+
+        let plan = SeverityPlan::new(8.0, 15, thresholds);
+        assert_eq!(plan.threshold.map(|threshold| threshold.points), Some(20));
+    }
+
+    #[test]
+    fn plan_ignores_thresholds_already_passed() {
+        let thresholds = vec![make_threshold(10, "temp_ban", Some(1440))];
+
+        assert!(SeverityPlan::new(12.0, 5, thresholds.clone()).threshold.is_none());
+        assert!(SeverityPlan::new(4.0, 5, thresholds.clone()).threshold.is_none());
+        assert!(SeverityPlan::new(5.0, 5, thresholds).threshold.is_some());
+    }
+
+    // ── validate_severity_thresholds ─────────────────────────────────────────
+
+    #[test]
+    fn accepts_typical_thresholds() {
+        let thresholds = vec![
+            make_new_threshold(10, "temp_ban", Some(1440)),
+            make_new_threshold(30, "perm_ban", None),
+        ];
+
+        assert!(validate_severity_thresholds(&thresholds).is_ok());
+        assert!(validate_severity_thresholds(&[]).is_ok());
+    }
+
+    #[test]
+    fn rejects_invalid_thresholds() {
+        assert!(validate_severity_thresholds(&[make_new_threshold(0, "perm_ban", None)]).is_err());
+        assert!(validate_severity_thresholds(&[make_new_threshold(10, "mute", Some(60))]).is_err());
+        assert!(validate_severity_thresholds(&[make_new_threshold(10, "temp_ban", None)]).is_err());
+        assert!(validate_severity_thresholds(&[make_new_threshold(10, "perm_ban", Some(60))]).is_err());
+    }
+
+    #[test]
+    fn rejects_duplicate_threshold_points() {
+        let thresholds = vec![
+            make_new_threshold(10, "temp_ban", Some(1440)),
+            make_new_threshold(10, "perm_ban", None),
+        ];
+
+        assert!(validate_severity_thresholds(&thresholds).is_err());
+    }
+}
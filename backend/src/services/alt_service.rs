@@ -0,0 +1,163 @@
+use crate::config::var_or;
+use crate::error::{AppError, AppResult};
+use crate::models::{AltAccount, BannedAlt};
+use sqlx::PgPool;
+use std::str::FromStr;
+use uuid::Uuid;
+
+/// What `GetPlayerLogin` does when the player shares an address with a banned account.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum BanEvasionAction {
+    Off,
+    Flag,   // Alert staff but let the player in
+    Deny,   // Alert staff and deny the login
+}
+
+impl FromStr for BanEvasionAction {
+    type Err = AppError;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value.to_ascii_lowercase().as_str() {
+            "off" => Ok(Self::Off),
+            "flag" => Ok(Self::Flag),
+            "deny" => Ok(Self::Deny),
+            _ => Err(AppError::CustomValidationError(format!("Unknown ban evasion action: {}", value))),
+        }
+    }
+}
+
+pub struct AltService {
+    pool: PgPool,
+    window_days: i32,
+    ban_evasion_action: BanEvasionAction,
+}
+
+impl AltService {
+    pub const MAX_DEPTH: i32 = 3;
+    pub const MAX_WINDOW_DAYS: i32 = 365;
+
+    pub fn new(pool: PgPool) -> Self {
+        Self {
+            pool,
+            window_days: var_or("ALT_WINDOW_DAYS", 30).clamp(1, Self::MAX_WINDOW_DAYS),
+            ban_evasion_action: var_or("BAN_EVASION_ACTION", BanEvasionAction::Flag),
+        }
+    }
+
+    pub fn window_days(&self) -> i32 {
+        self.window_days
+    }
+
+    pub fn ban_evasion_action(&self) -> BanEvasionAction {
+        self.ban_evasion_action
+    }
+
+    /// Walks accounts that used the same address as the player within the window, up to `max_depth` hops away.
+    pub async fn get_alt_accounts(&self, player_uuid: Uuid, window_days: i32, max_depth: i32) -> AppResult<Vec<AltAccount>> {
+        if !(1..=Self::MAX_WINDOW_DAYS).contains(&window_days) {
+            return Err(AppError::CustomValidationError(format!(
+                "The window has to be between 1 and {} days",
+                Self::MAX_WINDOW_DAYS
+            )));
+        }
+
+        let alts = sqlx::query_as::<_, AltAccount>(
+            r#"
+            WITH RECURSIVE alt_graph (player_uuid, depth, linked_player_uuid, shared_address) AS (
+                SELECT $1::UUID, 0, $1::UUID, NULL::INET
+                UNION
+                SELECT other.player_uuid, graph.depth + 1, graph.player_uuid, other.ip_address
+                FROM alt_graph graph
+                INNER JOIN player_addresses own ON own.player_uuid = graph.player_uuid
+                INNER JOIN player_addresses other ON other.ip_address = own.ip_address
+                                                 AND other.player_uuid <> own.player_uuid
+                WHERE graph.depth < $3
+                  AND own.last_seen >= NOW() - make_interval(days => $2)
+                  AND other.last_seen >= NOW() - make_interval(days => $2)
+            )
+            SELECT * FROM (
+                SELECT DISTINCT ON (graph.player_uuid)
+                    graph.player_uuid,
+                    pl.username,
+                    graph.depth,
+                    graph.linked_player_uuid,
+                    graph.shared_address,
+                    (SELECT MAX(pa.last_seen) FROM player_addresses pa WHERE pa.player_uuid = graph.player_uuid) AS last_seen,
+                    EXISTS (
+                        SELECT 1 FROM punishments p
+                        WHERE p.player_uuid = graph.player_uuid
+                          AND p.punishment_type IN ('temp_ban', 'perm_ban')
+                          AND p.active = true
+                          AND p.revoked = false
+                          AND (p.expires_at IS NULL OR p.expires_at > NOW())
+                    ) AS banned
+                FROM alt_graph graph
+                LEFT JOIN players pl ON pl.uuid = graph.player_uuid
+                WHERE graph.player_uuid <> $1
+                ORDER BY graph.player_uuid, graph.depth
+            ) alts
+            ORDER BY alts.depth, alts.last_seen DESC
+            "#
+        )
+        .bind(player_uuid)
+        .bind(window_days)
+        .bind(max_depth.clamp(1, Self::MAX_DEPTH))
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(alts)
+    }
+
+    /// Returns the active network-wide account ban of every direct alt of the player.
+    pub async fn get_banned_alts(&self, player_uuid: Uuid) -> AppResult<Vec<BannedAlt>> {
+        let banned_alts = sqlx::query_as::<_, BannedAlt>(
+            r#"
+            SELECT DISTINCT ON (p.player_uuid)
+                pl.username,
+                p.*,
+                pc.name AS category_name
+            FROM player_addresses own
+            INNER JOIN player_addresses other ON other.ip_address = own.ip_address
+                                             AND other.player_uuid <> own.player_uuid
+            INNER JOIN punishments p ON p.player_uuid = other.player_uuid
+            INNER JOIN punishment_categories pc ON p.category_id = pc.id
+            LEFT JOIN players pl ON pl.uuid = p.player_uuid
+            WHERE own.player_uuid = $1
+              AND own.last_seen >= NOW() - make_interval(days => $2)
+              AND other.last_seen >= NOW() - make_interval(days => $2)
+              AND p.punishment_type IN ('temp_ban', 'perm_ban')
+              AND p.scope = 'global'
+              AND p.ip_range IS NULL
+              AND p.active = true
+              AND p.revoked = false
+              AND (p.expires_at IS NULL OR p.expires_at > NOW())
+            ORDER BY p.player_uuid, p.expires_at DESC NULLS FIRST
+            "#
+        )
+        .bind(player_uuid)
+        .bind(self.window_days)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(banned_alts)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // ── BanEvasionAction ─────────────────────────────────────────────────────
+
+    #[test]
+    fn parses_actions_case_insensitively() {
+        assert_eq!("off".parse::<BanEvasionAction>().unwrap(), BanEvasionAction::Off);
+        assert_eq!("Flag".parse::<BanEvasionAction>().unwrap(), BanEvasionAction::Flag);
+        assert_eq!("DENY".parse::<BanEvasionAction>().unwrap(), BanEvasionAction::Deny);
+    }
+
+    #[test]
+    fn rejects_unknown_action() {
+        assert!("kick".parse::<BanEvasionAction>().is_err());
+    }
+}
@@ -0,0 +1,87 @@
+use crate::error::AppResult;
+use crate::models::{AuditChainBreak, AuditChainLink, AuditChainVerifier, AuditEvent, AuditFilter, NewAuditEvent};
+use sqlx::{PgConnection, PgPool};
+use tokio_stream::StreamExt;
+
+pub struct AuditService {
+    pool: PgPool,
+}
+
+impl AuditService {
+    pub fn new(pool: PgPool) -> Self {
+        Self {
+            pool
+        }
+    }
+
+    /// Writes the event on the caller's connection so it commits or rolls back with the change itself.
+    /// The database chains it to the previous event.
+    pub async fn record(conn: &mut PgConnection, event: NewAuditEvent) -> AppResult<()> {
+        sqlx::query(
+            r#"
+            INSERT INTO audit_events (actor_uuid, action, target_type, target_id, details)
+            VALUES ($1, $2, $3, $4, $5)
+            "#,
+        )
+        .bind(event.actor_uuid)
+        .bind(event.action)
+        .bind(event.target_type)
+        .bind(event.target_id)
+        .bind(event.details)
+        .execute(conn)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Lists the events matching every set filter, newest first, one page before the event `cursor`.
+    pub async fn list_events(&self, filter: &AuditFilter, cursor: Option<i64>, limit: i64) -> AppResult<Vec<AuditEvent>> {
+        let events = sqlx::query_as::<_, AuditEvent>(
+            r#"
+            SELECT * FROM audit_events
+            WHERE ($1::UUID IS NULL OR actor_uuid = $1)
+              AND ($2::VARCHAR IS NULL OR action = $2)
+              AND ($3::VARCHAR IS NULL OR target_type = $3)
+              AND ($4::TEXT IS NULL OR target_id = $4)
+              AND ($5::TIMESTAMPTZ IS NULL OR created_at >= $5)
+              AND ($6::TIMESTAMPTZ IS NULL OR created_at < $6)
+              AND ($7::BIGINT IS NULL OR id < $7)
+            ORDER BY id DESC
+            LIMIT $8
+            "#,
+        )
+        .bind(filter.actor_uuid)
+        .bind(filter.action.as_deref())
+        .bind(filter.target_type.as_deref())
+        .bind(filter.target_id.as_deref())
+        .bind(filter.created_after)
+        .bind(filter.created_before)
+        .bind(cursor)
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(events)
+    }
+
+    /// Walks the whole chain from the first event and stops at the first one that breaks it.
+    pub async fn verify_chain(&self) -> AppResult<(AuditChainVerifier, Option<AuditChainBreak>)> {
+        let mut verifier = AuditChainVerifier::default();
+        let mut links = sqlx::query_as::<_, AuditChainLink>(
+            r#"
+            SELECT id, actor_uuid, action, target_type, target_id, details::TEXT AS details, prev_hash, hash, created_at
+            FROM audit_events
+            ORDER BY id
+            "#,
+        )
+        .fetch(&self.pool);
+
+        while let Some(link) = links.next().await {
+            if let Err(chain_break) = verifier.check(&link?) {
+                return Ok((verifier, Some(chain_break)));
+            }
+        }
+
+        Ok((verifier, None))
+    }
+}
@@ -1,15 +1,81 @@
+use crate::config::var_or;
+use crate::error::AppResult;
 use crate::handler::BroadcastHandler;
-use crate::models::PunishmentWithTemplate;
+use crate::models::{LiveEvent, StaffAlert};
+use crate::services::PunishmentService;
+use sqlx::postgres::PgListener;
+use sqlx::PgPool;
+use std::time::Duration;
 use uuid::Uuid;
 
 pub struct BroadcastService {
-    pub punishment: BroadcastHandler<Uuid, PunishmentWithTemplate>,
+    // Keyed by the players online on the listening proxy
+    pub live: BroadcastHandler<Uuid, LiveEvent>,
 }
 
 impl BroadcastService {
     pub fn new() -> Self {
         Self {
-            punishment: BroadcastHandler::new()
+            live: BroadcastHandler::new()
         }
     }
-}
\ No newline at end of file
+
+    /// Delivers the alert to every recipient that is online on a proxy with a live stream.
+    pub async fn send_staff_alert(&self, recipients: &[Uuid], alert: StaffAlert) {
+        for recipient in recipients {
+            let _ = self.live.send_event(*recipient, LiveEvent::StaffAlert(alert.clone())).await;
+        }
+    }
+
+    /// Forwards every row written to the `punishment_events` outbox to the live listeners.
+    pub async fn relay_punishment_events(&self, pool: &PgPool, punishment_service: &PunishmentService) -> AppResult<()> {
+        let mut listener = PgListener::connect_with(pool).await?;
+        listener.listen("punishment_events").await?;
+
+        let mut last_sequence = punishment_service.get_latest_event_sequence().await?;
+
+        loop {
+            // Notifications only signal that something changed. Reading everything after the last
+            // relayed sequence also picks up events whose notification was lost during a reconnect.
+            if let Err(e) = listener.recv().await {
+                eprintln!("Error receiving punishment event notification: {}", e);
+                tokio::time::sleep(Duration::from_secs(1)).await;
+                continue;
+            }
+
+            // Retried until it succeeds, the events are committed and may not get another notification
+            let mut backoff = Duration::from_secs(1);
+            let events = loop {
+                match punishment_service.get_events_after(last_sequence).await {
+                    Ok(events) => break events,
+                    Err(e) => {
+                        eprintln!("Error loading punishment events, retrying in {}s: {}", backoff.as_secs(), e);
+                        tokio::time::sleep(backoff).await;
+                        backoff = (backoff * 2).min(Duration::from_secs(30));
+                    }
+                }
+            };
+
+            for event in events {
+                last_sequence = event.sequence;
+                let _ = self.live.send_event(event.punishment.player_uuid, LiveEvent::Punishment(Box::new(event))).await;
+            }
+        }
+    }
+
+    /// Periodically closes live listeners that stopped sending heartbeats.
+    pub async fn evict_idle_listeners(&self) -> AppResult<()> {
+        // Checked every third of the timeout, which has to stay above zero for the interval
+        let idle_timeout = Duration::from_secs(var_or("LIVE_STREAM_IDLE_TIMEOUT_SECONDS", 90).max(3));
+        let mut interval = tokio::time::interval(idle_timeout / 3);
+
+        loop {
+            interval.tick().await;
+
+            let evicted = self.live.evict_idle_listeners(idle_timeout).await;
+            if evicted > 0 {
+                eprintln!("Evicted {} live punishment listener(s) after missed heartbeats", evicted);
+            }
+        }
+    }
+}
@@ -0,0 +1,199 @@
+use crate::error::{AppError, AppResult};
+use crate::models::{
+    ChatFilter, ChatFilterRule, ChatFilterSettings, ChatFilterUpdate, FilterVerdict, IssueOutcome, NewAuditEvent, NewPunishment,
+    PlayerChatState, SYSTEM_PLAYER_UUID,
+};
+use crate::services::{AuditService, PunishmentService};
+use serde_json::json;
+use sqlx::{PgConnection, PgPool};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use time::{Duration, OffsetDateTime};
+use tokio::sync::RwLock;
+use uuid::Uuid;
+
+pub struct ChatFilterService {
+    pool: PgPool,
+    punishment_service: Arc<PunishmentService>,
+    // Compiled on first use and replaced whenever staff update the configuration
+    filter: RwLock<Option<Arc<ChatFilter>>>,
+    players: Mutex<PlayerStates>,
+}
+
+struct PlayerStates {
+    states: HashMap<Uuid, PlayerChatState>,
+    pruned_at: OffsetDateTime,
+}
+
+impl ChatFilterService {
+    const PRUNE_INTERVAL: Duration = Duration::minutes(1);
+
+    pub fn new(pool: PgPool, punishment_service: Arc<PunishmentService>) -> Self {
+        Self {
+            pool,
+            punishment_service,
+            filter: RwLock::new(None),
+            players: Mutex::new(PlayerStates {
+                states: HashMap::new(),
+                pruned_at: OffsetDateTime::now_utc(),
+            }),
+        }
+    }
+
+    /// Runs the message through the filter and issues a punishment in the configured category once
+    /// the player's violations reach the threshold. A punishment that cannot be issued, e.g. because
+    /// the player is a staff member, is logged and leaves the verdict as it is.
+    pub async fn check_chat(&self, player_uuid: Uuid, message: &str) -> AppResult<(FilterVerdict, Option<IssueOutcome>)> {
+        let filter = self.filter().await?;
+        let now = OffsetDateTime::now_utc();
+
+        let verdict = {
+            let mut players = self.players.lock().expect("chat filter state poisoned");
+
+            if now - players.pruned_at > Self::PRUNE_INTERVAL {
+                let cutoff = now - filter.memory();
+                players.states.retain(|_, state| !state.is_stale(cutoff));
+                players.pruned_at = now;
+            }
+
+            filter.check(message, players.states.entry(player_uuid).or_default(), now)
+        };
+
+        let Some(category_id) = verdict.punish_category_id else {
+            return Ok((verdict, None));
+        };
+
+        let punishment = NewPunishment {
+            player_uuid,
+            category_id,
+            reason: None,
+            evidence: None,
+            note: Some(format!(
+                "Issued by the chat filter after {} violations ({}), the last one: {}",
+                verdict.violations,
+                verdict.reasons.join(", "),
+                message
+            )),
+            scope: None,
+            server_group: None,
+            ip_range: None,
+        };
+
+        match self.punishment_service.issue_punishment(SYSTEM_PLAYER_UUID, punishment, true).await {
+            Ok(outcome) => Ok((verdict, Some(outcome))),
+            Err(e) => {
+                eprintln!("Chat filter could not punish {}: {}", player_uuid, e);
+                Ok((verdict, None))
+            }
+        }
+    }
+
+    async fn filter(&self) -> AppResult<Arc<ChatFilter>> {
+        if let Some(filter) = self.filter.read().await.as_ref() {
+            return Ok(filter.clone());
+        }
+
+        let mut filter = self.filter.write().await;
+        if let Some(filter) = filter.as_ref() {
+            return Ok(filter.clone());
+        }
+
+        let mut conn = self.pool.acquire().await?;
+        let (settings, rules) = Self::load_chat_filter(&mut conn).await?;
+        let compiled = Arc::new(ChatFilter::new(&settings, &rules)?);
+        *filter = Some(compiled.clone());
+
+        Ok(compiled)
+    }
+
+    pub async fn get_chat_filter(&self) -> AppResult<(ChatFilterSettings, Vec<ChatFilterRule>)> {
+        let mut conn = self.pool.acquire().await?;
+        Self::load_chat_filter(&mut conn).await
+    }
+
+    async fn load_chat_filter(conn: &mut PgConnection) -> AppResult<(ChatFilterSettings, Vec<ChatFilterRule>)> {
+        let settings = sqlx::query_as::<_, ChatFilterSettings>("SELECT * FROM chat_filter_settings")
+            .fetch_one(&mut *conn)
+            .await?;
+
+        let rules = sqlx::query_as::<_, ChatFilterRule>("SELECT * FROM chat_filter_rules ORDER BY id")
+            .fetch_all(&mut *conn)
+            .await?;
+
+        Ok((settings, rules))
+    }
+
+    /// Replaces the filter configuration, including every rule, and applies it to the next message.
+    pub async fn update_chat_filter(
+        &self,
+        staff_uuid: Uuid,
+        update: ChatFilterUpdate,
+    ) -> AppResult<(ChatFilterSettings, Vec<ChatFilterRule>)> {
+        update.validate()?;
+
+        let mut tx = self.pool.begin().await?;
+
+        if let Some(category_id) = update.punish_category_id {
+            let active = sqlx::query_scalar::<_, bool>("SELECT active FROM punishment_categories WHERE id = $1")
+                .bind(category_id)
+                .fetch_optional(&mut *tx)
+                .await?;
+
+            if active != Some(true) {
+                return Err(AppError::NotFound(format!("Punishment category {} does not exist or is inactive", category_id)));
+            }
+        }
+
+        let before = Self::load_chat_filter(&mut tx).await?;
+
+        sqlx::query(
+            r#"
+            UPDATE chat_filter_settings
+            SET enabled = $1, advertising_action = $2, allowed_domains = $3, spam_max_repeats = $4,
+                spam_window_seconds = $5, punish_category_id = $6, punish_after_violations = $7,
+                violation_window_minutes = $8, updated_by = $9, updated_at = NOW()
+            "#
+        )
+        .bind(update.enabled)
+        .bind(&update.advertising_action)
+        .bind(&update.allowed_domains)
+        .bind(update.spam_max_repeats)
+        .bind(update.spam_window_seconds)
+        .bind(update.punish_category_id)
+        .bind(update.punish_after_violations)
+        .bind(update.violation_window_minutes)
+        .bind(staff_uuid)
+        .execute(&mut *tx)
+        .await?;
+
+        sqlx::query("DELETE FROM chat_filter_rules")
+            .execute(&mut *tx)
+            .await?;
+
+        for rule in &update.rules {
+            sqlx::query("INSERT INTO chat_filter_rules (kind, pattern, action) VALUES ($1, $2, $3)")
+                .bind(&rule.kind)
+                .bind(&rule.pattern)
+                .bind(&rule.action)
+                .execute(&mut *tx)
+                .await?;
+        }
+
+        let after = Self::load_chat_filter(&mut tx).await?;
+        let compiled = Arc::new(ChatFilter::new(&after.0, &after.1)?);
+
+        AuditService::record(&mut tx, NewAuditEvent {
+            actor_uuid: Some(staff_uuid),
+            action: "chat_filter.update".to_string(),
+            target_type: "chat_filter_settings".to_string(),
+            target_id: None,
+            details: json!({ "before": before, "after": after }),
+        })
+        .await?;
+
+        tx.commit().await?;
+        *self.filter.write().await = Some(compiled);
+
+        Ok(after)
+    }
+}
@@ -0,0 +1,162 @@
+use crate::config::var_or;
+use crate::error::AppResult;
+use crate::models::{chat_partition_day, chat_partition_name, ChatLine, NewChatLine};
+use sqlx::PgPool;
+use time::{Date, Duration, OffsetDateTime};
+use uuid::Uuid;
+
+pub struct ChatService {
+    pool: PgPool,
+    retention_days: i64,
+}
+
+impl ChatService {
+    // Partitions are created this many days ahead so a late maintenance run never leaves a gap
+    const PARTITIONS_AHEAD: i64 = 2;
+
+    pub fn new(pool: PgPool) -> Self {
+        Self {
+            pool,
+            retention_days: var_or("CHAT_RETENTION_DAYS", 30),
+        }
+    }
+
+    pub fn retention(&self) -> Duration {
+        Duration::days(self.retention_days)
+    }
+
+    /// Stores a batch of forwarded lines in one statement.
+    pub async fn insert_lines(&self, lines: &[NewChatLine]) -> AppResult<u64> {
+        if lines.is_empty() {
+            return Ok(0);
+        }
+
+        let result = sqlx::query(
+            r#"
+            INSERT INTO chat_messages (player_uuid, server_name, server_group, message, sent_at)
+            SELECT * FROM UNNEST($1::UUID[], $2::VARCHAR[], $3::VARCHAR[], $4::TEXT[], $5::TIMESTAMPTZ[])
+            "#
+        )
+        .bind(lines.iter().map(|line| line.player_uuid).collect::<Vec<_>>())
+        .bind(lines.iter().map(|line| line.server_name.clone()).collect::<Vec<_>>())
+        .bind(lines.iter().map(|line| line.server_group.clone()).collect::<Vec<_>>())
+        .bind(lines.iter().map(|line| line.message.clone()).collect::<Vec<_>>())
+        .bind(lines.iter().map(|line| line.sent_at).collect::<Vec<_>>())
+        .execute(&self.pool)
+        .await?;
+
+        Ok(result.rows_affected())
+    }
+
+    /// Returns up to `before` lines the player sent until `at` and up to `after` lines following it,
+    /// oldest first.
+    pub async fn get_context(
+        &self,
+        player_uuid: Uuid,
+        at: OffsetDateTime,
+        before: i64,
+        after: i64,
+        server_name: Option<&str>,
+    ) -> AppResult<Vec<ChatLine>> {
+        let lines = sqlx::query_as::<_, ChatLine>(
+            r#"
+            SELECT context.*, pl.username
+            FROM (
+                (
+                    SELECT id, player_uuid, server_name, server_group, message, sent_at
+                    FROM chat_messages
+                    WHERE player_uuid = $1 AND sent_at <= $2 AND ($5::VARCHAR IS NULL OR server_name = $5)
+                    ORDER BY sent_at DESC, id DESC
+                    LIMIT $3
+                )
+                UNION ALL
+                (
+                    SELECT id, player_uuid, server_name, server_group, message, sent_at
+                    FROM chat_messages
+                    WHERE player_uuid = $1 AND sent_at > $2 AND ($5::VARCHAR IS NULL OR server_name = $5)
+                    ORDER BY sent_at, id
+                    LIMIT $4
+                )
+            ) context
+            LEFT JOIN players pl ON pl.uuid = context.player_uuid
+            ORDER BY context.sent_at, context.id
+            "#
+        )
+        .bind(player_uuid)
+        .bind(at)
+        .bind(before)
+        .bind(after)
+        .bind(server_name)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(lines)
+    }
+
+    /// Keeps the daily partitions in place and removes chat past the retention, once an hour.
+    pub async fn manage_partitions(&self) -> AppResult<()> {
+        let mut interval = tokio::time::interval(std::time::Duration::from_secs(3600));
+
+        loop {
+            interval.tick().await;
+
+            if let Err(e) = self.maintain_partitions().await {
+                eprintln!("Error maintaining chat partitions: {}", e);
+            }
+        }
+    }
+
+    /// Creates the partitions for today and the next days and drops those past the retention.
+    /// Runs before the server starts as well, so today's chat never lands in the default partition.
+    pub async fn maintain_partitions(&self) -> AppResult<()> {
+        let now = OffsetDateTime::now_utc();
+        let today = now.date();
+
+        for offset in 0..=Self::PARTITIONS_AHEAD {
+            let day = today + Duration::days(offset);
+            sqlx::query(&format!(
+                "CREATE TABLE IF NOT EXISTS {} PARTITION OF chat_messages FOR VALUES FROM ({}) TO ({})",
+                chat_partition_name(day),
+                Self::day_start(day),
+                Self::day_start(day.next_day().unwrap_or(day)),
+            ))
+            .execute(&self.pool)
+            .await?;
+        }
+
+        let cutoff = now - self.retention();
+        let partitions = sqlx::query_scalar::<_, String>(
+            r#"
+            SELECT child.relname::TEXT
+            FROM pg_inherits i
+            INNER JOIN pg_class parent ON parent.oid = i.inhparent
+            INNER JOIN pg_class child ON child.oid = i.inhrelid
+            WHERE parent.relname = 'chat_messages'
+            "#
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        // A partition goes once the whole day it holds is past the cutoff
+        for partition in partitions {
+            if let Some(day) = chat_partition_day(&partition)
+                && day.next_day().is_some_and(|end| end <= cutoff.date())
+            {
+                sqlx::query(&format!("DROP TABLE IF EXISTS {}", partition))
+                    .execute(&self.pool)
+                    .await?;
+            }
+        }
+
+        sqlx::query("DELETE FROM chat_messages_default WHERE sent_at < $1")
+            .bind(cutoff)
+            .execute(&self.pool)
+            .await?;
+
+        Ok(())
+    }
+
+    fn day_start(day: Date) -> String {
+        format!("'{:04}-{:02}-{:02} 00:00:00+00'", day.year(), day.month() as u8, day.day())
+    }
+}
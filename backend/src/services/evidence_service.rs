@@ -0,0 +1,169 @@
+use crate::error::{AppError, AppResult};
+use crate::models::{Evidence, EvidenceTarget, NewAuditEvent, NewEvidence};
+use crate::services::AuditService;
+use crate::storage::BlobStore;
+use serde_json::json;
+use sha2::{Digest, Sha256};
+use sqlx::{PgConnection, PgPool};
+use std::sync::Arc;
+use uuid::Uuid;
+
+pub struct EvidenceService {
+    pool: PgPool,
+    store: Arc<dyn BlobStore>,
+}
+
+impl EvidenceService {
+    pub fn new(pool: PgPool, store: Arc<dyn BlobStore>) -> Self {
+        Self {
+            pool,
+            store,
+        }
+    }
+
+    /// Stores the contents under their digest, skipping the write when an identical blob exists,
+    /// and records the upload. `sha256` has to be the digest of `data`.
+    pub async fn store_evidence(
+        &self,
+        uploader_uuid: Uuid,
+        evidence: NewEvidence,
+        data: Vec<u8>,
+        sha256: String,
+        target: Option<EvidenceTarget>,
+    ) -> AppResult<Evidence> {
+        let size_bytes = data.len() as i64;
+
+        // A blob without a row is harmless if the transaction fails, the next identical upload reuses it
+        if !self.store.exists(&sha256).await? {
+            self.store.put(&sha256, data).await?;
+        }
+
+        let mut tx = self.pool.begin().await?;
+
+        let stored = sqlx::query_as::<_, Evidence>(
+            r#"
+            INSERT INTO evidence (kind, file_name, content_type, size_bytes, sha256, uploaded_by)
+            VALUES ($1, $2, $3, $4, $5, $6)
+            RETURNING *
+            "#
+        )
+        .bind(&evidence.kind)
+        .bind(&evidence.file_name)
+        .bind(&evidence.content_type)
+        .bind(size_bytes)
+        .bind(&sha256)
+        .bind(uploader_uuid)
+        .fetch_one(&mut *tx)
+        .await?;
+
+        AuditService::record(&mut tx, NewAuditEvent {
+            actor_uuid: Some(uploader_uuid),
+            action: "evidence.upload".to_string(),
+            target_type: "evidence".to_string(),
+            target_id: Some(stored.id.to_string()),
+            details: json!({ "evidence": stored }),
+        })
+        .await?;
+
+        if let Some(target) = target {
+            Self::insert_link(&mut tx, uploader_uuid, stored.id, target).await?;
+        }
+
+        tx.commit().await?;
+
+        Ok(stored)
+    }
+
+    pub async fn link_evidence(&self, staff_uuid: Uuid, evidence_id: Uuid, target: EvidenceTarget) -> AppResult<()> {
+        let mut tx = self.pool.begin().await?;
+
+        let exists = sqlx::query_scalar::<_, bool>("SELECT EXISTS(SELECT 1 FROM evidence WHERE id = $1)")
+            .bind(evidence_id)
+            .fetch_one(&mut *tx)
+            .await?;
+        if !exists {
+            return Err(AppError::NotFound("evidence not found".to_string()));
+        }
+
+        Self::insert_link(&mut tx, staff_uuid, evidence_id, target).await?;
+        tx.commit().await?;
+
+        Ok(())
+    }
+
+    /// Attaches the evidence to the target, attaching it twice changes nothing.
+    async fn insert_link(conn: &mut PgConnection, staff_uuid: Uuid, evidence_id: Uuid, target: EvidenceTarget) -> AppResult<()> {
+        let (table, column) = target.table_and_column();
+
+        let exists = sqlx::query_scalar::<_, bool>(&format!("SELECT EXISTS(SELECT 1 FROM {} WHERE id = $1)", table))
+            .bind(target.id())
+            .fetch_one(&mut *conn)
+            .await?;
+        if !exists {
+            return Err(AppError::NotFound(format!("{} {} not found", column.trim_end_matches("_id"), target.id())));
+        }
+
+        let linked = sqlx::query(&format!(
+            "INSERT INTO evidence_links (evidence_id, {}, linked_by) VALUES ($1, $2, $3) ON CONFLICT DO NOTHING",
+            column
+        ))
+        .bind(evidence_id)
+        .bind(target.id())
+        .bind(staff_uuid)
+        .execute(&mut *conn)
+        .await?;
+
+        if linked.rows_affected() > 0 {
+            AuditService::record(conn, NewAuditEvent {
+                actor_uuid: Some(staff_uuid),
+                action: "evidence.link".to_string(),
+                target_type: "evidence".to_string(),
+                target_id: Some(evidence_id.to_string()),
+                details: json!({ column: target.id() }),
+            })
+            .await?;
+        }
+
+        Ok(())
+    }
+
+    /// Lists the evidence attached to the target in the order it was attached.
+    pub async fn list_evidence(&self, target: EvidenceTarget) -> AppResult<Vec<Evidence>> {
+        let (_, column) = target.table_and_column();
+
+        let evidence = sqlx::query_as::<_, Evidence>(&format!(
+            r#"
+            SELECT e.*
+            FROM evidence_links l
+            INNER JOIN evidence e ON e.id = l.evidence_id
+            WHERE l.{} = $1
+            ORDER BY l.linked_at, l.id
+            "#,
+            column
+        ))
+        .bind(target.id())
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(evidence)
+    }
+
+    /// Reads the contents back, failing when they no longer match the digest recorded at upload.
+    pub async fn read_evidence(&self, evidence_id: Uuid) -> AppResult<(Evidence, Vec<u8>)> {
+        let evidence = sqlx::query_as::<_, Evidence>("SELECT * FROM evidence WHERE id = $1")
+            .bind(evidence_id)
+            .fetch_optional(&self.pool)
+            .await?
+            .ok_or_else(|| AppError::NotFound("evidence not found".to_string()))?;
+
+        let data = self.store.get(&evidence.sha256).await?;
+        if format!("{:x}", Sha256::digest(&data)) != evidence.sha256 {
+            return Err(AppError::InternalError(format!(
+                "The stored contents of evidence {} no longer match their digest",
+                evidence.id
+            )));
+        }
+
+        Ok((evidence, data))
+    }
+}
@@ -0,0 +1,271 @@
+use crate::error::{AppError, AppResult};
+use crate::models::{Hold, HoldDisconnect, HoldSettings, IssueOutcome, NewAuditEvent, NewPunishment};
+use crate::services::{AuditService, PunishmentService};
+use serde_json::json;
+use sqlx::{PgConnection, PgPool};
+use std::sync::Arc;
+use time::OffsetDateTime;
+use uuid::Uuid;
+
+pub struct HoldService {
+    pool: PgPool,
+    punishment_service: Arc<PunishmentService>,
+}
+
+impl HoldService {
+    pub fn new(pool: PgPool, punishment_service: Arc<PunishmentService>) -> Self {
+        Self { pool, punishment_service }
+    }
+
+    pub async fn create_hold(&self, staff_uuid: Uuid, player_uuid: Uuid, reason: Option<String>) -> AppResult<Hold> {
+        if player_uuid == staff_uuid {
+            return Err(AppError::CustomValidationError("Staff members cannot hold themselves".to_string()));
+        }
+
+        let mut tx = self.pool.begin().await?;
+
+        // Locking the player keeps a concurrent hold from slipping past the open hold check
+        let player = sqlx::query_scalar::<_, Uuid>("SELECT uuid FROM players WHERE uuid = $1 FOR UPDATE")
+            .bind(player_uuid)
+            .fetch_optional(&mut *tx)
+            .await?;
+
+        if player.is_none() {
+            return Err(AppError::NotFound("player not found".to_string()));
+        }
+
+        PunishmentService::ensure_outranks(&mut tx, staff_uuid, player_uuid, "hold this player").await?;
+
+        if Self::find_open_hold(&mut tx, player_uuid).await?.is_some() {
+            return Err(AppError::CustomValidationError("The player is already held".to_string()));
+        }
+
+        let hold = sqlx::query_as::<_, Hold>(
+            "INSERT INTO player_holds (player_uuid, staff_uuid, reason) VALUES ($1, $2, $3) RETURNING *"
+        )
+        .bind(player_uuid)
+        .bind(staff_uuid)
+        .bind(&reason)
+        .fetch_one(&mut *tx)
+        .await?;
+
+        AuditService::record(&mut tx, NewAuditEvent {
+            actor_uuid: Some(staff_uuid),
+            action: "hold.create".to_string(),
+            target_type: "player".to_string(),
+            target_id: Some(player_uuid.to_string()),
+            details: json!({ "hold_id": hold.id, "reason": reason }),
+        })
+        .await?;
+
+        tx.commit().await?;
+
+        Ok(hold)
+    }
+
+    pub async fn release_hold(&self, staff_uuid: Uuid, player_uuid: Uuid) -> AppResult<Hold> {
+        let mut tx = self.pool.begin().await?;
+
+        let Some(open) = Self::find_open_hold(&mut tx, player_uuid).await? else {
+            return Err(AppError::NotFound("player is not held".to_string()));
+        };
+
+        let hold = Self::end_hold(&mut tx, open.id, Some(staff_uuid), "released").await?;
+
+        AuditService::record(&mut tx, NewAuditEvent {
+            actor_uuid: Some(staff_uuid),
+            action: "hold.release".to_string(),
+            target_type: "player".to_string(),
+            target_id: Some(player_uuid.to_string()),
+            details: json!({ "hold_id": hold.id, "held_by": hold.staff_uuid }),
+        })
+        .await?;
+
+        tx.commit().await?;
+
+        Ok(hold)
+    }
+
+    pub async fn get_open_hold(&self, player_uuid: Uuid) -> AppResult<Option<Hold>> {
+        let mut conn = self.pool.acquire().await?;
+        Self::find_open_hold(&mut conn, player_uuid).await
+    }
+
+    /// Ends the open hold of a player who left the network and issues the next punishment of the
+    /// configured category in the name of the staff member who held them. The hold is closed first,
+    /// so a punishment that cannot be issued is logged and never leaves the player held. Players
+    /// kicked or banned during the hold were disconnected by staff, their hold ends as punished.
+    pub async fn handle_disconnect(&self, player_uuid: Uuid) -> AppResult<Option<HoldDisconnect>> {
+        let mut tx = self.pool.begin().await?;
+
+        let Some(open) = Self::find_open_hold(&mut tx, player_uuid).await? else {
+            return Ok(None);
+        };
+
+        if let Some((punishment_id, issued_by)) = Self::find_disconnecting_punishment(&mut tx, &open).await? {
+            let hold = sqlx::query_as::<_, Hold>(
+                "UPDATE player_holds SET ended_at = NOW(), ended_by = $2, outcome = 'punished', punishment_id = $3 WHERE id = $1 RETURNING *"
+            )
+            .bind(open.id)
+            .bind(issued_by)
+            .bind(punishment_id)
+            .fetch_one(&mut *tx)
+            .await?;
+
+            AuditService::record(&mut tx, NewAuditEvent {
+                actor_uuid: None,
+                action: "hold.punished".to_string(),
+                target_type: "player".to_string(),
+                target_id: Some(player_uuid.to_string()),
+                details: json!({ "hold_id": hold.id, "held_by": hold.staff_uuid, "punishment_id": punishment_id }),
+            })
+            .await?;
+
+            tx.commit().await?;
+
+            return Ok(Some(HoldDisconnect { hold, approval: None }));
+        }
+
+        let settings = Self::load_hold_settings(&mut tx).await?;
+        let mut hold = Self::end_hold(&mut tx, open.id, None, "disconnected").await?;
+
+        AuditService::record(&mut tx, NewAuditEvent {
+            actor_uuid: None,
+            action: "hold.disconnect".to_string(),
+            target_type: "player".to_string(),
+            target_id: Some(player_uuid.to_string()),
+            details: json!({ "hold_id": hold.id, "held_by": hold.staff_uuid, "category_id": settings.disconnect_category_id }),
+        })
+        .await?;
+
+        tx.commit().await?;
+
+        let Some(category_id) = settings.disconnect_category_id else {
+            return Ok(Some(HoldDisconnect { hold, approval: None }));
+        };
+
+        let punishment = NewPunishment {
+            player_uuid,
+            category_id,
+            reason: None,
+            evidence: None,
+            note: Some(hold.disconnect_note(hold.ended_at.unwrap_or_else(OffsetDateTime::now_utc))),
+            scope: None,
+            server_group: None,
+            ip_range: None,
+        };
+
+        let mut approval = None;
+        match self.punishment_service.issue_punishment(hold.staff_uuid, punishment, true).await {
+            Ok(IssueOutcome::Issued(issued)) => {
+                hold = sqlx::query_as::<_, Hold>("UPDATE player_holds SET punishment_id = $2 WHERE id = $1 RETURNING *")
+                    .bind(hold.id)
+                    .bind(issued.punishment.id)
+                    .fetch_one(&self.pool)
+                    .await?;
+                approval = issued.automatic_ban_approval;
+            }
+            // Left to the approvers, the hold only links punishments that were issued
+            Ok(IssueOutcome::Queued(queued)) => approval = Some(*queued),
+            Err(e) => eprintln!("Could not punish {} for disconnecting while held: {}", player_uuid, e),
+        }
+
+        Ok(Some(HoldDisconnect { hold, approval }))
+    }
+
+    async fn find_open_hold(conn: &mut PgConnection, player_uuid: Uuid) -> AppResult<Option<Hold>> {
+        let hold = sqlx::query_as::<_, Hold>("SELECT * FROM player_holds WHERE player_uuid = $1 AND ended_at IS NULL FOR UPDATE")
+            .bind(player_uuid)
+            .fetch_optional(conn)
+            .await?;
+
+        Ok(hold)
+    }
+
+    /// Finds a kick or network-wide ban issued for the held player since the hold started, with its
+    /// issuer. Revoked bans and bans of a server group leave the player on the network.
+    async fn find_disconnecting_punishment(conn: &mut PgConnection, hold: &Hold) -> AppResult<Option<(Uuid, Uuid)>> {
+        let punishment = sqlx::query_as::<_, (Uuid, Uuid)>(
+            r#"
+            SELECT id, staff_uuid FROM punishments
+            WHERE player_uuid = $1
+              AND punishment_type IN ('kick', 'temp_ban', 'perm_ban')
+              AND scope IN ('global', 'proxy')
+              AND NOT revoked
+              AND issued_at >= $2
+            ORDER BY issued_at DESC
+            LIMIT 1
+            "#
+        )
+        .bind(hold.player_uuid)
+        .bind(hold.created_at)
+        .fetch_optional(conn)
+        .await?;
+
+        Ok(punishment)
+    }
+
+    async fn end_hold(conn: &mut PgConnection, id: Uuid, ended_by: Option<Uuid>, outcome: &str) -> AppResult<Hold> {
+        let hold = sqlx::query_as::<_, Hold>(
+            "UPDATE player_holds SET ended_at = NOW(), ended_by = $2, outcome = $3 WHERE id = $1 RETURNING *"
+        )
+        .bind(id)
+        .bind(ended_by)
+        .bind(outcome)
+        .fetch_one(conn)
+        .await?;
+
+        Ok(hold)
+    }
+
+    pub async fn get_hold_settings(&self) -> AppResult<HoldSettings> {
+        let mut conn = self.pool.acquire().await?;
+        Self::load_hold_settings(&mut conn).await
+    }
+
+    pub async fn update_hold_settings(&self, staff_uuid: Uuid, disconnect_category_id: Option<i32>) -> AppResult<HoldSettings> {
+        let mut tx = self.pool.begin().await?;
+
+        if let Some(category_id) = disconnect_category_id {
+            let active = sqlx::query_scalar::<_, bool>("SELECT active FROM punishment_categories WHERE id = $1")
+                .bind(category_id)
+                .fetch_optional(&mut *tx)
+                .await?;
+
+            if active != Some(true) {
+                return Err(AppError::NotFound(format!("Punishment category {} does not exist or is inactive", category_id)));
+            }
+        }
+
+        let before = Self::load_hold_settings(&mut tx).await?;
+
+        let after = sqlx::query_as::<_, HoldSettings>(
+            "UPDATE hold_settings SET disconnect_category_id = $1, updated_by = $2, updated_at = NOW() RETURNING *"
+        )
+        .bind(disconnect_category_id)
+        .bind(staff_uuid)
+        .fetch_one(&mut *tx)
+        .await?;
+
+        AuditService::record(&mut tx, NewAuditEvent {
+            actor_uuid: Some(staff_uuid),
+            action: "hold_settings.update".to_string(),
+            target_type: "hold_settings".to_string(),
+            target_id: None,
+            details: json!({ "before": before, "after": after }),
+        })
+        .await?;
+
+        tx.commit().await?;
+
+        Ok(after)
+    }
+
+    async fn load_hold_settings(conn: &mut PgConnection) -> AppResult<HoldSettings> {
+        let settings = sqlx::query_as::<_, HoldSettings>("SELECT * FROM hold_settings")
+            .fetch_one(conn)
+            .await?;
+
+        Ok(settings)
+    }
+}
@@ -1,5 +1,5 @@
 use crate::error::AppResult;
-use crate::models::PunishmentMessage;
+use crate::models::{notifies_in_chat, PunishmentMessage};
 use sqlx::PgPool;
 use std::collections::HashMap;
 use time::{format_description, OffsetDateTime};
@@ -31,6 +31,19 @@ impl MessageService {
         self.get_punishment_message("mute", reason, None, expires_at, None, None, None, None).await
     }
 
+    /// The chat message telling the player about the punishment, None for types the player is not
+    /// told about in chat such as shadow mutes.
+    pub async fn get_chat_message(&self, punishment_type: &str, reason: &str, expires_at: Option<OffsetDateTime>) -> AppResult<Option<String>> {
+        if !notifies_in_chat(punishment_type) {
+            return Ok(None);
+        }
+
+        match punishment_type {
+            "warn" => self.get_warn_message(reason, None, None).await.map(Some),
+            _ => self.get_mute_message(reason, expires_at).await.map(Some),
+        }
+    }
+
     pub async fn get_ban_message(&self, reason: &str, issued_at: OffsetDateTime, expires_at: Option<OffsetDateTime>) -> AppResult<String> {
         self.get_punishment_message("ban", reason, Some(issued_at), expires_at, None, None, None, None).await
     }
@@ -43,6 +56,7 @@ impl MessageService {
         self.get_punishment_message("warn", reason, None, None, offense_number, None, None, category_name).await
     }
 
+    #[allow(clippy::too_many_arguments)]
     async fn get_punishment_message(
         &self,
         message_type: &str,
@@ -279,10 +293,10 @@ mod tests {
         // by exercising format_time_remaining and string assembly inline.
         let reason = "You cheated";
         let issued_at = OffsetDateTime::now_utc();
-        let expires_at = Some(issued_at + time::Duration::days(30));
+        let expires_at = issued_at + time::Duration::days(30);
 
         // Verify the helper formats time correctly for the ban message context.
-        let duration = expires_at.unwrap() - issued_at;
+        let duration = expires_at - issued_at;
         let formatted = format_time_remaining(duration);
         assert!(formatted.contains('d'), "Expected days in formatted string: {}", formatted);
         assert!(formatted.contains("30d"), "Expected 30d: {}", formatted);
@@ -311,9 +325,9 @@ mod tests {
 
     #[test]
     fn expires_text_shows_time_for_future() {
-        let expires_at = Some(OffsetDateTime::now_utc() + time::Duration::hours(2));
+        let expires_at = OffsetDateTime::now_utc() + time::Duration::hours(2);
         let now = OffsetDateTime::now_utc();
-        let duration = expires_at.unwrap() - now;
+        let duration = expires_at - now;
         assert!(duration.is_positive());
         let formatted = format_time_remaining(duration);
         assert!(formatted.contains('h') || formatted.contains('m'));
@@ -321,9 +335,9 @@ mod tests {
 
     #[test]
     fn expires_text_expired_for_past() {
-        let expires_at = Some(OffsetDateTime::now_utc() - time::Duration::hours(1));
+        let expires_at = OffsetDateTime::now_utc() - time::Duration::hours(1);
         let now = OffsetDateTime::now_utc();
-        let duration = expires_at.unwrap() - now;
+        let duration = expires_at - now;
         assert!(!duration.is_positive());
     }
 }
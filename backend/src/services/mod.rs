@@ -1,11 +1,25 @@
+mod alt_service;
+mod audit_service;
+mod chat_filter_service;
+mod chat_service;
+mod evidence_service;
+mod hold_service;
 mod player_service;
 mod report_service;
 mod punishment_service;
 mod message_service;
 mod broadcast_service;
+mod policy_service;
 
+pub use alt_service::{AltService, BanEvasionAction};
+pub use audit_service::AuditService;
 pub use broadcast_service::BroadcastService;
+pub use chat_filter_service::ChatFilterService;
+pub use chat_service::ChatService;
+pub use evidence_service::EvidenceService;
+pub use hold_service::HoldService;
 pub use message_service::MessageService;
 pub use player_service::PlayerService;
+pub use policy_service::PolicyService;
 pub use punishment_service::PunishmentService;
 pub use report_service::ReportService;
\ No newline at end of file
@@ -1,10 +1,14 @@
 use crate::error::{AppError, AppResult};
 use crate::models::player::*;
+use crate::models::{permissions, NewAuditEvent, StaffRole};
+use crate::services::AuditService;
 use chrono::{Duration, Utc};
 use dotenvy::var;
 use jsonwebtoken::{decode, encode, Algorithm, DecodingKey, EncodingKey, Header, Validation};
 use serde::{Deserialize, Serialize};
+use serde_json::json;
 use sqlx::PgPool;
+use std::net::IpAddr;
 use tonic::{Request, Status};
 use uuid::Uuid;
 
@@ -61,7 +65,6 @@ impl PlayerService {
         Ok(claims)
     }
 
-    #[allow(dead_code)]
     pub async fn verify_request<T>(&self, request: &Request<T>) -> Result<Claims, Status> {
         let claims = self.verify_request_allow_password_change(request).await;
 
@@ -77,6 +80,41 @@ impl PlayerService {
         }
     }
 
+    /// Verifies a call made by a Minecraft server or the proxy. They authenticate with the shared
+    /// `SERVER_TOKEN` in the `x-server-token` header instead of a staff login.
+    pub fn verify_server<T>(&self, request: &Request<T>) -> Result<(), Status> {
+        use sha2::{Digest, Sha256};
+
+        let Some(expected) = var("SERVER_TOKEN").ok().filter(|token| !token.is_empty()) else {
+            return Err(Status::unauthenticated("Server calls are disabled, SERVER_TOKEN is not set"));
+        };
+
+        let token = request
+            .metadata()
+            .get("x-server-token")
+            .and_then(|token| token.to_str().ok())
+            .ok_or_else(|| Status::unauthenticated("Missing server token"))?;
+
+        // Comparing digests keeps the time taken independent of how much of the token matches
+        if Sha256::digest(token.as_bytes()) != Sha256::digest(expected.as_bytes()) {
+            return Err(Status::unauthenticated("Invalid server token"));
+        }
+
+        Ok(())
+    }
+
+    /// Like `verify_request`, but also requires a staff member whose role grants `permission`.
+    pub async fn verify_permission<T>(&self, request: &Request<T>, permission: &str) -> Result<Claims, Status> {
+        let claims = self.verify_request(request).await?;
+
+        let role = self.get_staff_role(claims.sub).await?;
+        if !role.is_some_and(|role| role.has_permission(permission)) {
+            return Err(Status::permission_denied(format!("Missing permission {}", permission)));
+        }
+
+        Ok(claims)
+    }
+
     fn generate_jwt_token(&self, player: &Player, token_type: TokenType, duration_hours: i64) -> AppResult<String> {
         let now = Utc::now();
         let exp = now + Duration::hours(duration_hours);
@@ -91,16 +129,16 @@ impl PlayerService {
         };
 
         let header = Header::new(Algorithm::HS256);
-        let encoding_key = EncodingKey::from_secret(&var("JWT_SECRET").expect("JWT_SECRET is not set").as_ref());
+        let encoding_key = EncodingKey::from_secret(var("JWT_SECRET").expect("JWT_SECRET is not set").as_ref());
 
         match encode(&header, &claims, &encoding_key) {
             Ok(token) => Ok(token),
-            Err(e) => Err(AppError::InternalError(format!("Failed to generate JWT token: {}", e)).into()),
+            Err(e) => Err(AppError::InternalError(format!("Failed to generate JWT token: {}", e))),
         }
     }
 
     pub async fn validate_token(&self, token: &str) -> AppResult<Claims> {
-        let decoding_key = DecodingKey::from_secret(&var("JWT_SECRET").expect("JWT_SECRET is not set").as_ref());
+        let decoding_key = DecodingKey::from_secret(var("JWT_SECRET").expect("JWT_SECRET is not set").as_ref());
         let validation = Validation::new(Algorithm::HS256);
 
         match decode::<Claims>(token, &decoding_key, &validation) {
@@ -108,17 +146,16 @@ impl PlayerService {
                 let claims = token_data.claims;
 
                 let player_uuid = claims.sub;
-                if let Some(player) = self.get_player_by_uuid(player_uuid).await? {
-                    if let Some(invalidated_before) = player.tokens_invalidated_before {
-                        if claims.iat < invalidated_before.timestamp() {
-                            return Err(AppError::Unauthorized("Token has been invalidated".to_string()).into());
-                        }
-                    }
+                if let Some(player) = self.get_player_by_uuid(player_uuid).await?
+                    && let Some(invalidated_before) = player.tokens_invalidated_before
+                    && claims.iat < invalidated_before.timestamp()
+                {
+                    return Err(AppError::Unauthorized("Token has been invalidated".to_string()));
                 }
 
                 Ok(claims)
             },
-            Err(_) => Err(AppError::Unauthorized("Invalid or expired token".to_string()).into()),
+            Err(_) => Err(AppError::Unauthorized("Invalid or expired token".to_string())),
         }
     }
 
@@ -133,6 +170,228 @@ impl PlayerService {
         Ok(player)
     }
 
+    /// Finds players whose current or a past username starts with `prefix`, one page after `cursor`.
+    pub async fn search_players(&self, prefix: &str, cursor: Option<&PlayerCursor>, limit: i64) -> AppResult<Vec<PlayerSearchResult>> {
+        let pattern = username_prefix_pattern(prefix)?;
+
+        let players = sqlx::query_as::<_, PlayerSearchResult>(
+            r#"
+            WITH matches AS (
+                SELECT uuid AS player_uuid, NULL::VARCHAR(16) AS matched_name
+                FROM players
+                WHERE LOWER(username) LIKE $1
+                UNION ALL
+                SELECT player_uuid, username
+                FROM player_name_history
+                WHERE LOWER(username) LIKE $1
+            )
+            SELECT * FROM (
+                SELECT DISTINCT ON (LOWER(pl.username), pl.uuid)
+                    pl.uuid,
+                    pl.username,
+                    NULLIF(m.matched_name, pl.username) AS matched_name,
+                    pl.staff
+                FROM matches m
+                INNER JOIN players pl ON pl.uuid = m.player_uuid
+                WHERE $2::TEXT IS NULL OR (LOWER(pl.username), pl.uuid) > ($2, $3)
+                -- A match on the current username wins over past names
+                ORDER BY LOWER(pl.username), pl.uuid, NULLIF(m.matched_name, pl.username) NULLS FIRST
+            ) results
+            ORDER BY LOWER(results.username), results.uuid
+            LIMIT $4
+            "#,
+        )
+            .bind(pattern)
+            .bind(cursor.map(|cursor| cursor.username.as_str()))
+            .bind(cursor.map(|cursor| cursor.uuid))
+            .bind(limit)
+            .fetch_all(&self.pool)
+            .await?;
+
+        Ok(players)
+    }
+
+    /// Returns the role of the player, None unless the player is staff with a role assigned.
+    pub async fn get_staff_role(&self, player_uuid: Uuid) -> AppResult<Option<StaffRole>> {
+        let role = sqlx::query_as::<_, StaffRole>(
+            r#"
+            SELECT r.*
+            FROM players pl
+            INNER JOIN staff_roles r ON r.id = pl.role_id
+            WHERE pl.uuid = $1 AND pl.staff = true
+            "#,
+        )
+            .bind(player_uuid)
+            .fetch_optional(&self.pool)
+            .await?;
+
+        Ok(role)
+    }
+
+    pub async fn get_staff_uuids(&self) -> AppResult<Vec<Uuid>> {
+        let staff = sqlx::query_scalar::<_, Uuid>(
+            "SELECT uuid FROM players WHERE staff = true"
+        )
+            .fetch_all(&self.pool)
+            .await?;
+
+        Ok(staff)
+    }
+
+    /// Returns the staff members whose role grants the permission.
+    pub async fn get_staff_uuids_with_permission(&self, permission: &str) -> AppResult<Vec<Uuid>> {
+        let staff = sqlx::query_scalar::<_, Uuid>(
+            r#"
+            SELECT pl.uuid
+            FROM players pl
+            INNER JOIN staff_roles r ON r.id = pl.role_id
+            WHERE pl.staff = true AND ($1 = ANY(r.permissions) OR $2 = ANY(r.permissions))
+            "#,
+        )
+            .bind(permission)
+            .bind(permissions::ALL)
+            .fetch_all(&self.pool)
+            .await?;
+
+        Ok(staff)
+    }
+
+    /// Creates the player on first login and keeps the current username and name history up to date.
+    pub async fn record_login(&self, player_uuid: Uuid, username: &str) -> AppResult<()> {
+        validate_username(username)?;
+
+        let mut tx = self.pool.begin().await?;
+
+        sqlx::query(
+            r#"
+            INSERT INTO players (uuid, username)
+            VALUES ($1, $2)
+            ON CONFLICT (uuid) DO UPDATE
+            SET username = EXCLUDED.username
+            WHERE players.username IS DISTINCT FROM EXCLUDED.username
+            "#,
+        )
+            .bind(player_uuid)
+            .bind(username)
+            .execute(&mut *tx)
+            .await?;
+
+        sqlx::query(
+            r#"
+            INSERT INTO player_name_history (player_uuid, username)
+            VALUES ($1, $2)
+            ON CONFLICT (player_uuid, username) DO UPDATE
+            SET last_seen = NOW()
+            "#,
+        )
+            .bind(player_uuid)
+            .bind(username)
+            .execute(&mut *tx)
+            .await?;
+
+        tx.commit().await?;
+
+        Ok(())
+    }
+
+    /// Opens a network session, closing any session the player still has open from a lost stream.
+    pub async fn start_session(&self, player_uuid: Uuid, stream_id: Uuid) -> AppResult<()> {
+        let mut tx = self.pool.begin().await?;
+
+        sqlx::query("UPDATE player_sessions SET ended_at = NOW() WHERE player_uuid = $1 AND ended_at IS NULL")
+            .bind(player_uuid)
+            .execute(&mut *tx)
+            .await?;
+
+        // Players that never passed a login check with a username have no players row to attach to
+        sqlx::query(
+            r#"
+            INSERT INTO player_sessions (player_uuid, stream_id)
+            SELECT uuid, $2 FROM players WHERE uuid = $1
+            "#,
+        )
+            .bind(player_uuid)
+            .bind(stream_id)
+            .execute(&mut *tx)
+            .await?;
+
+        tx.commit().await?;
+
+        Ok(())
+    }
+
+    pub async fn end_session(&self, player_uuid: Uuid) -> AppResult<()> {
+        sqlx::query("UPDATE player_sessions SET ended_at = NOW() WHERE player_uuid = $1 AND ended_at IS NULL")
+            .bind(player_uuid)
+            .execute(&self.pool)
+            .await?;
+
+        Ok(())
+    }
+
+    /// Closes every session reported by a live stream that has ended.
+    pub async fn end_stream_sessions(&self, stream_id: Uuid) -> AppResult<()> {
+        sqlx::query("UPDATE player_sessions SET ended_at = NOW() WHERE stream_id = $1 AND ended_at IS NULL")
+            .bind(stream_id)
+            .execute(&self.pool)
+            .await?;
+
+        Ok(())
+    }
+
+    pub async fn get_name_history(&self, player_uuid: Uuid) -> AppResult<Vec<PlayerNameHistory>> {
+        let history = sqlx::query_as::<_, PlayerNameHistory>(
+            r#"
+            SELECT username, first_seen, last_seen
+            FROM player_name_history
+            WHERE player_uuid = $1
+            ORDER BY last_seen DESC
+            "#,
+        )
+            .bind(player_uuid)
+            .fetch_all(&self.pool)
+            .await?;
+
+        Ok(history)
+    }
+
+    pub async fn get_activity(&self, player_uuid: Uuid) -> AppResult<PlayerActivity> {
+        let activity = sqlx::query_as::<_, PlayerActivity>(
+            r#"
+            SELECT
+                MIN(started_at) AS first_seen,
+                CASE WHEN BOOL_OR(ended_at IS NULL) THEN NOW() ELSE MAX(ended_at) END AS last_seen,
+                COALESCE(EXTRACT(EPOCH FROM SUM(COALESCE(ended_at, NOW()) - started_at))::BIGINT, 0) AS total_playtime_seconds,
+                COALESCE(BOOL_OR(ended_at IS NULL), FALSE) AS online
+            FROM player_sessions
+            WHERE player_uuid = $1
+            "#,
+        )
+            .bind(player_uuid)
+            .fetch_one(&self.pool)
+            .await?;
+
+        Ok(activity)
+    }
+
+    pub async fn record_player_address(&self, player_uuid: Uuid, ip_address: IpAddr) -> AppResult<()> {
+        sqlx::query(
+            r#"
+            INSERT INTO player_addresses (player_uuid, ip_address)
+            VALUES ($1, $2)
+            ON CONFLICT (player_uuid, ip_address) DO UPDATE
+            SET last_seen = NOW(),
+                login_count = player_addresses.login_count + 1
+            "#,
+        )
+            .bind(player_uuid)
+            .bind(ip_address)
+            .execute(&self.pool)
+            .await?;
+
+        Ok(())
+    }
+
     pub async fn change_password(&self, player_uuid: Uuid, request: PasswordChangeRequest) -> AppResult<EnhancedLoginResponse> {
         use sha2::{Digest, Sha256};
 
@@ -140,11 +399,12 @@ impl PlayerService {
 
         if let Some(player) = player {
             if request.new_password.len() < 8 {
-                return Err(AppError::CustomValidationError("Password must be at least 8 characters long".to_string()).into());
+                return Err(AppError::CustomValidationError("Password must be at least 8 characters long".to_string()));
             }
 
             let new_password_hash = format!("{:x}", Sha256::digest(request.new_password.as_bytes()));
 
+            let mut tx = self.pool.begin().await?;
             let result = sqlx::query(
                 r#"
                 UPDATE players
@@ -156,10 +416,20 @@ impl PlayerService {
             )
                 .bind(&new_password_hash)
                 .bind(player_uuid)
-                .execute(&self.pool)
+                .execute(&mut *tx)
                 .await?;
 
             if result.rows_affected() > 0 {
+                AuditService::record(&mut tx, NewAuditEvent {
+                    actor_uuid: Some(player_uuid),
+                    action: "auth.password_change".to_string(),
+                    target_type: "player".to_string(),
+                    target_id: Some(player_uuid.to_string()),
+                    details: json!({ "required": player.password_change_required }),
+                })
+                .await?;
+                tx.commit().await?;
+
                 let updated_player = Player {
                     uuid: player.uuid,
                     username: player.username,
@@ -167,6 +437,7 @@ impl PlayerService {
                     password_change_required: false,
                     tokens_invalidated_before: player.tokens_invalidated_before,
                     staff: player.staff,
+                    role_id: player.role_id,
                     created_at: player.created_at,
                     updated_at: Utc::now(),
                 };
@@ -179,17 +450,17 @@ impl PlayerService {
                     refresh_token: Some(refresh_token),
                 })
             } else {
-                Err(AppError::InternalError("Failed to update password".to_string()).into())
+                Err(AppError::InternalError("Failed to update password".to_string()))
             }
         } else {
-            Err(AppError::NotFound("player not found".to_string()).into())
+            Err(AppError::NotFound("player not found".to_string()))
         }
     }
 
     pub async fn refresh_user(&self, request: RefreshRequest) -> AppResult<RefreshResponse> {
         let claims = self.validate_token(&request.refresh_token).await?;
         if claims.token_type != TokenType::Refresh {
-            return Err(AppError::Unauthorized("Invalid token type for refresh".to_string()).into());
+            return Err(AppError::Unauthorized("Invalid token type for refresh".to_string()));
         }
 
         let player_uuid = claims.sub;
@@ -199,7 +470,7 @@ impl PlayerService {
             if player.password_change_required {
                 return Err(AppError::CustomValidationError(
                     "Password change required. Please change your password to continue.".to_string()
-                ).into());
+                ));
             }
 
             let access_token = self.generate_jwt_token(&player, TokenType::Access, 24)?; // 24 hours validity
@@ -210,10 +481,39 @@ impl PlayerService {
                 refresh_token,
             })
         } else {
-            Err(AppError::NotFound("player not found".to_string()).into())
+            Err(AppError::NotFound("player not found".to_string()))
         }
     }
 
+    /// Touches the player and records the login in the audit log.
+    async fn record_password_login(&self, player: &Player) -> AppResult<()> {
+        let mut tx = self.pool.begin().await?;
+
+        sqlx::query(
+            r#"
+            UPDATE players
+            SET updated_at = NOW()
+            WHERE uuid = $1
+            "#,
+        )
+            .bind(player.uuid)
+            .execute(&mut *tx)
+            .await?;
+
+        AuditService::record(&mut tx, NewAuditEvent {
+            actor_uuid: Some(player.uuid),
+            action: "auth.login".to_string(),
+            target_type: "player".to_string(),
+            target_id: Some(player.uuid.to_string()),
+            details: json!({ "password_change_required": player.password_change_required }),
+        })
+        .await?;
+
+        tx.commit().await?;
+
+        Ok(())
+    }
+
     pub async fn login_user(&self, request: LoginRequest) -> AppResult<EnhancedLoginResponse> {
         use sha2::{Digest, Sha256};
 
@@ -233,16 +533,7 @@ impl PlayerService {
             if player.password_change_required {
                 let access_token = self.generate_jwt_token(&player, TokenType::PasswordChangeOnly, 1)?; // 1 hour validity
 
-                sqlx::query(
-                    r#"
-                    UPDATE players
-                    SET updated_at = NOW()
-                    WHERE uuid = $1
-                    "#,
-                )
-                    .bind(player.uuid)
-                    .execute(&self.pool)
-                    .await?;
+                self.record_password_login(&player).await?;
 
                 return Ok(EnhancedLoginResponse {
                     access_token,
@@ -253,23 +544,14 @@ impl PlayerService {
             let access_token = self.generate_jwt_token(&player, TokenType::Access, 24)?; // 24 hours validity
             let refresh_token = self.generate_jwt_token(&player, TokenType::Refresh, 168)?; // 7 days validity for refresh
 
-            sqlx::query(
-                r#"
-                UPDATE players
-                SET updated_at = NOW()
-                WHERE uuid = $1
-                "#,
-            )
-                .bind(player.uuid)
-                .execute(&self.pool)
-                .await?;
+            self.record_password_login(&player).await?;
 
             Ok(EnhancedLoginResponse {
                 access_token,
                 refresh_token: Some(refresh_token),
             })
         } else {
-            Err(AppError::WrongCredentials("Invalid username or password".to_string()).into())
+            Err(AppError::WrongCredentials("Invalid username or password".to_string()))
         }
     }
 }
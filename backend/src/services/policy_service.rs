@@ -0,0 +1,641 @@
+use crate::error::{AppError, AppResult};
+use crate::models::{
+    validate_category_name, validate_color_hex, validate_escalation_ladder, validate_punishment_type, validate_role_limits,
+    validate_severity_thresholds, ApprovalSettings, CategoryUpdate, EscalationStep, NewAuditEvent, NewCategory, NewSeverityThreshold, PolicyVersion, PunishmentCategory,
+    PunishmentTemplate, RoleLimits, SeveritySettings, SeverityThreshold, StaffRole,
+};
+use crate::services::{AuditService, PunishmentService};
+use serde_json::json;
+use sqlx::{PgConnection, PgPool};
+use std::collections::HashMap;
+use time::OffsetDateTime;
+use uuid::Uuid;
+
+pub struct PolicyService {
+    pool: PgPool,
+}
+
+impl PolicyService {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+
+    /// Lists the categories with the ladder of their active policy version.
+    pub async fn list_categories(&self, include_inactive: bool) -> AppResult<Vec<(PunishmentCategory, Vec<PunishmentTemplate>)>> {
+        let categories = sqlx::query_as::<_, PunishmentCategory>(
+            r#"
+            SELECT
+                pc.*,
+                (SELECT version_number FROM policy_versions WHERE id = active_policy_version_id(pc.id)) AS active_version,
+                (SELECT version_number FROM policy_versions WHERE category_id = pc.id AND status = 'draft') AS draft_version
+            FROM punishment_categories pc
+            WHERE pc.active = true OR $1
+            ORDER BY pc.position, pc.id
+            "#,
+        )
+        .bind(include_inactive)
+        .fetch_all(&self.pool)
+        .await?;
+
+        let templates = sqlx::query_as::<_, PunishmentTemplate>(
+            r#"
+            SELECT * FROM punishment_templates
+            WHERE policy_version_id = active_policy_version_id(category_id)
+            ORDER BY category_id, offense_number
+            "#,
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        let mut ladders: HashMap<i32, Vec<PunishmentTemplate>> = HashMap::new();
+        for template in templates {
+            ladders.entry(template.category_id).or_default().push(template);
+        }
+
+        Ok(categories
+            .into_iter()
+            .map(|category| {
+                let ladder = ladders.remove(&category.id).unwrap_or_default();
+                (category, ladder)
+            })
+            .collect())
+    }
+
+    pub async fn get_category(&self, category_id: i32) -> AppResult<(PunishmentCategory, Vec<PunishmentTemplate>)> {
+        self.list_categories(true)
+            .await?
+            .into_iter()
+            .find(|(category, _)| category.id == category_id)
+            .ok_or_else(|| AppError::NotFound(format!("Punishment category {} does not exist", category_id)))
+    }
+
+    pub async fn create_category(&self, staff_uuid: Uuid, category: NewCategory) -> AppResult<PunishmentCategory> {
+        let name = category.name.trim();
+        validate_category_name(name)?;
+        if let Some(color_hex) = &category.color_hex {
+            validate_color_hex(color_hex)?;
+        }
+
+        let mut tx = self.pool.begin().await?;
+
+        Self::ensure_name_available(&mut tx, name, None).await?;
+
+        // New categories go to the end of the menu
+        let created = sqlx::query_as::<_, PunishmentCategory>(
+            r#"
+            INSERT INTO punishment_categories (name, description, color_hex, position)
+            VALUES ($1, $2, COALESCE($3, '#FF6B6B'), (SELECT COALESCE(MAX(position), 0) + 1 FROM punishment_categories))
+            RETURNING *
+            "#,
+        )
+        .bind(name)
+        .bind(&category.description)
+        .bind(&category.color_hex)
+        .fetch_one(&mut *tx)
+        .await?;
+
+        AuditService::record(&mut tx, NewAuditEvent {
+            actor_uuid: Some(staff_uuid),
+            action: "category.create".to_string(),
+            target_type: "category".to_string(),
+            target_id: Some(created.id.to_string()),
+            details: json!({ "after": created }),
+        })
+        .await?;
+
+        tx.commit().await?;
+
+        Ok(created)
+    }
+
+    pub async fn update_category(&self, staff_uuid: Uuid, category_id: i32, update: CategoryUpdate) -> AppResult<PunishmentCategory> {
+        let name = update.name.as_deref().map(str::trim);
+        if let Some(name) = name {
+            validate_category_name(name)?;
+        }
+        if let Some(color_hex) = &update.color_hex {
+            validate_color_hex(color_hex)?;
+        }
+        let decay = update.decay.map(|decay| (decay.mode(), decay.days()));
+
+        let mut tx = self.pool.begin().await?;
+
+        let before = Self::find_category(&mut tx, category_id, true).await?;
+        if let Some(name) = name {
+            Self::ensure_name_available(&mut tx, name, Some(category_id)).await?;
+        }
+
+        let after = sqlx::query_as::<_, PunishmentCategory>(
+            r#"
+            UPDATE punishment_categories
+            SET name = COALESCE($2, name),
+                description = COALESCE($3, description),
+                color_hex = COALESCE($4, color_hex),
+                active = COALESCE($5, active),
+                decay_mode = COALESCE($6, decay_mode),
+                decay_days = CASE WHEN $6 IS NULL THEN decay_days ELSE $7 END,
+                requires_approval = COALESCE($8, requires_approval),
+                updated_at = NOW()
+            WHERE id = $1
+            RETURNING *
+            "#,
+        )
+        .bind(category_id)
+        .bind(name)
+        .bind(&update.description)
+        .bind(&update.color_hex)
+        .bind(update.active)
+        .bind(decay.map(|(mode, _)| mode))
+        .bind(decay.and_then(|(_, days)| days))
+        .bind(update.requires_approval)
+        .fetch_one(&mut *tx)
+        .await?;
+
+        AuditService::record(&mut tx, NewAuditEvent {
+            actor_uuid: Some(staff_uuid),
+            action: "category.update".to_string(),
+            target_type: "category".to_string(),
+            target_id: Some(category_id.to_string()),
+            details: json!({ "before": before, "after": after }),
+        })
+        .await?;
+
+        tx.commit().await?;
+
+        Ok(after)
+    }
+
+    /// Moves the categories into the given order, which has to list every category exactly once.
+    pub async fn reorder_categories(&self, staff_uuid: Uuid, category_ids: &[i32]) -> AppResult<()> {
+        let mut tx = self.pool.begin().await?;
+
+        let mut existing = sqlx::query_scalar::<_, i32>("SELECT id FROM punishment_categories ORDER BY id FOR UPDATE")
+            .fetch_all(&mut *tx)
+            .await?;
+        let mut requested = category_ids.to_vec();
+        requested.sort_unstable();
+
+        if requested != existing {
+            existing.retain(|id| !category_ids.contains(id));
+            return Err(AppError::CustomValidationError(if existing.is_empty() {
+                "The new order must list every category exactly once".to_string()
+            } else {
+                format!("The new order is missing categories {:?}", existing)
+            }));
+        }
+
+        sqlx::query(
+            r#"
+            UPDATE punishment_categories pc
+            SET position = ordered.position, updated_at = NOW()
+            FROM UNNEST($1::INTEGER[]) WITH ORDINALITY AS ordered(id, position)
+            WHERE pc.id = ordered.id AND pc.position <> ordered.position
+            "#,
+        )
+        .bind(category_ids)
+        .execute(&mut *tx)
+        .await?;
+
+        AuditService::record(&mut tx, NewAuditEvent {
+            actor_uuid: Some(staff_uuid),
+            action: "category.reorder".to_string(),
+            target_type: "category".to_string(),
+            target_id: None,
+            details: json!({ "order": category_ids }),
+        })
+        .await?;
+
+        tx.commit().await?;
+
+        Ok(())
+    }
+
+    /// Saves the ladder as the draft version of the category, creating the draft if there is none.
+    pub async fn set_escalation_ladder(
+        &self,
+        staff_uuid: Uuid,
+        category_id: i32,
+        steps: &[EscalationStep],
+    ) -> AppResult<(PolicyVersion, Vec<PunishmentTemplate>)> {
+        validate_escalation_ladder(steps)?;
+
+        let mut tx = self.pool.begin().await?;
+
+        // Locking the category serialises concurrent ladder edits
+        Self::find_category(&mut tx, category_id, true).await?;
+
+        let draft = sqlx::query_as::<_, PolicyVersion>(
+            "SELECT * FROM policy_versions WHERE category_id = $1 AND status = 'draft'"
+        )
+        .bind(category_id)
+        .fetch_optional(&mut *tx)
+        .await?;
+
+        let (draft, before) = match draft {
+            Some(draft) => {
+                let before = Self::get_ladder(&mut tx, draft.id).await?;
+                (draft, before)
+            }
+            None => {
+                let draft = sqlx::query_as::<_, PolicyVersion>(
+                    r#"
+                    INSERT INTO policy_versions (category_id, version_number, created_by)
+                    VALUES ($1, (SELECT COALESCE(MAX(version_number), 0) + 1 FROM policy_versions WHERE category_id = $1), $2)
+                    RETURNING *
+                    "#,
+                )
+                .bind(category_id)
+                .bind(staff_uuid)
+                .fetch_one(&mut *tx)
+                .await?;
+                (draft, Vec::new())
+            }
+        };
+
+        sqlx::query("DELETE FROM punishment_templates WHERE policy_version_id = $1")
+            .bind(draft.id)
+            .execute(&mut *tx)
+            .await?;
+
+        let mut after = Vec::with_capacity(steps.len());
+        for (index, step) in steps.iter().enumerate() {
+            let template = sqlx::query_as::<_, PunishmentTemplate>(
+                r#"
+                INSERT INTO punishment_templates
+                    (category_id, policy_version_id, offense_number, punishment_type, duration_minutes, reason_template, scope, server_group, points, requires_acknowledgement)
+                VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10)
+                RETURNING *
+                "#,
+            )
+            .bind(category_id)
+            .bind(draft.id)
+            .bind(index as i32 + 1)
+            .bind(&step.punishment_type)
+            .bind(step.duration_minutes)
+            .bind(step.reason_template.trim())
+            .bind(&step.scope)
+            .bind(&step.server_group)
+            .bind(step.points)
+            .bind(step.requires_acknowledgement)
+            .fetch_one(&mut *tx)
+            .await?;
+
+            after.push(template);
+        }
+
+        AuditService::record(&mut tx, NewAuditEvent {
+            actor_uuid: Some(staff_uuid),
+            action: "policy.draft".to_string(),
+            target_type: "policy_version".to_string(),
+            target_id: Some(draft.id.to_string()),
+            details: json!({ "category_id": category_id, "before": before, "after": after }),
+        })
+        .await?;
+
+        tx.commit().await?;
+
+        Ok((draft, after))
+    }
+
+    /// Publishes the draft of the category. From `activates_at` on, newly issued punishments use its ladder.
+    pub async fn publish_policy_version(
+        &self,
+        staff_uuid: Uuid,
+        category_id: i32,
+        activates_at: Option<OffsetDateTime>,
+    ) -> AppResult<(PolicyVersion, Vec<PunishmentTemplate>)> {
+        // Backdating would make history claim a ladder applied before anyone could use it
+        if activates_at.is_some_and(|activates_at| activates_at < OffsetDateTime::now_utc()) {
+            return Err(AppError::CustomValidationError(
+                "A policy version cannot be activated in the past".to_string(),
+            ));
+        }
+
+        let mut tx = self.pool.begin().await?;
+
+        let version = sqlx::query_as::<_, PolicyVersion>(
+            r#"
+            UPDATE policy_versions
+            SET status = 'published',
+                published_by = $2,
+                published_at = NOW(),
+                activates_at = COALESCE($3, NOW())
+            WHERE category_id = $1 AND status = 'draft'
+            RETURNING *
+            "#,
+        )
+        .bind(category_id)
+        .bind(staff_uuid)
+        .bind(activates_at)
+        .fetch_optional(&mut *tx)
+        .await?
+        .ok_or_else(|| AppError::NotFound(format!("Punishment category {} has no draft to publish", category_id)))?;
+
+        let ladder = Self::get_ladder(&mut tx, version.id).await?;
+
+        AuditService::record(&mut tx, NewAuditEvent {
+            actor_uuid: Some(staff_uuid),
+            action: "policy.publish".to_string(),
+            target_type: "policy_version".to_string(),
+            target_id: Some(version.id.to_string()),
+            details: json!({ "category_id": category_id, "version": version }),
+        })
+        .await?;
+
+        tx.commit().await?;
+
+        Ok((version, ladder))
+    }
+
+    pub async fn list_policy_versions(&self, category_id: i32) -> AppResult<Vec<(PolicyVersion, Vec<PunishmentTemplate>)>> {
+        let mut conn = self.pool.acquire().await?;
+        Self::find_category(&mut conn, category_id, false).await?;
+
+        let versions = sqlx::query_as::<_, PolicyVersion>(
+            "SELECT * FROM policy_versions WHERE category_id = $1 ORDER BY version_number DESC"
+        )
+        .bind(category_id)
+        .fetch_all(&mut *conn)
+        .await?;
+
+        let mut result = Vec::with_capacity(versions.len());
+        for version in versions {
+            let ladder = Self::get_ladder(&mut conn, version.id).await?;
+            result.push((version, ladder));
+        }
+
+        Ok(result)
+    }
+
+    pub async fn get_policy_version(&self, version_id: i32) -> AppResult<(PolicyVersion, Vec<PunishmentTemplate>)> {
+        let mut conn = self.pool.acquire().await?;
+
+        let version = sqlx::query_as::<_, PolicyVersion>("SELECT * FROM policy_versions WHERE id = $1")
+            .bind(version_id)
+            .fetch_optional(&mut *conn)
+            .await?
+            .ok_or_else(|| AppError::NotFound(format!("Policy version {} does not exist", version_id)))?;
+        let ladder = Self::get_ladder(&mut conn, version.id).await?;
+
+        Ok((version, ladder))
+    }
+
+    pub async fn get_severity_settings(&self) -> AppResult<(SeveritySettings, Vec<SeverityThreshold>)> {
+        let mut conn = self.pool.acquire().await?;
+        Self::load_severity_settings(&mut conn).await
+    }
+
+    /// Replaces the severity configuration, including every threshold.
+    pub async fn update_severity_settings(
+        &self,
+        staff_uuid: Uuid,
+        enabled: bool,
+        decay_days: Option<i32>,
+        thresholds: Vec<NewSeverityThreshold>,
+    ) -> AppResult<(SeveritySettings, Vec<SeverityThreshold>)> {
+        if decay_days.is_some_and(|days| days <= 0) {
+            return Err(AppError::CustomValidationError(
+                "Severity points need to decay over at least one day".to_string(),
+            ));
+        }
+        validate_severity_thresholds(&thresholds)?;
+
+        let mut tx = self.pool.begin().await?;
+        let before = Self::load_severity_settings(&mut tx).await?;
+
+        sqlx::query("UPDATE severity_settings SET enabled = $1, decay_days = $2, updated_by = $3, updated_at = NOW()")
+            .bind(enabled)
+            .bind(decay_days)
+            .bind(staff_uuid)
+            .execute(&mut *tx)
+            .await?;
+
+        sqlx::query("DELETE FROM severity_thresholds")
+            .execute(&mut *tx)
+            .await?;
+
+        for threshold in &thresholds {
+            sqlx::query(
+                "INSERT INTO severity_thresholds (points, punishment_type, duration_minutes, reason) VALUES ($1, $2, $3, $4)"
+            )
+            .bind(threshold.points)
+            .bind(&threshold.punishment_type)
+            .bind(threshold.duration_minutes)
+            .bind(threshold.reason.trim())
+            .execute(&mut *tx)
+            .await?;
+        }
+
+        let after = Self::load_severity_settings(&mut tx).await?;
+
+        AuditService::record(&mut tx, NewAuditEvent {
+            actor_uuid: Some(staff_uuid),
+            action: "severity.update".to_string(),
+            target_type: "severity_settings".to_string(),
+            target_id: None,
+            details: json!({ "before": before, "after": after }),
+        })
+        .await?;
+
+        tx.commit().await?;
+
+        Ok(after)
+    }
+
+    pub async fn get_approval_settings(&self) -> AppResult<ApprovalSettings> {
+        let mut conn = self.pool.acquire().await?;
+        Self::load_approval_settings(&mut conn).await
+    }
+
+    /// Replaces the punishment types that always need approval.
+    pub async fn update_approval_settings(&self, staff_uuid: Uuid, required_types: Vec<String>) -> AppResult<ApprovalSettings> {
+        for punishment_type in &required_types {
+            validate_punishment_type(punishment_type)?;
+        }
+
+        let mut tx = self.pool.begin().await?;
+        let before = Self::load_approval_settings(&mut tx).await?;
+
+        let after = sqlx::query_as::<_, ApprovalSettings>(
+            "UPDATE approval_settings SET required_types = $1, updated_by = $2, updated_at = NOW() RETURNING *"
+        )
+        .bind(&required_types)
+        .bind(staff_uuid)
+        .fetch_one(&mut *tx)
+        .await?;
+
+        AuditService::record(&mut tx, NewAuditEvent {
+            actor_uuid: Some(staff_uuid),
+            action: "approval.update".to_string(),
+            target_type: "approval_settings".to_string(),
+            target_id: None,
+            details: json!({ "before": before, "after": after }),
+        })
+        .await?;
+
+        tx.commit().await?;
+
+        Ok(after)
+    }
+
+    pub async fn load_approval_settings(conn: &mut PgConnection) -> AppResult<ApprovalSettings> {
+        let settings = sqlx::query_as::<_, ApprovalSettings>("SELECT * FROM approval_settings")
+            .fetch_one(conn)
+            .await?;
+
+        Ok(settings)
+    }
+
+    pub async fn list_staff_roles(&self) -> AppResult<Vec<StaffRole>> {
+        let roles = sqlx::query_as::<_, StaffRole>("SELECT * FROM staff_roles ORDER BY id")
+            .fetch_all(&self.pool)
+            .await?;
+
+        Ok(roles)
+    }
+
+    /// Replaces every punishment limit of the role.
+    pub async fn update_role_limits(&self, staff_uuid: Uuid, role_id: i32, limits: RoleLimits) -> AppResult<StaffRole> {
+        validate_role_limits(&limits)?;
+
+        let mut tx = self.pool.begin().await?;
+
+        let before = sqlx::query_as::<_, StaffRole>("SELECT * FROM staff_roles WHERE id = $1 FOR UPDATE")
+            .bind(role_id)
+            .fetch_optional(&mut *tx)
+            .await?
+            .ok_or_else(|| AppError::NotFound(format!("Staff role {} does not exist", role_id)))?;
+
+        let own_role = Self::find_own_role(&mut tx, staff_uuid).await?;
+        if let Some(denied) = own_role.edit_denied(&before) {
+            return Err(AppError::PermissionDenied(denied));
+        }
+
+        let role = sqlx::query_as::<_, StaffRole>(
+            r#"
+            UPDATE staff_roles
+            SET allowed_types = $2,
+                max_duration_minutes = $3,
+                daily_limit = $4,
+                updated_at = NOW()
+            WHERE id = $1
+            RETURNING *
+            "#,
+        )
+        .bind(role_id)
+        .bind(&limits.allowed_types)
+        .bind(limits.max_duration_minutes)
+        .bind(limits.daily_limit)
+        .fetch_one(&mut *tx)
+        .await?;
+
+        AuditService::record(&mut tx, NewAuditEvent {
+            actor_uuid: Some(staff_uuid),
+            action: "role.limits".to_string(),
+            target_type: "staff_role".to_string(),
+            target_id: Some(role_id.to_string()),
+            details: json!({ "before": before, "after": role }),
+        })
+        .await?;
+
+        tx.commit().await?;
+
+        Ok(role)
+    }
+
+    pub async fn set_role_weight(&self, staff_uuid: Uuid, role_id: i32, weight: i32) -> AppResult<StaffRole> {
+        let mut tx = self.pool.begin().await?;
+
+        let before = sqlx::query_as::<_, StaffRole>("SELECT * FROM staff_roles WHERE id = $1 FOR UPDATE")
+            .bind(role_id)
+            .fetch_optional(&mut *tx)
+            .await?
+            .ok_or_else(|| AppError::NotFound(format!("Staff role {} does not exist", role_id)))?;
+
+        let own_role = Self::find_own_role(&mut tx, staff_uuid).await?;
+        if let Some(denied) = own_role.weight_change_denied(&before, weight) {
+            return Err(AppError::PermissionDenied(denied));
+        }
+
+        let role = sqlx::query_as::<_, StaffRole>(
+            "UPDATE staff_roles SET weight = $2, updated_at = NOW() WHERE id = $1 RETURNING *"
+        )
+        .bind(role_id)
+        .bind(weight)
+        .fetch_one(&mut *tx)
+        .await?;
+
+        AuditService::record(&mut tx, NewAuditEvent {
+            actor_uuid: Some(staff_uuid),
+            action: "role.weight".to_string(),
+            target_type: "staff_role".to_string(),
+            target_id: Some(role_id.to_string()),
+            details: json!({ "before": before.weight, "after": role.weight }),
+        })
+        .await?;
+
+        tx.commit().await?;
+
+        Ok(role)
+    }
+
+    async fn find_own_role(conn: &mut PgConnection, staff_uuid: Uuid) -> AppResult<StaffRole> {
+        PunishmentService::find_staff_role(conn, staff_uuid)
+            .await?
+            .ok_or_else(|| AppError::PermissionDenied("Only staff members with a role can edit roles".to_string()))
+    }
+
+    /// Loads the severity settings with their thresholds, lowest first.
+    pub async fn load_severity_settings(conn: &mut PgConnection) -> AppResult<(SeveritySettings, Vec<SeverityThreshold>)> {
+        let settings = sqlx::query_as::<_, SeveritySettings>("SELECT * FROM severity_settings")
+            .fetch_one(&mut *conn)
+            .await?;
+
+        let thresholds = sqlx::query_as::<_, SeverityThreshold>("SELECT * FROM severity_thresholds ORDER BY points")
+            .fetch_all(&mut *conn)
+            .await?;
+
+        Ok((settings, thresholds))
+    }
+
+    async fn find_category(conn: &mut PgConnection, category_id: i32, lock: bool) -> AppResult<PunishmentCategory> {
+        let query = if lock {
+            "SELECT * FROM punishment_categories WHERE id = $1 FOR UPDATE"
+        } else {
+            "SELECT * FROM punishment_categories WHERE id = $1"
+        };
+
+        sqlx::query_as::<_, PunishmentCategory>(query)
+            .bind(category_id)
+            .fetch_optional(conn)
+            .await?
+            .ok_or_else(|| AppError::NotFound(format!("Punishment category {} does not exist", category_id)))
+    }
+
+    async fn get_ladder(conn: &mut PgConnection, policy_version_id: i32) -> AppResult<Vec<PunishmentTemplate>> {
+        let ladder = sqlx::query_as::<_, PunishmentTemplate>(
+            "SELECT * FROM punishment_templates WHERE policy_version_id = $1 ORDER BY offense_number"
+        )
+        .bind(policy_version_id)
+        .fetch_all(conn)
+        .await?;
+
+        Ok(ladder)
+    }
+
+    async fn ensure_name_available(conn: &mut PgConnection, name: &str, except_id: Option<i32>) -> AppResult<()> {
+        let taken = sqlx::query_scalar::<_, bool>(
+            "SELECT EXISTS (SELECT 1 FROM punishment_categories WHERE name = $1 AND id IS DISTINCT FROM $2)"
+        )
+        .bind(name)
+        .bind(except_id)
+        .fetch_one(conn)
+        .await?;
+
+        if taken {
+            return Err(AppError::CustomValidationError(format!("A category named {} already exists", name)));
+        }
+
+        Ok(())
+    }
+}
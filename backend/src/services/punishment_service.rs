@@ -1,12 +1,34 @@
-use crate::error::AppResult;
-use crate::models::PunishmentWithTemplate;
-use sqlx::PgPool;
+use crate::error::{AppError, AppResult};
+use crate::models::{validate_punishment_type, DecayRule, EscalationPlan, IssueOutcome, IssuedPunishment, NewAuditEvent, PunishmentApproval, PunishmentRevision, PunishmentUpdate, SeverityPlan, SeverityThreshold, StaffRole, SYSTEM_PLAYER_UUID, validate_ip_range, validate_scope, AppealCounts, CategoryPunishmentCount, NewPunishment, PunishmentCursor, PunishmentFilter, PunishmentSort, PunishmentEvent, PunishmentTemplate, PunishmentWithTemplate, ServerIdentity};
+use crate::services::{AuditService, PolicyService};
+use serde_json::json;
+use sqlx::types::ipnetwork::IpNetwork;
+use sqlx::types::Json;
+use sqlx::{PgConnection, PgPool};
+use std::net::IpAddr;
+use time::OffsetDateTime;
 use uuid::Uuid;
 
 pub struct PunishmentService {
     pool: PgPool,
 }
 
+/// Which approval checks a punishment still has to pass before it is issued.
+enum Review {
+    Required { request_approval: bool },
+    Approved,
+}
+
+enum Inserted {
+    Issued { id: Uuid, automatic_ban: Option<AutomaticBan> },
+    Queued(Box<PunishmentApproval>),
+}
+
+enum AutomaticBan {
+    Issued(Uuid),
+    Queued(Box<PunishmentApproval>),
+}
+
 impl PunishmentService {
     pub fn new(pool: PgPool) -> Self {
         Self {
@@ -14,30 +36,46 @@ impl PunishmentService {
         }
     }
 
-    pub async fn get_active_punishments(&self, player_id: &str) -> AppResult<Vec<PunishmentWithTemplate>> {
+    /// Returns the punishments of the account plus IP punishments covering `ip_address`.
+    pub async fn get_active_punishments(
+        &self,
+        player_id: &str,
+        ip_address: Option<IpAddr>,
+        server: &ServerIdentity,
+    ) -> AppResult<Vec<PunishmentWithTemplate>> {
         let player_uuid = Uuid::parse_str(player_id)?;
 
         let punishments = sqlx::query_as::<_, PunishmentWithTemplate>(
             r#"
             SELECT
-                p.id,
-                p.player_uuid,
-                p.staff_uuid,
-                p.category_id,
-                p.offense_number,
-                p.punishment_type,
-                p.reason,
-                p.evidence,
-                p.note,
-                p.issued_at,
-                p.expires_at,
-                p.active,
-                p.revoked,
-                p.revoked_by,
-                p.revoked_at,
-                p.revoke_reason,
-                p.created_at,
-                p.updated_at,
+                p.*,
+                pc.name AS category_name
+            FROM punishments p
+            INNER JOIN punishment_categories pc ON p.category_id = pc.id
+            WHERE (p.player_uuid = $1 OR p.ip_range >>= $2::INET)
+              AND p.active = true
+              AND p.revoked = false
+              AND (p.expires_at IS NULL OR p.expires_at > NOW())
+            ORDER BY p.issued_at DESC
+            "#
+        )
+        .bind(player_uuid)
+        .bind(ip_address)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(punishments
+            .into_iter()
+            .filter(|p| server.covers(&p.scope, p.server_group.as_deref()))
+            .collect())
+    }
+
+    /// Returns every active account punishment of the player regardless of scope.
+    pub async fn get_player_active_punishments(&self, player_uuid: Uuid) -> AppResult<Vec<PunishmentWithTemplate>> {
+        let punishments = sqlx::query_as::<_, PunishmentWithTemplate>(
+            r#"
+            SELECT
+                p.*,
                 pc.name AS category_name
             FROM punishments p
             INNER JOIN punishment_categories pc ON p.category_id = pc.id
@@ -54,4 +92,1068 @@ impl PunishmentService {
 
         Ok(punishments)
     }
-}
\ No newline at end of file
+
+    /// Lists the punishments matching every set filter, one page after `cursor`.
+    pub async fn list_punishments(
+        &self,
+        filter: &PunishmentFilter,
+        sort: PunishmentSort,
+        cursor: Option<&PunishmentCursor>,
+        limit: i64,
+    ) -> AppResult<Vec<PunishmentWithTemplate>> {
+        if let Some(punishment_type) = &filter.punishment_type {
+            validate_punishment_type(punishment_type)?;
+        }
+
+        let (comparison, direction) = match sort {
+            PunishmentSort::NewestFirst => ("<", "DESC"),
+            PunishmentSort::OldestFirst => (">", "ASC"),
+        };
+
+        let punishments = sqlx::query_as::<_, PunishmentWithTemplate>(&format!(
+            r#"
+            SELECT
+                p.*,
+                pc.name AS category_name
+            FROM punishments p
+            INNER JOIN punishment_categories pc ON p.category_id = pc.id
+            WHERE ($1::UUID IS NULL OR p.player_uuid = $1)
+              AND ($2::UUID IS NULL OR p.staff_uuid = $2)
+              AND ($3::INTEGER IS NULL OR p.category_id = $3)
+              AND ($4::VARCHAR IS NULL OR p.punishment_type = $4)
+              AND (
+                  $5::TEXT IS NULL
+                  OR ($5 = 'active' AND p.active = true AND p.revoked = false AND (p.expires_at IS NULL OR p.expires_at > NOW()))
+                  OR ($5 = 'revoked' AND p.revoked = true)
+                  OR ($5 = 'expired' AND p.revoked = false AND (p.active = false OR p.expires_at <= NOW()))
+              )
+              AND ($6::TIMESTAMPTZ IS NULL OR p.issued_at >= $6)
+              AND ($7::TIMESTAMPTZ IS NULL OR p.issued_at < $7)
+              AND ($8::TIMESTAMPTZ IS NULL OR (p.issued_at, p.id) {comparison} ($8, $9))
+            ORDER BY p.issued_at {direction}, p.id {direction}
+            LIMIT $10
+            "#
+        ))
+        .bind(filter.player_uuid)
+        .bind(filter.staff_uuid)
+        .bind(filter.category_id)
+        .bind(filter.punishment_type.as_deref())
+        .bind(filter.status.map(|status| status.as_str()))
+        .bind(filter.issued_after)
+        .bind(filter.issued_before)
+        .bind(cursor.map(|cursor| cursor.issued_at))
+        .bind(cursor.map(|cursor| cursor.id))
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(punishments)
+    }
+
+    /// Counts the non-revoked punishments of the player per category.
+    pub async fn get_punishment_counts(&self, player_uuid: Uuid) -> AppResult<Vec<CategoryPunishmentCount>> {
+        let counts = sqlx::query_as::<_, CategoryPunishmentCount>(
+            r#"
+            SELECT
+                pc.id AS category_id,
+                pc.name AS category_name,
+                COUNT(*) AS total,
+                COUNT(*) FILTER (WHERE p.active = true AND (p.expires_at IS NULL OR p.expires_at > NOW())) AS active
+            FROM punishments p
+            INNER JOIN punishment_categories pc ON p.category_id = pc.id
+            WHERE p.player_uuid = $1
+              AND p.revoked = false
+            GROUP BY pc.id, pc.name
+            ORDER BY pc.name
+            "#
+        )
+        .bind(player_uuid)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(counts)
+    }
+
+    pub async fn get_appeal_counts(&self, player_uuid: Uuid) -> AppResult<AppealCounts> {
+        let counts = sqlx::query_as::<_, AppealCounts>(
+            r#"
+            SELECT
+                COUNT(*) AS total,
+                COUNT(*) FILTER (WHERE status IN ('pending', 'under_review')) AS open
+            FROM appeals
+            WHERE player_uuid = $1
+            "#
+        )
+        .bind(player_uuid)
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok(counts)
+    }
+
+    pub async fn get_punishment(&self, id: Uuid) -> AppResult<Option<PunishmentWithTemplate>> {
+        let punishment = sqlx::query_as::<_, PunishmentWithTemplate>(
+            r#"
+            SELECT
+                p.*,
+                pc.name AS category_name
+            FROM punishments p
+            INNER JOIN punishment_categories pc ON p.category_id = pc.id
+            WHERE p.id = $1
+            "#
+        )
+        .bind(id)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(punishment)
+    }
+
+    /// Shows which step `issue_punishment` would apply to the player right now.
+    pub async fn preview_punishment(&self, player_uuid: Uuid, category_id: i32) -> AppResult<EscalationPlan> {
+        let mut conn = self.pool.acquire().await?;
+
+        let player_exists = sqlx::query_scalar::<_, bool>("SELECT EXISTS (SELECT 1 FROM players WHERE uuid = $1)")
+            .bind(player_uuid)
+            .fetch_one(&mut *conn)
+            .await?;
+
+        if !player_exists {
+            return Err(AppError::NotFound("player not found".to_string()));
+        }
+
+        Self::plan_escalation(&mut conn, player_uuid, category_id).await
+    }
+
+    /// Picks the step of the category's active ladder the player's next punishment gets.
+    async fn plan_escalation(conn: &mut PgConnection, player_uuid: Uuid, category_id: i32) -> AppResult<EscalationPlan> {
+        let category = sqlx::query_as::<_, (bool, String, Option<i32>)>(
+            "SELECT active, decay_mode, decay_days FROM punishment_categories WHERE id = $1"
+        )
+        .bind(category_id)
+        .fetch_optional(&mut *conn)
+        .await?;
+
+        let decay = match category {
+            None => return Err(AppError::NotFound("punishment category not found".to_string())),
+            Some((false, _, _)) => return Err(AppError::CustomValidationError("Punishment category is inactive".to_string())),
+            Some((true, decay_mode, decay_days)) => DecayRule::from_columns(&decay_mode, decay_days)?,
+        };
+
+        // Automatic bans from severity thresholds carry the triggering category but are no offense in it
+        let offenses = sqlx::query_scalar::<_, OffsetDateTime>(
+            "SELECT issued_at FROM punishments WHERE player_uuid = $1 AND category_id = $2 AND NOT revoked AND threshold_points IS NULL"
+        )
+        .bind(player_uuid)
+        .bind(category_id)
+        .fetch_all(&mut *conn)
+        .await?;
+
+        let ladder = sqlx::query_as::<_, PunishmentTemplate>(
+            r#"
+            SELECT * FROM punishment_templates
+            WHERE policy_version_id = active_policy_version_id($1)
+            ORDER BY offense_number
+            "#
+        )
+        .bind(category_id)
+        .fetch_all(&mut *conn)
+        .await?;
+
+        let counted_offenses = decay.count_offenses(&offenses, OffsetDateTime::now_utc());
+        let ladder_length = ladder.len() as i32;
+
+        // Once the offense count exceeds the highest step, the last step repeats
+        let template = ladder
+            .into_iter()
+            .rev()
+            .find(|template| template.offense_number as i64 <= counted_offenses + 1)
+            .ok_or_else(|| AppError::NotFound("no escalation template for this category".to_string()))?;
+
+        let severity = Self::plan_severity(conn, player_uuid, template.points).await?;
+
+        Ok(EscalationPlan {
+            template,
+            decay,
+            previous_offenses: offenses.len() as i64,
+            counted_offenses,
+            ladder_length,
+            severity,
+        })
+    }
+
+    /// Returns the player's current severity points, `None` while severity points are disabled.
+    pub async fn get_severity_points(&self, player_uuid: Uuid) -> AppResult<Option<f64>> {
+        let mut conn = self.pool.acquire().await?;
+        let severity = Self::plan_severity(&mut conn, player_uuid, 0).await?;
+
+        Ok(severity.map(|severity| severity.current_points))
+    }
+
+    async fn plan_severity(conn: &mut PgConnection, player_uuid: Uuid, awarded_points: i32) -> AppResult<Option<SeverityPlan>> {
+        let (settings, thresholds) = PolicyService::load_severity_settings(conn).await?;
+        if !settings.enabled {
+            return Ok(None);
+        }
+
+        // Points are summed across every category, which is what sets them apart from ladders
+        let punishments = sqlx::query_as::<_, (i32, OffsetDateTime)>(
+            "SELECT points, issued_at FROM punishments WHERE player_uuid = $1 AND NOT revoked AND points > 0"
+        )
+        .bind(player_uuid)
+        .fetch_all(&mut *conn)
+        .await?;
+
+        let current_points = settings.total_points(&punishments, OffsetDateTime::now_utc());
+
+        Ok(Some(SeverityPlan::new(current_points, awarded_points, thresholds)))
+    }
+
+    /// Issues the next step of the category's escalation ladder to the player, plus an automatic
+    /// ban when it takes the player's severity points over a threshold. Types and categories that
+    /// need approval are queued, a step exceeding a limit of the staff member's role fails or is
+    /// queued with `request_approval`. Automatic bans needing approval are always queued.
+    pub async fn issue_punishment(&self, staff_uuid: Uuid, punishment: NewPunishment, request_approval: bool) -> AppResult<IssueOutcome> {
+        let mut tx = self.pool.begin().await?;
+        let inserted = Self::insert_punishment(&mut tx, staff_uuid, punishment, Review::Required { request_approval }).await?;
+        tx.commit().await?;
+
+        match inserted {
+            Inserted::Issued { id, automatic_ban } => {
+                let issued = self.load_issued(id, automatic_ban).await?;
+                Ok(IssueOutcome::Issued(Box::new(issued)))
+            }
+            Inserted::Queued(approval) => Ok(IssueOutcome::Queued(approval)),
+        }
+    }
+
+    async fn insert_punishment(conn: &mut PgConnection, staff_uuid: Uuid, punishment: NewPunishment, review: Review) -> AppResult<Inserted> {
+        if punishment.player_uuid == staff_uuid {
+            return Err(AppError::CustomValidationError("Staff members cannot punish themselves".to_string()));
+        }
+
+        // Locking the player serialises concurrent punishments so offense numbers stay consistent
+        let player = sqlx::query_scalar::<_, Uuid>("SELECT uuid FROM players WHERE uuid = $1 FOR UPDATE")
+            .bind(punishment.player_uuid)
+            .fetch_optional(&mut *conn)
+            .await?;
+
+        if player.is_none() {
+            return Err(AppError::NotFound("player not found".to_string()));
+        }
+
+        Self::ensure_outranks(conn, staff_uuid, punishment.player_uuid, "punish its members").await?;
+
+        let plan = Self::plan_escalation(conn, punishment.player_uuid, punishment.category_id).await?;
+        let template = plan.template;
+        let threshold = plan.severity.and_then(|severity| severity.threshold);
+        let requested_scope = (punishment.scope.clone(), punishment.server_group.clone());
+
+        let (scope, server_group) = match punishment.scope {
+            Some(scope) => (scope, punishment.server_group),
+            None => (template.scope, template.server_group),
+        };
+        validate_scope(&scope, server_group.as_deref())?;
+
+        if punishment.ip_range.is_some() && !matches!(template.punishment_type.as_str(), "mute" | "shadow_mute" | "temp_ban" | "perm_ban") {
+            return Err(AppError::CustomValidationError(format!(
+                "IP punishments only support mutes, shadow mutes and bans, but offense {} of this category is a {}",
+                template.offense_number, template.punishment_type
+            )));
+        }
+
+        // CIDR columns reject host bits, so "203.0.113.7/24" is stored as "203.0.113.0/24"
+        let ip_range = punishment
+            .ip_range
+            .map(|range| IpNetwork::new(range.network(), range.prefix()).unwrap_or(range));
+        if let Some(range) = &ip_range {
+            validate_ip_range(range)?;
+        }
+
+        // The range reaches every account that used it, so it needs to outrank each staff member among them
+        if let Some(range) = ip_range {
+            let staff_on_range = sqlx::query_scalar::<_, Uuid>(
+                r#"
+                SELECT DISTINCT pa.player_uuid
+                FROM player_addresses pa
+                INNER JOIN players pl ON pl.uuid = pa.player_uuid
+                WHERE pa.ip_address <<= $1 AND pl.staff = true AND pa.player_uuid <> $2
+                "#
+            )
+            .bind(range)
+            .bind(staff_uuid)
+            .fetch_all(&mut *conn)
+            .await?;
+
+            for member_uuid in staff_on_range {
+                Self::ensure_outranks(conn, staff_uuid, member_uuid, "punish addresses its members used").await?;
+            }
+        }
+
+        if let Review::Required { request_approval } = review {
+            // Both checks apply to the step the ladder gives, so they run once it is known
+            let approval_reason = match Self::required_approval_reason(conn, punishment.category_id, &template.punishment_type).await? {
+                Some(reason) => Some(reason),
+                None => match Self::exceeded_role_limit(conn, staff_uuid, &template.punishment_type, template.duration_minutes).await? {
+                    Some(limit) if !request_approval => return Err(AppError::PermissionDenied(limit)),
+                    limit => limit,
+                },
+            };
+
+            if let Some(approval_reason) = approval_reason {
+                let approval = sqlx::query_as::<_, PunishmentApproval>(
+                    r#"
+                    INSERT INTO punishment_approvals (
+                        player_uuid, requested_by, category_id, punishment_type, duration_minutes,
+                        reason, evidence, note, scope, server_group, ip_range, approval_reason
+                    )
+                    VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12)
+                    RETURNING *
+                    "#
+                )
+                .bind(punishment.player_uuid)
+                .bind(staff_uuid)
+                .bind(punishment.category_id)
+                .bind(&template.punishment_type)
+                .bind(template.duration_minutes)
+                .bind(punishment.reason)
+                .bind(punishment.evidence)
+                .bind(punishment.note)
+                .bind(requested_scope.0)
+                .bind(requested_scope.1)
+                .bind(ip_range)
+                .bind(approval_reason)
+                .fetch_one(&mut *conn)
+                .await?;
+
+                AuditService::record(conn, NewAuditEvent {
+                    actor_uuid: Some(staff_uuid),
+                    action: "punishment.request".to_string(),
+                    target_type: "punishment_approval".to_string(),
+                    target_id: Some(approval.id.to_string()),
+                    details: json!({ "approval": approval }),
+                })
+                .await?;
+
+                return Ok(Inserted::Queued(Box::new(approval)));
+            }
+        }
+
+        let reason = punishment.reason.unwrap_or(template.reason_template);
+        let id = sqlx::query_scalar::<_, Uuid>(
+            r#"
+            INSERT INTO punishments (
+                player_uuid, staff_uuid, category_id, offense_number, punishment_type,
+                reason, evidence, note, expires_at, scope, server_group, ip_range, template_id, points, active,
+                requires_acknowledgement
+            )
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, NOW() + $9::INTEGER * INTERVAL '1 minute', $10, $11, $12, $13, $14, $5 != 'kick', $15)
+            RETURNING id
+            "#
+        )
+        .bind(punishment.player_uuid)
+        .bind(staff_uuid)
+        .bind(punishment.category_id)
+        .bind(template.offense_number)
+        .bind(&template.punishment_type)
+        .bind(&reason)
+        .bind(punishment.evidence)
+        .bind(punishment.note)
+        .bind(template.duration_minutes)
+        .bind(&scope)
+        .bind(&server_group)
+        .bind(ip_range)
+        .bind(template.id)
+        .bind(template.points)
+        .bind(template.requires_acknowledgement)
+        .fetch_one(&mut *conn)
+        .await?;
+
+        AuditService::record(conn, NewAuditEvent {
+            actor_uuid: Some(staff_uuid),
+            action: "punishment.issue".to_string(),
+            target_type: "punishment".to_string(),
+            target_id: Some(id.to_string()),
+            details: json!({
+                "player_uuid": punishment.player_uuid,
+                "category_id": punishment.category_id,
+                "offense_number": template.offense_number,
+                "type": template.punishment_type,
+                "duration_minutes": template.duration_minutes,
+                "reason": reason,
+                "scope": scope,
+                "server_group": server_group,
+                "ip_range": ip_range.map(|range| range.to_string()),
+                "points": template.points,
+            }),
+        })
+        .await?;
+
+        let automatic_ban = match threshold {
+            Some(threshold) => Some(
+                Self::issue_automatic_ban(conn, staff_uuid, punishment.player_uuid, punishment.category_id, id, threshold).await?
+            ),
+            None => None,
+        };
+
+        Ok(Inserted::Issued { id, automatic_ban })
+    }
+
+    /// Issues the ban of the severity threshold `offense_id` reached, or queues it in the name of
+    /// the backend when bans of its type need approval or the staff member's role could not issue it.
+    async fn issue_automatic_ban(
+        conn: &mut PgConnection,
+        staff_uuid: Uuid,
+        player_uuid: Uuid,
+        category_id: i32,
+        offense_id: Uuid,
+        threshold: SeverityThreshold,
+    ) -> AppResult<AutomaticBan> {
+        let approval_reason = match Self::required_approval_reason(conn, category_id, &threshold.punishment_type).await? {
+            Some(reason) => Some(reason),
+            // The daily limit counts punishments staff issue themselves, so only the type and duration apply
+            None => Self::find_staff_role(conn, staff_uuid)
+                .await?
+                .and_then(|role| role.exceeded_limit(&threshold.punishment_type, threshold.duration_minutes, 0)),
+        };
+
+        let Some(approval_reason) = approval_reason else {
+            let id = Self::insert_automatic_ban(
+                conn,
+                offense_id,
+                &threshold.punishment_type,
+                threshold.duration_minutes,
+                &threshold.reason,
+                threshold.points,
+            )
+            .await?;
+
+            return Ok(AutomaticBan::Issued(id));
+        };
+
+        let approval = sqlx::query_as::<_, PunishmentApproval>(
+            r#"
+            INSERT INTO punishment_approvals (
+                player_uuid, requested_by, category_id, punishment_type, duration_minutes,
+                reason, note, scope, approval_reason, threshold_points, offense_id
+            )
+            VALUES ($1, $2, $3, $4, $5, $6, $7, 'global', $8, $9, $10)
+            RETURNING *
+            "#
+        )
+        .bind(player_uuid)
+        .bind(SYSTEM_PLAYER_UUID)
+        .bind(category_id)
+        .bind(&threshold.punishment_type)
+        .bind(threshold.duration_minutes)
+        .bind(&threshold.reason)
+        .bind(Self::automatic_ban_note(threshold.points))
+        .bind(format!("Automatic ban on reaching {} severity points: {}", threshold.points, approval_reason))
+        .bind(threshold.points)
+        .bind(offense_id)
+        .fetch_one(&mut *conn)
+        .await?;
+
+        AuditService::record(conn, NewAuditEvent {
+            actor_uuid: None,
+            action: "punishment.request".to_string(),
+            target_type: "punishment_approval".to_string(),
+            target_id: Some(approval.id.to_string()),
+            details: json!({ "approval": approval }),
+        })
+        .await?;
+
+        Ok(AutomaticBan::Queued(Box::new(approval)))
+    }
+
+    /// Bans the player in the name of the backend, in the category and offense of the punishment
+    /// that reached the threshold.
+    async fn insert_automatic_ban(
+        conn: &mut PgConnection,
+        offense_id: Uuid,
+        punishment_type: &str,
+        duration_minutes: Option<i32>,
+        reason: &str,
+        threshold_points: i32,
+    ) -> AppResult<Uuid> {
+        let (id, player_uuid) = sqlx::query_as::<_, (Uuid, Uuid)>(
+            r#"
+            INSERT INTO punishments (
+                player_uuid, staff_uuid, category_id, offense_number, punishment_type,
+                reason, note, expires_at, scope, threshold_points
+            )
+            SELECT player_uuid, $2, category_id, offense_number, $3, $4, $5, NOW() + $6::INTEGER * INTERVAL '1 minute', 'global', $7
+            FROM punishments
+            WHERE id = $1
+            RETURNING id, player_uuid
+            "#
+        )
+        .bind(offense_id)
+        .bind(SYSTEM_PLAYER_UUID)
+        .bind(punishment_type)
+        .bind(reason)
+        .bind(Self::automatic_ban_note(threshold_points))
+        .bind(duration_minutes)
+        .bind(threshold_points)
+        .fetch_one(&mut *conn)
+        .await?;
+
+        AuditService::record(conn, NewAuditEvent {
+            actor_uuid: None,
+            action: "punishment.issue".to_string(),
+            target_type: "punishment".to_string(),
+            target_id: Some(id.to_string()),
+            details: json!({
+                "player_uuid": player_uuid,
+                "type": punishment_type,
+                "duration_minutes": duration_minutes,
+                "reason": reason,
+                "threshold_points": threshold_points,
+            }),
+        })
+        .await?;
+
+        Ok(id)
+    }
+
+    fn automatic_ban_note(threshold_points: i32) -> String {
+        format!("Issued automatically on reaching {} severity points", threshold_points)
+    }
+
+    /// Why the punishment needs a second staff member whoever issues it, if it does.
+    async fn required_approval_reason(conn: &mut PgConnection, category_id: i32, punishment_type: &str) -> AppResult<Option<String>> {
+        let settings = PolicyService::load_approval_settings(conn).await?;
+
+        let (category_name, requires_approval) = sqlx::query_as::<_, (String, bool)>(
+            "SELECT name, requires_approval FROM punishment_categories WHERE id = $1"
+        )
+        .bind(category_id)
+        .fetch_one(&mut *conn)
+        .await?;
+
+        Ok(settings.required_reason(punishment_type, &category_name, requires_approval))
+    }
+
+    async fn exceeded_role_limit(
+        conn: &mut PgConnection,
+        staff_uuid: Uuid,
+        punishment_type: &str,
+        duration_minutes: Option<i32>,
+    ) -> AppResult<Option<String>> {
+        let Some(role) = Self::find_staff_role(conn, staff_uuid).await? else {
+            return Ok(None);
+        };
+
+        // Automatic bans are issued by the backend on the staff member's behalf and do not count
+        let issued_today = sqlx::query_scalar::<_, i64>(
+            r#"
+            SELECT COUNT(*) FROM punishments
+            WHERE staff_uuid = $1 AND issued_at > NOW() - INTERVAL '1 day' AND threshold_points IS NULL
+            "#
+        )
+        .bind(staff_uuid)
+        .fetch_one(&mut *conn)
+        .await?;
+
+        Ok(role.exceeded_limit(punishment_type, duration_minutes, issued_today))
+    }
+
+    /// Fails unless the actor's role outranks the role of the target, players without a staff role
+    /// can be acted on by every staff member.
+    pub async fn ensure_outranks(conn: &mut PgConnection, actor_uuid: Uuid, target_uuid: Uuid, action: &str) -> AppResult<()> {
+        let Some(target) = Self::find_staff_role(conn, target_uuid).await? else {
+            return Ok(());
+        };
+        let actor = Self::find_staff_role(conn, actor_uuid).await?;
+
+        if actor.is_some_and(|actor| actor.outranks(&target)) {
+            return Ok(());
+        }
+
+        Err(AppError::PermissionDenied(format!(
+            "Only roles weighing more than {} ({}) can {}",
+            target.name, target.weight, action
+        )))
+    }
+
+    pub async fn find_staff_role(conn: &mut PgConnection, player_uuid: Uuid) -> AppResult<Option<StaffRole>> {
+        let role = sqlx::query_as::<_, StaffRole>(
+            r#"
+            SELECT r.*
+            FROM players pl
+            INNER JOIN staff_roles r ON r.id = pl.role_id
+            WHERE pl.uuid = $1 AND pl.staff = true
+            "#
+        )
+        .bind(player_uuid)
+        .fetch_optional(conn)
+        .await?;
+
+        Ok(role)
+    }
+
+    /// Revokes an active punishment. Punishments issued by another staff member can only be revoked
+    /// by roles outranking theirs.
+    pub async fn revoke_punishment(&self, staff_uuid: Uuid, id: Uuid, reason: Option<String>) -> AppResult<PunishmentWithTemplate> {
+        let mut tx = self.pool.begin().await?;
+
+        let punishment = sqlx::query_as::<_, (Uuid, bool)>("SELECT staff_uuid, revoked FROM punishments WHERE id = $1 FOR UPDATE")
+            .bind(id)
+            .fetch_optional(&mut *tx)
+            .await?;
+
+        let issued_by = match punishment {
+            None => return Err(AppError::NotFound("punishment not found".to_string())),
+            Some((_, true)) => return Err(AppError::CustomValidationError("The punishment is already revoked".to_string())),
+            Some((issued_by, false)) => issued_by,
+        };
+
+        if issued_by != staff_uuid {
+            Self::ensure_outranks(&mut tx, staff_uuid, issued_by, "revoke punishments its members issued").await?;
+        }
+
+        sqlx::query(
+            r#"
+            UPDATE punishments
+            SET active = false,
+                revoked = true,
+                revoked_by = $2,
+                revoked_at = NOW(),
+                revoke_reason = $3,
+                updated_at = NOW()
+            WHERE id = $1
+            "#
+        )
+        .bind(id)
+        .bind(staff_uuid)
+        .bind(&reason)
+        .execute(&mut *tx)
+        .await?;
+
+        AuditService::record(&mut tx, NewAuditEvent {
+            actor_uuid: Some(staff_uuid),
+            action: "punishment.revoke".to_string(),
+            target_type: "punishment".to_string(),
+            target_id: Some(id.to_string()),
+            details: json!({ "issued_by": issued_by, "reason": reason }),
+        })
+        .await?;
+
+        tx.commit().await?;
+
+        self.get_punishment(id)
+            .await?
+            .ok_or_else(|| AppError::NotFound("punishment not found".to_string()))
+    }
+
+    /// Records that the player confirmed the warning. Acknowledging it again keeps the first time.
+    pub async fn acknowledge_warning(&self, player_uuid: Uuid, id: Uuid) -> AppResult<OffsetDateTime> {
+        let mut tx = self.pool.begin().await?;
+
+        let warning = sqlx::query_as::<_, (Uuid, bool, bool, Option<OffsetDateTime>)>(
+            "SELECT player_uuid, requires_acknowledgement, revoked, acknowledged_at FROM punishments WHERE id = $1 FOR UPDATE"
+        )
+        .bind(id)
+        .fetch_optional(&mut *tx)
+        .await?;
+
+        match warning {
+            // Someone else's punishment is as good as a missing one to the caller
+            None => return Err(AppError::NotFound("punishment not found".to_string())),
+            Some((owner, ..)) if owner != player_uuid => return Err(AppError::NotFound("punishment not found".to_string())),
+            Some((_, false, ..)) => {
+                return Err(AppError::CustomValidationError("The punishment does not need to be acknowledged".to_string()));
+            }
+            Some((_, true, true, _)) => return Err(AppError::CustomValidationError("The warning was revoked".to_string())),
+            Some((_, true, false, Some(acknowledged_at))) => return Ok(acknowledged_at),
+            Some((_, true, false, None)) => {}
+        }
+
+        let acknowledged_at = sqlx::query_scalar::<_, OffsetDateTime>(
+            "UPDATE punishments SET acknowledged_at = NOW(), updated_at = NOW() WHERE id = $1 RETURNING acknowledged_at"
+        )
+        .bind(id)
+        .fetch_one(&mut *tx)
+        .await?;
+
+        AuditService::record(&mut tx, NewAuditEvent {
+            actor_uuid: Some(player_uuid),
+            action: "punishment.acknowledge".to_string(),
+            target_type: "punishment".to_string(),
+            target_id: Some(id.to_string()),
+            details: json!({}),
+        })
+        .await?;
+
+        tx.commit().await?;
+
+        Ok(acknowledged_at)
+    }
+
+    /// Edits an issued punishment and stores the change as its next revision. Punishments issued by
+    /// another staff member can only be edited by roles outranking theirs.
+    pub async fn update_punishment(
+        &self,
+        staff_uuid: Uuid,
+        id: Uuid,
+        update: PunishmentUpdate,
+    ) -> AppResult<(PunishmentWithTemplate, PunishmentRevision)> {
+        let mut tx = self.pool.begin().await?;
+
+        let punishment = sqlx::query_as::<_, PunishmentWithTemplate>(
+            r#"
+            SELECT
+                p.*,
+                pc.name AS category_name
+            FROM punishments p
+            INNER JOIN punishment_categories pc ON p.category_id = pc.id
+            WHERE p.id = $1
+            FOR UPDATE OF p
+            "#
+        )
+        .bind(id)
+        .fetch_optional(&mut *tx)
+        .await?
+        .ok_or_else(|| AppError::NotFound("punishment not found".to_string()))?;
+
+        if punishment.staff_uuid != staff_uuid {
+            Self::ensure_outranks(&mut tx, staff_uuid, punishment.staff_uuid, "edit punishments its members issued").await?;
+        }
+
+        let changes = update.changes(&punishment)?;
+
+        sqlx::query(
+            r#"
+            UPDATE punishments
+            SET reason = $2,
+                evidence = $3,
+                note = $4,
+                expires_at = $5,
+                updated_at = NOW()
+            WHERE id = $1
+            "#
+        )
+        .bind(id)
+        .bind(update.reason.map(|reason| reason.trim().to_string()).unwrap_or(punishment.reason))
+        .bind(update.evidence.unwrap_or(punishment.evidence))
+        .bind(update.note.unwrap_or(punishment.note))
+        .bind(update.expires_at.or(punishment.expires_at))
+        .execute(&mut *tx)
+        .await?;
+
+        let revision = sqlx::query_as::<_, PunishmentRevision>(
+            r#"
+            INSERT INTO punishment_revisions (punishment_id, revision, edited_by, changes)
+            SELECT $1, COALESCE(MAX(revision), 0) + 1, $2, $3
+            FROM punishment_revisions
+            WHERE punishment_id = $1
+            RETURNING *
+            "#
+        )
+        .bind(id)
+        .bind(staff_uuid)
+        .bind(Json(&changes))
+        .fetch_one(&mut *tx)
+        .await?;
+
+        AuditService::record(&mut tx, NewAuditEvent {
+            actor_uuid: Some(staff_uuid),
+            action: "punishment.update".to_string(),
+            target_type: "punishment".to_string(),
+            target_id: Some(id.to_string()),
+            details: json!({ "revision": revision.revision, "changes": changes }),
+        })
+        .await?;
+
+        tx.commit().await?;
+
+        let punishment = self
+            .get_punishment(id)
+            .await?
+            .ok_or_else(|| AppError::NotFound("punishment not found".to_string()))?;
+
+        Ok((punishment, revision))
+    }
+
+    pub async fn list_revisions(&self, punishment_id: Uuid) -> AppResult<Vec<PunishmentRevision>> {
+        let revisions = sqlx::query_as::<_, PunishmentRevision>(
+            "SELECT * FROM punishment_revisions WHERE punishment_id = $1 ORDER BY revision"
+        )
+        .bind(punishment_id)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(revisions)
+    }
+
+    async fn load_issued(&self, id: Uuid, automatic_ban: Option<AutomaticBan>) -> AppResult<IssuedPunishment> {
+        let punishment = self
+            .get_punishment(id)
+            .await?
+            .ok_or_else(|| AppError::NotFound("punishment not found".to_string()))?;
+        let (automatic_ban, automatic_ban_approval) = match automatic_ban {
+            Some(AutomaticBan::Issued(id)) => (self.get_punishment(id).await?, None),
+            Some(AutomaticBan::Queued(approval)) => (None, Some(*approval)),
+            None => (None, None),
+        };
+
+        Ok(IssuedPunishment {
+            punishment,
+            automatic_ban,
+            automatic_ban_approval,
+        })
+    }
+
+    /// Issues a queued punishment in the name of the staff member who requested it. The ladder is
+    /// planned again, so the step reflects the player's offenses at the time of approval. Queued
+    /// automatic bans are issued as they were queued.
+    pub async fn approve_punishment(
+        &self,
+        reviewer_uuid: Uuid,
+        approval_id: Uuid,
+        comment: Option<String>,
+    ) -> AppResult<(PunishmentApproval, IssuedPunishment)> {
+        let mut tx = self.pool.begin().await?;
+        let approval = Self::find_pending_approval(&mut tx, reviewer_uuid, approval_id).await?;
+
+        // The player's history or the policy can have changed while the request was queued. The
+        // reviewer approved the step they were shown, a different one goes back to the queue.
+        sqlx::query("SELECT uuid FROM players WHERE uuid = $1 FOR UPDATE")
+            .bind(approval.player_uuid)
+            .execute(&mut *tx)
+            .await?;
+
+        let (id, automatic_ban) = if let (Some(offense_id), Some(threshold_points)) = (approval.offense_id, approval.threshold_points) {
+            // Automatic bans are issued as queued, unless the punishment that reached the threshold is gone
+            let revoked = sqlx::query_scalar::<_, bool>("SELECT revoked FROM punishments WHERE id = $1")
+                .bind(offense_id)
+                .fetch_one(&mut *tx)
+                .await?;
+            if revoked {
+                return Err(AppError::CustomValidationError(
+                    "The punishment that reached the severity threshold was revoked, reject the request instead".to_string(),
+                ));
+            }
+
+            let id = Self::insert_automatic_ban(
+                &mut tx,
+                offense_id,
+                &approval.punishment_type,
+                approval.duration_minutes,
+                approval.reason.as_deref().unwrap_or_default(),
+                threshold_points,
+            )
+            .await?;
+
+            (id, None)
+        } else {
+            let step = Self::plan_escalation(&mut tx, approval.player_uuid, approval.category_id).await?.template;
+            if let Some(change) = approval.step_change(&step.punishment_type, step.duration_minutes) {
+                let updated = sqlx::query_as::<_, PunishmentApproval>(
+                    "UPDATE punishment_approvals SET punishment_type = $2, duration_minutes = $3 WHERE id = $1 RETURNING *"
+                )
+                .bind(approval_id)
+                .bind(&step.punishment_type)
+                .bind(step.duration_minutes)
+                .fetch_one(&mut *tx)
+                .await?;
+
+                AuditService::record(&mut tx, NewAuditEvent {
+                    actor_uuid: Some(reviewer_uuid),
+                    action: "punishment.request_update".to_string(),
+                    target_type: "punishment_approval".to_string(),
+                    target_id: Some(approval_id.to_string()),
+                    details: json!({ "before": approval, "after": updated }),
+                })
+                .await?;
+
+                tx.commit().await?;
+
+                return Err(AppError::CustomValidationError(format!(
+                    "{}, the request was updated and has to be reviewed again",
+                    change
+                )));
+            }
+
+            let punishment = NewPunishment {
+                player_uuid: approval.player_uuid,
+                category_id: approval.category_id,
+                reason: approval.reason.clone(),
+                evidence: approval.evidence.clone(),
+                note: approval.note.clone(),
+                scope: approval.scope.clone(),
+                server_group: approval.server_group.clone(),
+                ip_range: approval.ip_range,
+            };
+
+            match Self::insert_punishment(&mut tx, approval.requested_by, punishment, Review::Approved).await? {
+                Inserted::Issued { id, automatic_ban } => (id, automatic_ban),
+                Inserted::Queued(_) => return Err(AppError::InternalError("approved punishment was queued again".to_string())),
+            }
+        };
+
+        let approval = sqlx::query_as::<_, PunishmentApproval>(
+            r#"
+            UPDATE punishment_approvals
+            SET status = 'approved', reviewed_by = $2, reviewed_at = NOW(), review_comment = $3, punishment_id = $4
+            WHERE id = $1
+            RETURNING *
+            "#
+        )
+        .bind(approval_id)
+        .bind(reviewer_uuid)
+        .bind(comment)
+        .bind(id)
+        .fetch_one(&mut *tx)
+        .await?;
+
+        AuditService::record(&mut tx, NewAuditEvent {
+            actor_uuid: Some(reviewer_uuid),
+            action: "punishment.approve".to_string(),
+            target_type: "punishment_approval".to_string(),
+            target_id: Some(approval_id.to_string()),
+            details: json!({ "approval": approval }),
+        })
+        .await?;
+
+        tx.commit().await?;
+
+        let issued = self.load_issued(id, automatic_ban).await?;
+
+        Ok((approval, issued))
+    }
+
+    pub async fn reject_punishment(&self, reviewer_uuid: Uuid, approval_id: Uuid, comment: String) -> AppResult<PunishmentApproval> {
+        if comment.trim().is_empty() {
+            return Err(AppError::CustomValidationError("A rejection needs a comment".to_string()));
+        }
+
+        let mut tx = self.pool.begin().await?;
+        Self::find_pending_approval(&mut tx, reviewer_uuid, approval_id).await?;
+
+        let approval = sqlx::query_as::<_, PunishmentApproval>(
+            r#"
+            UPDATE punishment_approvals
+            SET status = 'rejected', reviewed_by = $2, reviewed_at = NOW(), review_comment = $3
+            WHERE id = $1
+            RETURNING *
+            "#
+        )
+        .bind(approval_id)
+        .bind(reviewer_uuid)
+        .bind(comment.trim())
+        .fetch_one(&mut *tx)
+        .await?;
+
+        AuditService::record(&mut tx, NewAuditEvent {
+            actor_uuid: Some(reviewer_uuid),
+            action: "punishment.reject".to_string(),
+            target_type: "punishment_approval".to_string(),
+            target_id: Some(approval_id.to_string()),
+            details: json!({ "approval": approval }),
+        })
+        .await?;
+
+        tx.commit().await?;
+
+        Ok(approval)
+    }
+
+    async fn find_pending_approval(conn: &mut PgConnection, reviewer_uuid: Uuid, approval_id: Uuid) -> AppResult<PunishmentApproval> {
+        let approval = sqlx::query_as::<_, PunishmentApproval>("SELECT * FROM punishment_approvals WHERE id = $1 FOR UPDATE")
+            .bind(approval_id)
+            .fetch_optional(&mut *conn)
+            .await?
+            .ok_or_else(|| AppError::NotFound(format!("Approval request {} does not exist", approval_id)))?;
+
+        if approval.status != "pending" {
+            return Err(AppError::CustomValidationError(format!(
+                "Approval request {} was already {}",
+                approval_id, approval.status
+            )));
+        }
+
+        // Two different staff members is the point of the queue
+        if approval.requested_by == reviewer_uuid {
+            return Err(AppError::PermissionDenied("Staff members cannot review their own requests".to_string()));
+        }
+
+        Ok(approval)
+    }
+
+    /// Lists the approval requests with the given status, oldest first.
+    pub async fn list_approvals(&self, status: &str) -> AppResult<Vec<PunishmentApproval>> {
+        if !matches!(status, "pending" | "approved" | "rejected") {
+            return Err(AppError::CustomValidationError(format!("Unknown approval status: {}", status)));
+        }
+
+        let approvals = sqlx::query_as::<_, PunishmentApproval>(
+            "SELECT * FROM punishment_approvals WHERE status = $1 ORDER BY created_at"
+        )
+        .bind(status)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(approvals)
+    }
+
+    pub async fn get_latest_event_sequence(&self) -> AppResult<i64> {
+        let sequence = sqlx::query_scalar::<_, i64>(
+            "SELECT COALESCE(MAX(sequence), 0) FROM punishment_events"
+        )
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok(sequence)
+    }
+
+    pub async fn get_events_after(&self, sequence: i64) -> AppResult<Vec<PunishmentEvent>> {
+        let events = sqlx::query_as::<_, PunishmentEvent>(
+            r#"
+            SELECT
+                e.sequence,
+                e.issued,
+                p.*,
+                pc.name AS category_name
+            FROM punishment_events e
+            INNER JOIN punishments p ON e.punishment_id = p.id
+            INNER JOIN punishment_categories pc ON p.category_id = pc.id
+            WHERE e.sequence > $1
+            ORDER BY e.sequence
+            "#
+        )
+        .bind(sequence)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(events)
+    }
+
+    /// Returns the latest event of every punishment of the player changed after `resume_from`.
+    pub async fn get_missed_events(&self, player_uuid: Uuid, resume_from: i64) -> AppResult<Vec<PunishmentEvent>> {
+        let events = sqlx::query_as::<_, PunishmentEvent>(
+            r#"
+            SELECT * FROM (
+                SELECT DISTINCT ON (e.punishment_id)
+                    e.sequence,
+                    e.issued,
+                    p.*,
+                    pc.name AS category_name
+                FROM punishment_events e
+                INNER JOIN punishments p ON e.punishment_id = p.id
+                INNER JOIN punishment_categories pc ON p.category_id = pc.id
+                WHERE e.player_uuid = $1
+                  AND e.sequence > $2
+                ORDER BY e.punishment_id, e.sequence DESC
+            ) latest
+            ORDER BY latest.sequence
+            "#
+        )
+        .bind(player_uuid)
+        .bind(resume_from)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(events)
+    }
+}
@@ -1,8 +1,61 @@
+use crate::error::{AppError, AppResult};
+use crate::models::{NewReport, Report, ReportCounts};
+use sqlx::PgPool;
+use uuid::Uuid;
+
 pub struct ReportService {
+    pool: PgPool,
 }
 
 impl ReportService {
-    pub fn new() -> Self {
-        Self { }
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
     }
-}
\ No newline at end of file
+
+    pub async fn create_report(&self, report: NewReport) -> AppResult<Report> {
+        report.validate()?;
+
+        let players = sqlx::query_scalar::<_, i64>("SELECT COUNT(*) FROM players WHERE uuid IN ($1, $2)")
+            .bind(report.reporter_uuid)
+            .bind(report.reported_uuid)
+            .fetch_one(&self.pool)
+            .await?;
+
+        if players != 2 {
+            return Err(AppError::NotFound("player not found".to_string()));
+        }
+
+        let report = sqlx::query_as::<_, Report>(
+            r#"
+            INSERT INTO reports (reporter_uuid, reported_uuid, reason, server_name)
+            VALUES ($1, $2, $3, $4)
+            RETURNING *
+            "#
+        )
+        .bind(report.reporter_uuid)
+        .bind(report.reported_uuid)
+        .bind(report.reason.trim())
+        .bind(&report.server_name)
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok(report)
+    }
+
+    pub async fn get_report_counts(&self, player_uuid: Uuid) -> AppResult<ReportCounts> {
+        let counts = sqlx::query_as::<_, ReportCounts>(
+            r#"
+            SELECT
+                COUNT(*) FILTER (WHERE reported_uuid = $1) AS received,
+                COUNT(*) FILTER (WHERE reporter_uuid = $1) AS filed
+            FROM reports
+            WHERE reported_uuid = $1 OR reporter_uuid = $1
+            "#
+        )
+        .bind(player_uuid)
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok(counts)
+    }
+}
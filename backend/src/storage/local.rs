@@ -0,0 +1,84 @@
+use crate::error::{AppError, AppResult};
+use crate::storage::BlobStore;
+use std::path::PathBuf;
+use tokio::fs;
+use uuid::Uuid;
+
+/// Keeps blobs as files below a directory, fanned out by the first two characters of the key.
+pub struct LocalBlobStore {
+    root: PathBuf,
+}
+
+impl LocalBlobStore {
+    pub fn new(root: impl Into<PathBuf>) -> Self {
+        Self {
+            root: root.into()
+        }
+    }
+
+    fn path(&self, key: &str) -> AppResult<PathBuf> {
+        // Keys are hex digests, anything else could escape the directory
+        if key.len() < 2 || !key.chars().all(|c| c.is_ascii_hexdigit()) {
+            return Err(AppError::InternalError(format!("Invalid blob key: {}", key)));
+        }
+
+        Ok(self.root.join(&key[..2]).join(key))
+    }
+}
+
+#[tonic::async_trait]
+impl BlobStore for LocalBlobStore {
+    async fn put(&self, key: &str, data: Vec<u8>) -> AppResult<()> {
+        let path = self.path(key)?;
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent).await?;
+        }
+
+        // Renaming a finished file into place keeps readers from seeing a partial blob
+        let partial = path.with_extension(format!("{}.partial", Uuid::new_v4()));
+        fs::write(&partial, data).await?;
+        fs::rename(&partial, &path).await?;
+
+        Ok(())
+    }
+
+    async fn get(&self, key: &str) -> AppResult<Vec<u8>> {
+        Ok(fs::read(self.path(key)?).await?)
+    }
+
+    async fn exists(&self, key: &str) -> AppResult<bool> {
+        Ok(fs::try_exists(self.path(key)?).await?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // ── LocalBlobStore ───────────────────────────────────────────────────────
+
+    #[test]
+    fn fans_blobs_out_by_key_prefix() {
+        let store = LocalBlobStore::new("/srv/evidence");
+        assert_eq!(store.path("b94d27b9").unwrap(), PathBuf::from("/srv/evidence/b9/b94d27b9"));
+    }
+
+    #[test]
+    fn rejects_keys_that_are_not_digests() {
+        let store = LocalBlobStore::new("/srv/evidence");
+        assert!(store.path("../../etc/passwd").is_err());
+        assert!(store.path("a").is_err());
+    }
+
+    #[tokio::test]
+    async fn round_trips_blobs() {
+        let store = LocalBlobStore::new(std::env::temp_dir().join(format!("evidence-{}", Uuid::new_v4())));
+
+        assert!(!store.exists("b94d27b9").await.unwrap());
+        store.put("b94d27b9", b"hello world".to_vec()).await.unwrap();
+        assert!(store.exists("b94d27b9").await.unwrap());
+        assert_eq!(store.get("b94d27b9").await.unwrap(), b"hello world");
+
+        fs::remove_dir_all(&store.root).await.unwrap();
+    }
+}
@@ -0,0 +1,30 @@
+mod local;
+#[cfg(feature = "s3")]
+mod s3;
+
+use crate::config::var_or;
+use crate::error::{AppError, AppResult};
+use std::sync::Arc;
+
+pub use local::LocalBlobStore;
+#[cfg(feature = "s3")]
+pub use s3::S3BlobStore;
+
+/// Where evidence contents live. Keys are SHA-256 digests in hex, so a blob never changes once written
+/// and identical uploads share one blob.
+#[tonic::async_trait]
+pub trait BlobStore: Send + Sync {
+    async fn put(&self, key: &str, data: Vec<u8>) -> AppResult<()>;
+    async fn get(&self, key: &str) -> AppResult<Vec<u8>>;
+    async fn exists(&self, key: &str) -> AppResult<bool>;
+}
+
+/// Picks the store named by EVIDENCE_STORE, "local" (default) or "s3" when built with the s3 feature.
+pub fn blob_store_from_env() -> AppResult<Arc<dyn BlobStore>> {
+    match var_or("EVIDENCE_STORE", "local".to_string()).as_str() {
+        "local" => Ok(Arc::new(LocalBlobStore::new(var_or("EVIDENCE_DIRECTORY", "./evidence".to_string())))),
+        #[cfg(feature = "s3")]
+        "s3" => Ok(Arc::new(S3BlobStore::from_env()?)),
+        other => Err(AppError::InternalError(format!("Unsupported EVIDENCE_STORE: {}", other))),
+    }
+}
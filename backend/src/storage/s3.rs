@@ -0,0 +1,62 @@
+use crate::error::{AppError, AppResult};
+use crate::storage::BlobStore;
+use dotenvy::var;
+use object_store::aws::{AmazonS3, AmazonS3Builder};
+use object_store::path::Path;
+use object_store::{ObjectStore, PutPayload};
+
+/// Keeps blobs in an S3-compatible bucket. Credentials, region and endpoint come from the usual
+/// AWS_* variables, the bucket from EVIDENCE_S3_BUCKET.
+pub struct S3BlobStore {
+    store: AmazonS3,
+}
+
+impl S3BlobStore {
+    pub fn from_env() -> AppResult<Self> {
+        let bucket = var("EVIDENCE_S3_BUCKET")
+            .map_err(|_| AppError::InternalError("EVIDENCE_S3_BUCKET is not set".to_string()))?;
+
+        let store = AmazonS3Builder::from_env()
+            .with_bucket_name(bucket)
+            .build()
+            .map_err(store_error)?;
+
+        Ok(Self {
+            store
+        })
+    }
+}
+
+fn store_error(error: object_store::Error) -> AppError {
+    match error {
+        object_store::Error::NotFound { path, .. } => AppError::NotFound(format!("blob {} not found", path)),
+        error => AppError::InternalError(format!("Evidence store error: {}", error)),
+    }
+}
+
+#[tonic::async_trait]
+impl BlobStore for S3BlobStore {
+    async fn put(&self, key: &str, data: Vec<u8>) -> AppResult<()> {
+        self.store
+            .put(&Path::from(key), PutPayload::from(data))
+            .await
+            .map_err(store_error)?;
+
+        Ok(())
+    }
+
+    async fn get(&self, key: &str) -> AppResult<Vec<u8>> {
+        let result = self.store.get(&Path::from(key)).await.map_err(store_error)?;
+        let bytes = result.bytes().await.map_err(store_error)?;
+
+        Ok(bytes.to_vec())
+    }
+
+    async fn exists(&self, key: &str) -> AppResult<bool> {
+        match self.store.head(&Path::from(key)).await {
+            Ok(_) => Ok(true),
+            Err(object_store::Error::NotFound { .. }) => Ok(false),
+            Err(error) => Err(store_error(error)),
+        }
+    }
+}
@@ -3,6 +3,8 @@ package dev.fishigames.sentinel.models;
 public class Config {
     private String backend = "172.17.0.1:50051";
     private String baseWebUrl = "http://localhost:3000";
+    // Sent as x-server-token, has to match SERVER_TOKEN of the backend
+    private String serverToken = "";
 
     public String getBackend() {
         return backend;
@@ -11,4 +13,8 @@ public class Config {
     public String getBaseWebUrl() {
         return baseWebUrl;
     }
+
+    public String getServerToken() {
+        return serverToken;
+    }
 }
\ No newline at end of file
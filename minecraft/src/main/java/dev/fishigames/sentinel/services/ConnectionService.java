@@ -1,6 +1,8 @@
 package dev.fishigames.sentinel.services;
 
 import io.grpc.ManagedChannel;
+import io.grpc.Metadata;
+import io.grpc.stub.MetadataUtils;
 import io.grpc.netty.shaded.io.grpc.netty.NettyChannelBuilder;
 import io.grpc.netty.shaded.io.netty.channel.nio.NioEventLoopGroup;
 import io.grpc.netty.shaded.io.netty.channel.socket.nio.NioSocketChannel;
@@ -10,6 +12,8 @@ import java.util.logging.Logger;
 
 public class ConnectionService {
     private static final Logger LOGGER = Logger.getLogger(ConnectionService.class.getName());
+    private static final Metadata.Key<String> SERVER_TOKEN_KEY =
+            Metadata.Key.of("x-server-token", Metadata.ASCII_STRING_MARSHALLER);
     private final ManagedChannel managedChannel;
 
     public ConnectionService(ConfigService configService) {
@@ -21,6 +25,13 @@ public class ConnectionService {
 
         LOGGER.info("[Sentinel] Backend host: " + backendHost + ", port: " + backendPort);
 
+        if (config.getServerToken().isEmpty()) {
+            LOGGER.warning("[Sentinel] No server token configured, the backend will reject this server");
+        }
+
+        var headers = new Metadata();
+        headers.put(SERVER_TOKEN_KEY, config.getServerToken());
+
         managedChannel = NettyChannelBuilder
                 .forAddress(new InetSocketAddress(backendHost, backendPort))
                 .eventLoopGroup(new NioEventLoopGroup())
@@ -31,6 +42,7 @@ public class ConnectionService {
                 .keepAliveWithoutCalls(true)
                 .maxInboundMessageSize(1024 * 1024) // 1MB
                 .enableRetry()
+                .intercept(MetadataUtils.newAttachHeadersInterceptor(headers))
                 .build();
 
         LOGGER.info("[Sentinel] Attempting to connect to Sentinel gRPC server at " + config.getBackend());
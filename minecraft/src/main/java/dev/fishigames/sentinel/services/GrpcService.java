@@ -11,6 +11,7 @@ import java.util.concurrent.ScheduledExecutorService;
 import java.util.concurrent.TimeUnit;
 import java.util.concurrent.atomic.AtomicBoolean;
 import java.util.concurrent.atomic.AtomicInteger;
+import java.util.concurrent.atomic.AtomicLong;
 import java.util.function.Consumer;
 import java.util.logging.Logger;
 
@@ -18,6 +19,8 @@ public class GrpcService {
     private static final Logger LOGGER = Logger.getLogger(GrpcService.class.getName());
     private static final long RECONNECT_INITIAL_DELAY_SECONDS = 5;
     private static final long RECONNECT_MAX_DELAY_SECONDS = 60;
+    // Well within the backend idle timeout, which closes streams that stop pinging
+    private static final long HEARTBEAT_INTERVAL_SECONDS = 20;
 
     private final PunishmentServiceGrpc.PunishmentServiceBlockingStub punishmentServiceBlockingStub;
     private final PunishmentServiceGrpc.PunishmentServiceStub punishmentServiceStub;
@@ -28,6 +31,7 @@ public class GrpcService {
     private final HashMap<UUID, Consumer<PunishmentOuterClass.GetLivePunishmentsResponse>> punishmentStreams = new HashMap<>();
     private final AtomicBoolean isReconnecting = new AtomicBoolean(false);
     private final AtomicInteger reconnectAttempts = new AtomicInteger(0);
+    private final AtomicLong heartbeatNonce = new AtomicLong(0);
 
     private final CacheService cacheService;
     private final ScheduledExecutorService reconnectScheduler = Executors.newSingleThreadScheduledExecutor(runnable -> {
@@ -47,6 +51,9 @@ public class GrpcService {
                 PunishmentServiceGrpc.newBlockingStub(connectionService.getManagedChannel());
         punishmentServiceStub = PunishmentServiceGrpc.newStub(connectionService.getManagedChannel());
 
+        reconnectScheduler.scheduleAtFixedRate(this::sendHeartbeat,
+                HEARTBEAT_INTERVAL_SECONDS, HEARTBEAT_INTERVAL_SECONDS, TimeUnit.SECONDS);
+
         LOGGER.info("[Sentinel] GRPC Service initialized and connected");
     }
 
@@ -86,14 +93,34 @@ public class GrpcService {
         }
     }
 
+    private void sendHeartbeat() {
+        try {
+            if (livePunishmentsStreamObserver != null && !isReconnecting.get()) {
+                livePunishmentsStreamObserver.onNext(PunishmentOuterClass.GetLivePunishmentsRequest.newBuilder()
+                        .setPing(PunishmentOuterClass.Ping.newBuilder()
+                                .setNonce(heartbeatNonce.incrementAndGet())
+                                .build())
+                        .build());
+            }
+        } catch (Exception exception) {
+            LOGGER.warning("[Sentinel] Error sending heartbeat: " + exception.getMessage());
+        }
+    }
+
     public void registerStreams() {
         isReconnecting.set(false);
         reconnectAttempts.set(0);
         livePunishmentsStreamObserver = punishmentServiceStub.getLivePunishments(new StreamObserver<>() {
             @Override
             public void onNext(PunishmentOuterClass.GetLivePunishmentsResponse response) {
+                if (response.hasPong()) {
+                    return;
+                }
+
                 punishmentStreams.forEach((id, consumer) -> consumer.accept(response));
-                cacheService.addPunishment(UUID.fromString(response.getPunishments().getPlayerId()), response);
+                if (response.hasPunishments()) {
+                    cacheService.addPunishment(UUID.fromString(response.getPunishments().getPlayerId()), response);
+                }
             }
 
             @Override